@@ -1,5 +1,8 @@
 use std::num::{NonZeroU32};
 use clap::{App, Arg};
+use segemehl_21_core::statistics::calculation::binned::BinConfig;
+use segemehl_21_core::statistics::shared::assembler_mode::AssemblerMode;
+use segemehl_21_core::statistics::shared::rebinning::Rebinning;
 
 #[derive(Debug, Clone)]
 pub struct CommandLineParameters {
@@ -7,8 +10,20 @@ pub struct CommandLineParameters {
     pub bai_path: Option<String>,
     pub output_path: String,
     pub expected_record_count: Option<usize>,
-    pub bin_size: Option<NonZeroU32>,
-    pub info_dump: bool
+    pub bin_config: BinConfig,
+    pub zoom_levels: Vec<BinConfig>,
+    pub info_dump: bool,
+    pub weight_multimappers: bool,
+    pub assembler_mode: AssemblerMode,
+    pub rebinning: Rebinning,
+    pub circular_rna_path: Option<String>,
+    pub fusion_path: Option<String>,
+    pub split_read_path: Option<String>,
+    pub quarantine_path: Option<String>,
+    pub coordinate_sorted: bool,
+    pub skip_mate_pairs: bool,
+    pub unmapped_fastq_path: Option<String>,
+    pub json_log: bool
 }
 
 impl CommandLineParameters {
@@ -59,6 +74,25 @@ impl CommandLineParameters {
                     .takes_value(true)
                     .required(false)
                     .validator(non_zero_number_validator)
+                    .conflicts_with("bin_count")
+            )
+            .arg(
+                Arg::with_name("bin_count")
+                    .long("bin_count")
+                    .value_name("BIN_COUNT")
+                    .help("Divide every Reference into this Number of Bins instead of Bins with a fixed Size")
+                    .takes_value(true)
+                    .required(false)
+                    .validator(non_zero_number_validator)
+            )
+            .arg(
+                Arg::with_name("zoom_levels")
+                    .long("zoom_levels")
+                    .value_name("ZOOM_LEVELS")
+                    .help("Comma separated Bin Sizes the References are additionally binned with, e.g. 1000,100000, so the Bin Plots can be zoomed")
+                    .takes_value(true)
+                    .required(false)
+                    .validator(zoom_levels_validator)
             )
             .arg(
                 Arg::with_name("info_dump")
@@ -68,6 +102,107 @@ impl CommandLineParameters {
                     .takes_value(false)
                     .required(false)
             )
+            .arg(
+                Arg::with_name("weight_multimappers")
+                    .long("weight-multimappers")
+                    .value_name("WEIGHT_MULTIMAPPERS")
+                    .help("Weight each Alignment with 1/NH when calculating the Coverage per Bin")
+                    .takes_value(false)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("assembler_mode")
+                    .long("assembler_mode")
+                    .value_name("ASSEMBLER_MODE")
+                    .help("How Split Reads are assembled, either from Flags or from the segemehl Split Tags")
+                    .takes_value(true)
+                    .required(false)
+                    .possible_values(&["flags", "segemehl_tags"])
+            )
+            .arg(
+                Arg::with_name("rebin_width")
+                    .long("rebin_width")
+                    .value_name("REBIN_WIDTH")
+                    .help("Store Gap and Read Lengths in Bins of this Width, every Length changes by at most half the Width")
+                    .takes_value(true)
+                    .required(false)
+                    .validator(non_zero_number_validator)
+                    .conflicts_with("rebin_relative_error")
+            )
+            .arg(
+                Arg::with_name("rebin_relative_error")
+                    .long("rebin_relative_error")
+                    .value_name("REBIN_RELATIVE_ERROR")
+                    .help("Store Gap and Read Lengths in logarithmic Bins, every Length changes by at most this Fraction of itself")
+                    .takes_value(true)
+                    .required(false)
+                    .validator(relative_error_validator)
+            )
+            .arg(
+                Arg::with_name("circular_rna_path")
+                    .long("circ_rna_candidates")
+                    .value_name("CIRC_RNA_PATH")
+                    .help("Export the Circular RNA Candidates, as BED if the Path ends with .bed, otherwise as TSV")
+                    .takes_value(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("fusion_path")
+                    .long("fusion_candidates")
+                    .value_name("FUSION_PATH")
+                    .help("Export the Fusion and Trans-Splice Candidates as BEDPE")
+                    .takes_value(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("split_read_path")
+                    .long("split_reads")
+                    .value_name("SPLIT_READ_PATH")
+                    .help("Export every assembled Split Read, as BEDPE if the Path ends with .bedpe, otherwise as BED12")
+                    .takes_value(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("quarantine_path")
+                    .long("quarantine")
+                    .value_name("QUARANTINE_PATH")
+                    .help("Write the Split Read Fragments that could not be assembled as JSON, together with the Reason")
+                    .takes_value(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("coordinate_sorted")
+                    .long("coordinate_sorted")
+                    .value_name("COORDINATE_SORTED")
+                    .help("Calculate the References one after another to save Memory, requires coordinate sorted Input")
+                    .takes_value(false)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("skip_mate_pairs")
+                    .long("skip-mate-pairs")
+                    .value_name("SKIP_MATE_PAIRS")
+                    .help("Leave ordinary paired-end Mates out of the Split Read Assembly, they are only counted")
+                    .takes_value(false)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("unmapped_fastq_path")
+                    .long("unmapped-fastq")
+                    .value_name("UNMAPPED_FASTQ_PATH")
+                    .help("Write the primary unmapped Reads as FASTQ")
+                    .takes_value(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::with_name("log_format")
+                    .long("log_format")
+                    .value_name("LOG_FORMAT")
+                    .help("How Warnings and Progress Events are reported, either on the Console or as JSON Lines on stderr")
+                    .takes_value(true)
+                    .required(false)
+                    .possible_values(&["console", "json"])
+            )
             .get_matches();
 
         let bam_path = matches.value_of("bam_path").map(|item| String::from(item))
@@ -77,19 +212,54 @@ impl CommandLineParameters {
             .unwrap_or("output.stat".to_string());
         let expected_record_count = matches.value_of("expected_record_count")
             .map(|item| item.trim().parse::<usize>().unwrap());
-        let bin_size = matches.value_of("bin_size")
-                .map(|item| item.parse::<u32>().unwrap())
-                .map(|item| NonZeroU32::new(item).unwrap());
+        let bin_config = match (matches.value_of("bin_size"), matches.value_of("bin_count")) {
+            (Some(bin_size), _) => BinConfig::LengthOfBins(NonZeroU32::new(bin_size.trim().parse::<u32>().unwrap()).unwrap()),
+            (_, Some(bin_count)) => BinConfig::NumberOfBins(NonZeroU32::new(bin_count.trim().parse::<u32>().unwrap()).unwrap()),
+            _ => BinConfig::LengthOfBins(NonZeroU32::new(10000).unwrap())
+        };
+        let zoom_levels = matches.value_of("zoom_levels")
+            .map(|item| parse_zoom_levels(item).unwrap())
+            .unwrap_or_default();
 
         let info_dump = matches.is_present("info_dump");
+        let weight_multimappers = matches.is_present("weight_multimappers");
+        let assembler_mode = match matches.value_of("assembler_mode") {
+            Some("segemehl_tags") => AssemblerMode::SegemehlTags,
+            _ => AssemblerMode::Flags
+        };
+        let rebinning = match (matches.value_of("rebin_width"), matches.value_of("rebin_relative_error")) {
+            (Some(width), _) => Rebinning::FixedWidth(NonZeroU32::new(width.trim().parse::<u32>().unwrap()).unwrap()),
+            (_, Some(relative_error)) => Rebinning::logarithmic(relative_error.trim().parse::<f64>().unwrap()).unwrap(),
+            _ => Rebinning::Exact
+        };
+        let circular_rna_path = matches.value_of("circular_rna_path").map(String::from);
+        let fusion_path = matches.value_of("fusion_path").map(String::from);
+        let split_read_path = matches.value_of("split_read_path").map(String::from);
+        let quarantine_path = matches.value_of("quarantine_path").map(String::from);
+        let coordinate_sorted = matches.is_present("coordinate_sorted");
+        let skip_mate_pairs = matches.is_present("skip_mate_pairs");
+        let unmapped_fastq_path = matches.value_of("unmapped_fastq_path").map(String::from);
+        let json_log = matches.value_of("log_format") == Some("json");
 
         CommandLineParameters {
             bam_path,
             bai_path,
             output_path,
             expected_record_count,
-            bin_size,
-            info_dump
+            bin_config,
+            zoom_levels,
+            info_dump,
+            weight_multimappers,
+            assembler_mode,
+            rebinning,
+            circular_rna_path,
+            fusion_path,
+            split_read_path,
+            quarantine_path,
+            coordinate_sorted,
+            skip_mate_pairs,
+            unmapped_fastq_path,
+            json_log
         }
     }
 }
@@ -107,4 +277,30 @@ fn non_zero_number_validator(value: String) -> Result<(), String> {
     NonZeroU32::new(number)
         .map(|_| ())
         .ok_or("Number was Zero".to_string())
+}
+
+fn parse_zoom_levels(value: &str) -> Result<Vec<BinConfig>, String> {
+    value.split(',')
+        .map(|bin_size| {
+            let bin_size = bin_size.trim().parse::<u32>()
+                .map_err(|err| format!("{}", err))?;
+
+            NonZeroU32::new(bin_size)
+                .map(BinConfig::LengthOfBins)
+                .ok_or("Bin Size was Zero".to_string())
+        })
+        .collect()
+}
+
+fn zoom_levels_validator(value: String) -> Result<(), String> {
+    parse_zoom_levels(&value).map(|_| ())
+}
+
+fn relative_error_validator(value: String) -> Result<(), String> {
+    let relative_error = value.trim().parse::<f64>()
+        .map_err(|err| format!("{}", err))?;
+
+    Rebinning::logarithmic(relative_error)
+        .map(|_| ())
+        .ok_or("Relative Error has to be between 0 and 1".to_string())
 }
\ No newline at end of file
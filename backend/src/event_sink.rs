@@ -0,0 +1,31 @@
+use console::style;
+use segemehl_21_core::event::{Event, EventLevel, EventSink};
+
+/// Prints the events for a human, warnings stand out in yellow.
+#[derive(Debug, Default)]
+pub struct ConsoleEventSink;
+
+impl EventSink for ConsoleEventSink {
+    fn handle(&self, event: Event) {
+        match event.get_level() {
+            EventLevel::Info => println!("      {}", style(&event).dim()),
+            EventLevel::Warning => println!("{} {}", style("WARN:").yellow().bold(), event)
+        }
+    }
+}
+
+/// Writes one JSON object per event to stderr, so stdout stays free for the data.
+#[derive(Debug, Default)]
+pub struct JsonEventSink;
+
+impl EventSink for JsonEventSink {
+    fn handle(&self, event: Event) {
+        let line = serde_json::json!({
+            "level": event.get_level(),
+            "message": event.to_string(),
+            "event": event
+        });
+
+        eprintln!("{}", line);
+    }
+}
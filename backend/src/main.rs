@@ -1,30 +1,38 @@
 use std::{
     convert::TryFrom,
-    fs::File,
-    num::NonZeroU32
+    fs::File
 };
 use std::convert::TryInto;
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use bam::BamReader;
 use console::style;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 use anyhow::{Context};
 
 use segemehl_21_core::{
+    event::EventSink,
     statistics::calculation::CalculationData,
     statistics::presentation::PresentationData,
-    util::get_record_length_on_reference
+    statistics::presentation::insert_size::InsertSizeStatistics,
+    statistics::presentation::split_read::class::SplitReadClass,
+    statistics::presentation::split_read::quarantine::QuarantineReason,
+    util::{get_record_length_on_reference, write_record_as_fastq}
 };
 pub use segemehl_21_core::header::Header;
 
 use crate::command_line::CommandLineParameters;
-use crate::reader::{get_parallel_reader};
+use crate::event_sink::{ConsoleEventSink, JsonEventSink};
+use crate::progress::SpinnerProgressObserver;
+use crate::reader::{get_parallel_reader, get_sequential_reader};
 
 mod old_formatting;
 mod util;
 mod command_line;
+mod event_sink;
+mod progress;
 mod reader;
 
 fn main() -> anyhow::Result<()> {
@@ -33,8 +41,9 @@ fn main() -> anyhow::Result<()> {
     let bam_path = params.bam_path.as_str();
     let bai_path = params.bai_path;
     let _expected_record_count = params.expected_record_count;
-    let bin_size = params.bin_size.unwrap_or(NonZeroU32::new(10000).unwrap());
     let info_dump = params.info_dump;
+    let weight_multimappers = params.weight_multimappers;
+    let assembler_mode = params.assembler_mode;
 
     println!();
 
@@ -51,9 +60,6 @@ fn main() -> anyhow::Result<()> {
         )
     }
 
-    let reader = get_parallel_reader(bam_path, bai_path)
-        .context("could not create record readers")?;
-
     let header_reader = BamReader::from_path(bam_path,0u16)
         .with_context(|| format!("could not create bam header reader at: {}", bam_path))?;
 
@@ -65,23 +71,107 @@ fn main() -> anyhow::Result<()> {
         style("[2/4]").bold().dim()
     );
 
-    let calculation_data = CalculationData::new(&header, bin_size)
-        .context("could not create calculation data")?;
+    let event_sink: Arc<dyn EventSink> = if params.json_log {
+        Arc::new(JsonEventSink)
+    }
+    else {
+        Arc::new(ConsoleEventSink)
+    };
+
+    let progress_observer = if params.json_log {
+        Arc::new(SpinnerProgressObserver::hidden())
+    }
+    else {
+        Arc::new(SpinnerProgressObserver::new())
+    };
+
+    let is_grouped_by_name = header.metadata.as_ref()
+        .map(|metadata| metadata.is_grouped_by_name())
+        .unwrap_or(false);
+
+    let mut calculation_data = CalculationData::new(&header, params.bin_config, weight_multimappers, assembler_mode, params.coordinate_sorted)
+        .context("could not create calculation data")?
+        .with_zoom_levels(params.zoom_levels)
+        .with_rebinning(params.rebinning)
+        .with_split_read_export(params.split_read_path.is_some())
+        .with_quarantine_export(params.quarantine_path.is_some())
+        .with_mate_pair_skipping(params.skip_mate_pairs)
+        .with_event_sink(event_sink)
+        .with_progress_observer(progress_observer.clone());
+
+    // Only the sequential reader below keeps the file order the streaming assembler relies on.
+    if is_grouped_by_name {
+        calculation_data = calculation_data.with_streaming_assembly();
+    }
 
     let total_record_stats: (AtomicUsize, AtomicUsize) = (AtomicUsize::new(0), AtomicUsize::new(0));
 
-    reader.into_par_iter().try_for_each(|record| -> anyhow::Result<()> {
+    let unmapped_fastq_writer = match params.unmapped_fastq_path.as_ref() {
+        Some(unmapped_fastq_path) => Some(Mutex::new(BufWriter::new(File::create(unmapped_fastq_path).with_context(|| {
+            format!("could not create unmapped fastq file at {}", unmapped_fastq_path)
+        })?))),
+        None => None
+    };
+
+    let read_record = |record: Result<bam::Record, std::io::Error>| -> anyhow::Result<bam::Record> {
         let record = record.context("Error decompressing Read")?;
         total_record_stats.0.fetch_add(1, Ordering::Relaxed);
 
         let _ = total_record_stats.1.fetch_add(get_record_length_on_reference(&record) as usize, Ordering::Relaxed);
 
-        calculation_data.add_record(record).context("error adding record")?;
-        Ok(())
-    })?;
+        if let Some(writer) = unmapped_fastq_writer.as_ref() {
+            let flags = record.flag();
+
+            if !flags.is_mapped() && !flags.is_secondary() && !flags.is_supplementary() {
+                write_record_as_fastq(&record, &mut *writer.lock().unwrap())
+                    .context("could not write unmapped read")?;
+            }
+        }
+
+        Ok(record)
+    };
+
+    let add_record = |record: Result<bam::Record, std::io::Error>| -> anyhow::Result<()> {
+        calculation_data.add_record(read_record(record)?).context("error adding record")
+    };
+
+    if calculation_data.requires_ordered_input() {
+        println!("Reading Records in File Order");
+
+        get_sequential_reader(bam_path)
+            .context("could not create record reader")?
+            .try_for_each(add_record)?;
+    }
+    else if calculation_data.is_assembling_while_reading() {
+        println!("Assembling Split Reads in File Order");
+
+        // The bridge pulls the records one after another, so only the assembler sees them in file order.
+        get_sequential_reader(bam_path)
+            .context("could not create record reader")?
+            .map(|record| -> anyhow::Result<bam::Record> {
+                let record = read_record(record)?;
+                calculation_data.add_ordered_record(&record).context("error adding record")?;
+                Ok(record)
+            })
+            .par_bridge()
+            .try_for_each(|record| -> anyhow::Result<()> {
+                calculation_data.add_unordered_record(record?).context("error adding record")
+            })?;
+    }
+    else {
+        get_parallel_reader(bam_path, bai_path)
+            .context("could not create record readers")?
+            .into_par_iter()
+            .try_for_each(add_record)?;
+    }
 
     let (record_count, total_record_length) = (total_record_stats.0.into_inner(), total_record_stats.1.into_inner());
 
+    if let Some(writer) = unmapped_fastq_writer {
+        writer.into_inner().unwrap().flush()
+            .context("could not write unmapped reads")?;
+    }
+
     println!(
         "{} Calculating Statistics...",
         style("[3/4]").bold().dim()
@@ -90,6 +180,8 @@ fn main() -> anyhow::Result<()> {
     let presentation_data: PresentationData = calculation_data.try_into()
         .context("could not convert calculation data to presentation data")?;
 
+    progress_observer.finish();
+
     println!();
     println!("Record Count: {}", record_count);
     println!("Total Record Length: {}", total_record_length);
@@ -99,18 +191,137 @@ fn main() -> anyhow::Result<()> {
         println!("Dumping Per Reference Statistics");
 
         for statistic in presentation_data.get_per_reference_data() {
+            let coverage_uniformity = statistic.get_coverage_uniformity();
+
             println!();
             println!("Reference Name: {}", statistic.get_reference_name());
             println!("Reference Length: {}", statistic.get_reference_length());
             println!("Records for Reference: {}", statistic.get_read_length_on_reference_map().get_frequency_sum());
             println!("Total Record Length for Reference: {}", statistic.get_read_length_on_reference_map().get_weighted_frequency_sum());
-            println!("Mean Read Length: {}", statistic.get_read_length_on_reference_map().get_mean_frequency().unwrap_or(0.0));
-            println!("Median Read Length: {}", statistic.get_read_length_on_reference_map().get_median_frequency().unwrap_or(0.0));
-            println!("Mode Read Length: {}", statistic.get_read_length_on_reference_map().get_max_frequency().unwrap_or((0, 0)).0);
+            println!("Aligned Bases for Reference: {}", statistic.get_aligned_base_count());
+            println!("Mean Depth: {}", statistic.get_mean_depth());
+            println!("Reads per Base: {}", statistic.get_read_density());
+            println!("Coverage Coefficient of Variation: {}", coverage_uniformity.coefficient_of_variation);
+            println!("Coverage Gini Coefficient: {}", coverage_uniformity.gini_coefficient);
+            println!("Fold-80 Base Penalty: {}", coverage_uniformity.fold_80_base_penalty.unwrap_or(f64::NAN));
+            println!("Bins within 0.2x-5x of the Median Coverage: {}", coverage_uniformity.near_median_fraction);
+            println!("Mean Read Length: {}", statistic.get_read_length_on_reference_map().get_mean().unwrap_or(0.0));
+            println!("Median Read Length: {}", statistic.get_read_length_on_reference_map().get_median().unwrap_or(0.0));
+            println!("Mode Read Length: {}", statistic.get_read_length_on_reference_map().get_mode().unwrap_or(0));
             println!("Smallest Read Length: {}", statistic.get_read_length_on_reference_map().get_min_entry().unwrap_or((0, 0)).0);
             println!("Biggest Read Length: {}", statistic.get_read_length_on_reference_map().get_max_entry().unwrap_or((0, 0)).0);
+            println!("Soft Clipped Bases: {}", statistic.get_cigar_operations().soft_clips);
+            println!("Hard Clipped Bases: {}", statistic.get_cigar_operations().hard_clips);
+            println!("Uniquely Mapped Fraction: {}", statistic.get_unique_mapping_fraction());
+            println!("Mean Hits per Read: {}", statistic.get_hit_count_map().get_mean().unwrap_or(0.0));
+            println!("Primary Alignments: {}", statistic.get_alignment_categories().primary);
+            println!("Secondary Alignments: {}", statistic.get_alignment_categories().secondary);
+            println!("Supplementary Alignments: {}", statistic.get_alignment_categories().supplementary);
+            println!("Duplicate Alignments: {}", statistic.get_alignment_categories().duplicates);
+            println!("QC-Fail Alignments: {}", statistic.get_alignment_categories().qc_failed);
+            println!("Mean Insert Size: {}", statistic.get_insert_size_statistics().mean);
+            println!("Median Insert Size: {}", statistic.get_insert_size_statistics().median);
+            println!("Insert Size MAD: {}", statistic.get_insert_size_statistics().median_absolute_deviation);
+            println!();
+        }
+
+        println!("Dumping Per Read Group Statistics");
+
+        for (read_group, insert_size_map) in presentation_data.get_insert_size_data().get_per_read_group_maps() {
+            let insert_size_statistics = InsertSizeStatistics::from(insert_size_map);
+
             println!();
+            println!("Read Group: {}", read_group);
+            println!("Properly Paired Templates: {}", insert_size_statistics.count);
+            println!("Mean Insert Size: {}", insert_size_statistics.mean);
+            println!("Median Insert Size: {}", insert_size_statistics.median);
+            println!("Insert Size MAD: {}", insert_size_statistics.median_absolute_deviation);
+            println!();
+        }
+
+        println!("Dumping Unmapped Read Statistics");
+        println!();
+
+        let unmapped_data = presentation_data.get_unmapped_data();
+        let base_composition = unmapped_data.get_base_composition();
+
+        println!("Unmapped Reads: {}", unmapped_data.get_read_length_map().get_frequency_sum());
+        println!("Base Composition (A/C/G/T/N): {}/{}/{}/{}/{}", base_composition.a, base_composition.c, base_composition.g, base_composition.t, base_composition.n);
+        println!("GC Content: {}", base_composition.get_gc_content());
+        println!("Mean Base Quality: {}", unmapped_data.get_mean_quality_map().get_mean().unwrap_or(0.0));
+        println!("Low Complexity Fraction: {}", unmapped_data.get_low_complexity_fraction());
+        println!("Mate Unmapped: {}", unmapped_data.get_split_read().get_mate_unmapped_count());
+
+        for (reference_name, count) in presentation_data.get_unmapped_mate_references() {
+            println!("Mate Mapped to {}: {}", reference_name, count);
+        }
+
+        println!();
+        println!("Dumping Split Read Classes");
+        println!();
+
+        for class in SplitReadClass::ALL.iter() {
+            println!("{}: {}", class, presentation_data.get_split_read_data().get_class_count(*class));
+        }
+
+        println!();
+        println!("Dumping Unmergeable Split Read Fragments");
+        println!();
+
+        for reason in QuarantineReason::ALL.iter() {
+            println!("{}: {}", reason, presentation_data.get_split_read_data().get_quarantine_count(*reason));
+        }
+
+        println!();
+        println!("Circular RNA Candidates: {}", presentation_data.get_circular_rna_candidates().get_candidate_count());
+        println!("Back-Splice Supporting Reads: {}", presentation_data.get_circular_rna_candidates().get_supporting_read_count());
+        println!("Fusion Candidates: {}", presentation_data.get_fusion_candidates().get_candidate_count());
+        println!("Cross-Reference Supporting Reads: {}", presentation_data.get_fusion_candidates().get_supporting_read_count());
+        println!();
+    }
+
+    if let Some(circular_rna_path) = params.circular_rna_path.as_ref() {
+        let writer = BufWriter::new(File::create(circular_rna_path).with_context(|| {
+            format!("could not create circular rna file at {}", circular_rna_path)
+        })?);
+
+        if circular_rna_path.ends_with(".bed") {
+            presentation_data.get_circular_rna_candidates().write_bed(writer)
+        }
+        else {
+            presentation_data.get_circular_rna_candidates().write_tsv(writer)
+        }.context("could not write circular rna candidates")?;
+    }
+
+    if let Some(fusion_path) = params.fusion_path.as_ref() {
+        let writer = BufWriter::new(File::create(fusion_path).with_context(|| {
+            format!("could not create fusion file at {}", fusion_path)
+        })?);
+
+        presentation_data.get_fusion_candidates().write_bedpe(writer)
+            .context("could not write fusion candidates")?;
+    }
+
+    if let Some(quarantine_path) = params.quarantine_path.as_ref() {
+        let writer = BufWriter::new(File::create(quarantine_path).with_context(|| {
+            format!("could not create quarantine file at {}", quarantine_path)
+        })?);
+
+        serde_json::to_writer(writer, presentation_data.get_split_read_quarantine())
+            .context("could not write quarantined split read fragments")?;
+    }
+
+    if let Some(split_read_path) = params.split_read_path.as_ref() {
+        let writer = BufWriter::new(File::create(split_read_path).with_context(|| {
+            format!("could not create split read file at {}", split_read_path)
+        })?);
+
+        if split_read_path.ends_with(".bedpe") {
+            presentation_data.get_split_read_export().write_bedpe(writer)
         }
+        else {
+            presentation_data.get_split_read_export().write_bed12(writer)
+        }.context("could not write split reads")?;
     }
 
     println!(
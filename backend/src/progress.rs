@@ -0,0 +1,55 @@
+use std::fmt::{Debug, Formatter};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use segemehl_21_core::event::Stage;
+use segemehl_21_core::progress::ProgressObserver;
+
+/// Shows the number of read records and the current stage on a spinner.
+pub struct SpinnerProgressObserver {
+    progress_bar: ProgressBar
+}
+
+impl SpinnerProgressObserver {
+    pub fn new() -> Self {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_style(ProgressStyle::default_spinner()
+            .template("      {spinner} [{elapsed_precise}] {msg}")
+            .tick_chars("/-\\|"));
+        progress_bar.enable_steady_tick(60/15);
+
+        Self {
+            progress_bar
+        }
+    }
+
+    /// Keeps stderr free for the JSON log.
+    pub fn hidden() -> Self {
+        Self {
+            progress_bar: ProgressBar::hidden()
+        }
+    }
+
+    pub fn finish(&self) {
+        self.progress_bar.finish_and_clear();
+    }
+}
+
+impl Debug for SpinnerProgressObserver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpinnerProgressObserver").finish()
+    }
+}
+
+impl ProgressObserver for SpinnerProgressObserver {
+    fn records_processed(&self, record_count: u64) {
+        self.progress_bar.set_message(format!("{} Records read", record_count));
+    }
+
+    fn stage_started(&self, stage: Stage) {
+        self.progress_bar.set_message(stage.to_string());
+    }
+
+    fn reference_finalized(&self, _ref_id: usize, reference_name: &str) {
+        self.progress_bar.set_message(format!("Finished Reference {}", reference_name));
+    }
+}
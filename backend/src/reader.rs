@@ -35,4 +35,12 @@ pub fn get_parallel_reader(bam_path: &str, bai_path: Option<String>) -> Result<B
             Ok(Box::from(reader.into_iter().par_bridge()))
         }
     }
-}
\ No newline at end of file
+}
+/// Yields the records in file order, for calculations that depend on it.
+pub fn get_sequential_reader(bam_path: &str) -> Result<impl Iterator<Item = Result<Record, std::io::Error>>, ReaderCreationError> {
+    let reader = BamReader::from_path(bam_path, 16u16).map_err(|err| ReaderCreationError::BamReader {
+        path: bam_path.to_string(),
+        source: err
+    })?;
+    Ok(reader.into_iter())
+}
@@ -0,0 +1,88 @@
+//! Compares the record throughput of a single worker with all workers, each of which counts into its own local data.
+//!
+//! Run with `cargo bench -p segemehl_21_core --bench add_record`.
+
+use std::convert::TryFrom;
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+use bam::header::HeaderEntry;
+use bam::Record;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use segemehl_21_core::header::Header;
+use segemehl_21_core::statistics::calculation::CalculationData;
+use segemehl_21_core::statistics::calculation::binned::BinConfig;
+use segemehl_21_core::statistics::presentation::PresentationData;
+use segemehl_21_core::statistics::shared::assembler_mode::AssemblerMode;
+
+const REFERENCE_LENGTH: u32 = 10_000_000;
+const RECORD_COUNT: u32 = 1_000_000;
+const RUNS: u32 = 3;
+
+/// Mostly single reads, with unmapped reads, insert size pairs and split reads mixed in so every map is exercised.
+fn create_records(header: &bam::Header) -> Vec<Record> {
+    let cigars = ["100M", "20S80M", "50M2I48M", "40M1000N60M", "30M3D70M"];
+
+    (0..RECORD_COUNT).map(|index| {
+        let position = (index as u64 * (REFERENCE_LENGTH - 10_000) as u64 / RECORD_COUNT as u64) as u32 + 1;
+        let sam = match index % 10 {
+            0 => format!("unmapped{}\t4\t*\t0\t0\t*\t*\t0\t0\t{}\t{}", index, "ACGTTGCA".repeat(12), "I".repeat(96)),
+            1 => format!("pair{}\t99\tchr1\t{}\t60\t100M\t=\t{}\t400\t*\t*\tNH:i:1", index, position, position + 300),
+            2 => format!("split{}\t65\tchr1\t{}\t60\t50M\tchr1\t{}\t0\t*\t*\tNH:i:1", index / 10, position, position + 5000),
+            3 => format!("split{}\t129\tchr1\t{}\t60\t50M\tchr1\t{}\t0\t*\t*\tNH:i:1", index / 10, position + 5000, position),
+            _ => format!(
+                "read{}\t{}\tchr1\t{}\t{}\t{}\t*\t0\t0\t*\t*\tNH:i:{}",
+                index,
+                if index % 2 == 0 { 0 } else { 16 },
+                position,
+                index % 60,
+                cigars[index as usize % cigars.len()],
+                index % 3 + 1
+            )
+        };
+
+        let mut record = Record::new();
+        record.fill_from_sam(&sam, header).unwrap();
+        record
+    }).collect()
+}
+
+fn run(header: &Header, records: &[Record], threads: usize) -> (Duration, u64) {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+
+    let calculation_data = CalculationData::new(header, BinConfig::LengthOfBins(NonZeroU32::new(1000).unwrap()), true, AssemblerMode::Flags, false)
+        .unwrap()
+        .with_metrics(Default::default());
+
+    let records = records.to_vec();
+    let start = Instant::now();
+
+    pool.install(|| records.into_iter().par_bridge().for_each(|record| calculation_data.add_record(record).unwrap()));
+
+    let presentation_data = PresentationData::try_from(calculation_data).unwrap();
+    let duration = start.elapsed();
+
+    (duration, presentation_data.get_read_length_on_reference_map().get_frequency_sum() + presentation_data.get_unmapped_data().get_read_length_map().get_frequency_sum())
+}
+
+fn main() {
+    let mut bam_header = bam::Header::new();
+    bam_header.push_entry(HeaderEntry::ref_sequence("chr1".to_string(), REFERENCE_LENGTH)).unwrap();
+
+    let header = Header::try_from(&bam_header).unwrap();
+    let records = create_records(&bam_header);
+
+    println!("{} records on {} threads", RECORD_COUNT, rayon::current_num_threads());
+
+    for (name, threads) in [("single worker", 1), ("all workers", rayon::current_num_threads())] {
+        let mut best = Duration::MAX;
+
+        for _ in 0..RUNS {
+            let (duration, record_count) = run(&header, &records, threads);
+            assert_eq!(record_count, RECORD_COUNT as u64);
+            best = best.min(duration);
+        }
+
+        println!("{:<26} {:>8.0} records/s", name, RECORD_COUNT as f64 / best.as_secs_f64());
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt::{Debug, Display, Formatter};
+use serde_derive::Serialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum EventLevel {
+	Info,
+	Warning
+}
+
+/// The steps of turning calculation data into presentation data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum Stage {
+	PerReferenceStatistics,
+	UnmappedStatistics,
+	SplitReadAssembly,
+	SplitReadStatistics
+}
+
+impl Display for Stage {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Stage::PerReferenceStatistics => "Calculating Per Reference Statistics",
+			Stage::UnmappedStatistics => "Calculating Unmapped Statistics",
+			Stage::SplitReadAssembly => "Assembling Split Reads",
+			Stage::SplitReadStatistics => "Calculating Split Read Statistics"
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+/// Something core wants to report, core itself never writes to stdout.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Event {
+	StageStarted {
+		stage: Stage
+	},
+	UnmergeableReadsDropped {
+		record_count: usize
+	}
+}
+
+impl Event {
+	pub fn get_level(&self) -> EventLevel {
+		match self {
+			Event::StageStarted { .. } => EventLevel::Info,
+			Event::UnmergeableReadsDropped { .. } => EventLevel::Warning
+		}
+	}
+}
+
+impl Display for Event {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Event::StageStarted { stage } => write!(f, "{}", stage),
+			Event::UnmergeableReadsDropped { record_count } => write!(f, "{} unmergeable reads dropped", record_count)
+		}
+	}
+}
+
+/// Receives the events of a calculation, the backend routes them to the console or a JSON log.
+pub trait EventSink: Debug + Send + Sync {
+	fn handle(&self, event: Event);
+}
+
+/// Drops every event, the default if no sink is set.
+#[derive(Debug, Default)]
+pub struct NullEventSink;
+
+impl EventSink for NullEventSink {
+	fn handle(&self, _event: Event) {}
+}
+
+#[cfg(test)]
+mod event_tests {
+	use crate::event::{Event, EventLevel, Stage};
+
+	#[test]
+	fn event_display_test() {
+		let stage_started = Event::StageStarted { stage: Stage::SplitReadStatistics };
+		let dropped = Event::UnmergeableReadsDropped { record_count: 3 };
+
+		assert_eq!(stage_started.to_string(), "Calculating Split Read Statistics");
+		assert_eq!(stage_started.get_level(), EventLevel::Info);
+		assert_eq!(dropped.to_string(), "3 unmergeable reads dropped");
+		assert_eq!(dropped.get_level(), EventLevel::Warning);
+	}
+}
@@ -118,6 +118,15 @@ impl Metadata {
 			sub_sorting_order
 		})
 	}
+
+	/// Whether all records sharing a QNAME are adjacent, either because the file is sorted or grouped by name.
+	pub fn is_grouped_by_name(&self) -> bool {
+		matches!(self.sorting_order, SortingOrder::QueryName) || matches!(self.grouping_order, GroupingOrder::Query)
+	}
+
+	pub fn is_coordinate_sorted(&self) -> bool {
+		matches!(self.sorting_order, SortingOrder::Coordinate)
+	}
 }
 
 #[derive(Error, Debug)]
@@ -22,6 +22,7 @@ pub enum AlternateLocusError {
 	}
 }
 
+#[derive(Debug, Clone)]
 pub struct AlternateLocus {
 	pub sequence: String,
 	pub start: u32,
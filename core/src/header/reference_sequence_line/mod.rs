@@ -64,6 +64,7 @@ pub enum ReferenceSequenceError {
 	}
 }
 
+#[derive(Debug, Clone)]
 pub struct ReferenceSequence {
 	pub name: String,
 	pub length: u32,
@@ -7,6 +7,7 @@ pub enum MoleculeTopologyError {
 	InvalidTopology
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum MoleculeTopology {
 	Linear,
 	Circular
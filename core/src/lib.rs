@@ -1,3 +1,5 @@
+pub mod event;
+pub mod progress;
 pub mod util;
 pub mod header;
 pub mod statistics;
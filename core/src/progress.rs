@@ -0,0 +1,63 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::event::Stage;
+
+/// Follows a calculation from reading the records to the finished presentation data.
+///
+/// Every method has an empty default, so an observer only implements what it displays.
+pub trait ProgressObserver: Debug + Send + Sync {
+	/// Called every [`RECORD_PROGRESS_INTERVAL`] records with the total number of records added so far.
+	fn records_processed(&self, _record_count: u64) {}
+
+	fn stage_started(&self, _stage: Stage) {}
+
+	/// Called once the statistics of a reference are complete.
+	fn reference_finalized(&self, _ref_id: usize, _reference_name: &str) {}
+}
+
+/// Reporting every single record would cost more than adding it.
+pub const RECORD_PROGRESS_INTERVAL: u64 = 10_000;
+
+#[derive(Debug, Default)]
+pub struct NullProgressObserver;
+
+impl ProgressObserver for NullProgressObserver {}
+
+/// Shared flag to abort a running calculation, clones refer to the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+	cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+}
+
+#[cfg(test)]
+mod cancellation_token_tests {
+	use crate::progress::CancellationToken;
+
+	#[test]
+	fn clones_share_the_flag_test() {
+		let token = CancellationToken::new();
+		let clone = token.clone();
+
+		assert!(!clone.is_cancelled());
+
+		token.cancel();
+
+		assert!(clone.is_cancelled());
+	}
+}
@@ -0,0 +1,472 @@
+use std::convert::TryFrom;
+use std::fmt::{Debug, Formatter};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use bam::Record;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use thiserror::Error;
+
+use crate::event::{EventSink, NullEventSink};
+use crate::header::Header;
+use crate::progress::{CancellationToken, NullProgressObserver, ProgressObserver};
+use crate::statistics::calculation::binned::BinConfig;
+use crate::statistics::calculation::{CalculationAddRecordError, CalculationData, CalculationDataNewError};
+use crate::statistics::calculation::custom::{MetricCollector, MetricCollectorRegistry};
+use crate::statistics::presentation::{PresentationData, PresentationDataError};
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::statistics::shared::metric_selection::MetricSelection;
+use crate::statistics::shared::rebinning::Rebinning;
+
+/// Records are only added to the statistics if every filter returns true.
+pub type RecordFilter = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+#[derive(Error, Debug)]
+pub enum StatisticsBuilderError {
+	#[error("could not create calculation data")]
+	CalculationData {
+		source: CalculationDataNewError
+	},
+	#[error("could not add record")]
+	AddRecord {
+		source: CalculationAddRecordError
+	},
+	#[error("could not convert calculation data to presentation data")]
+	Presentation {
+		source: PresentationDataError
+	},
+	#[error("coordinate sorted statistics need the records in file order, use build_sequential")]
+	OrderedInputRequired
+}
+
+/// Calculates the statistics of a record source, without wiring the calculation and presentation data by hand.
+///
+/// ```ignore
+/// let presentation_data = StatisticsBuilder::new()
+///     .with_bin_size(NonZeroU32::new(1000).unwrap())
+///     .with_metrics(MetricSelection::all().with_split_reads(false))
+///     .with_record_filter(|record| record.mapq() >= 10)
+///     .build(&header, records)?;
+/// ```
+pub struct StatisticsBuilder {
+	bin_config: BinConfig,
+	zoom_levels: Vec<BinConfig>,
+	weight_multimappers: bool,
+	assembler_mode: AssemblerMode,
+	coordinate_sorted: bool,
+	metrics: MetricSelection,
+	rebinning: Rebinning,
+	keep_split_reads: bool,
+	keep_quarantined_reads: bool,
+	skip_mate_pairs: bool,
+	record_filters: Vec<RecordFilter>,
+	metric_collectors: MetricCollectorRegistry,
+	event_sink: Arc<dyn EventSink>,
+	progress_observer: Arc<dyn ProgressObserver>,
+	cancellation_token: CancellationToken
+}
+
+impl StatisticsBuilder {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	pub fn with_bin_size(mut self, bin_size: NonZeroU32) -> Self {
+		self.bin_config = BinConfig::LengthOfBins(bin_size);
+		self
+	}
+
+	pub fn with_bin_config(mut self, bin_config: BinConfig) -> Self {
+		self.bin_config = bin_config;
+		self
+	}
+
+	/// Bins every reference additionally with each config, so the bins can be viewed at several resolutions.
+	pub fn with_zoom_levels(mut self, zoom_levels: Vec<BinConfig>) -> Self {
+		self.zoom_levels = zoom_levels;
+		self
+	}
+
+	pub fn with_weight_multimappers(mut self, weight_multimappers: bool) -> Self {
+		self.weight_multimappers = weight_multimappers;
+		self
+	}
+
+	pub fn with_assembler_mode(mut self, assembler_mode: AssemblerMode) -> Self {
+		self.assembler_mode = assembler_mode;
+		self
+	}
+
+	/// Calculates the references one after another, only possible with [`StatisticsBuilder::build_sequential`].
+	pub fn with_coordinate_sorted(mut self, coordinate_sorted: bool) -> Self {
+		self.coordinate_sorted = coordinate_sorted;
+		self
+	}
+
+	pub fn with_metrics(mut self, metrics: MetricSelection) -> Self {
+		self.metrics = metrics;
+		self
+	}
+
+	pub fn with_rebinning(mut self, rebinning: Rebinning) -> Self {
+		self.rebinning = rebinning;
+		self
+	}
+
+	pub fn with_split_read_export(mut self, keep_split_reads: bool) -> Self {
+		self.keep_split_reads = keep_split_reads;
+		self
+	}
+
+	pub fn with_quarantine_export(mut self, keep_quarantined_reads: bool) -> Self {
+		self.keep_quarantined_reads = keep_quarantined_reads;
+		self
+	}
+
+	/// Ordinary paired-end mates are only counted, see [`CalculationData::with_mate_pair_skipping`].
+	pub fn with_mate_pair_skipping(mut self, skip_mate_pairs: bool) -> Self {
+		self.skip_mate_pairs = skip_mate_pairs;
+		self
+	}
+
+	/// Can be called multiple times, a record has to pass every filter.
+	pub fn with_record_filter<F>(mut self, record_filter: F) -> Self where F: Fn(&Record) -> bool + Send + Sync + 'static {
+		self.record_filters.push(Box::new(record_filter));
+		self
+	}
+
+	/// Only receives the records that pass the filters.
+	pub fn with_metric_collector<C: MetricCollector>(mut self, collector: C) -> Self {
+		self.metric_collectors.register(collector);
+		self
+	}
+
+	pub fn with_event_sink(mut self, event_sink: Arc<dyn EventSink>) -> Self {
+		self.event_sink = event_sink;
+		self
+	}
+
+	pub fn with_progress_observer(mut self, progress_observer: Arc<dyn ProgressObserver>) -> Self {
+		self.progress_observer = progress_observer;
+		self
+	}
+
+	pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+		self.cancellation_token = cancellation_token;
+		self
+	}
+
+	/// Adds the records in parallel, name sorted input is therefore assembled from buffered fragments.
+	pub fn build<I>(mut self, header: &Header, records: I) -> Result<PresentationData, StatisticsBuilderError> where I: IntoParallelIterator<Item = Record> {
+		if self.coordinate_sorted {
+			return Err(StatisticsBuilderError::OrderedInputRequired);
+		}
+
+		let calculation_data = self.create_calculation_data(header)?;
+
+		records.into_par_iter()
+			.filter(|record| self.accepts(record))
+			.try_for_each(|record| calculation_data.add_record(record))
+			.map_err(|source| StatisticsBuilderError::AddRecord { source })?;
+
+		Self::into_presentation_data(calculation_data)
+	}
+
+	/// Adds the records in the order of the iterator, which allows coordinate sorted statistics
+	/// and streaming assembly if the header says the records are grouped by name.
+	pub fn build_sequential<I>(mut self, header: &Header, records: I) -> Result<PresentationData, StatisticsBuilderError> where I: IntoIterator<Item = Record> {
+		let is_grouped_by_name = header.metadata.as_ref()
+			.map(|metadata| metadata.is_grouped_by_name())
+			.unwrap_or(false);
+		let mut calculation_data = self.create_calculation_data(header)?;
+
+		if is_grouped_by_name {
+			calculation_data = calculation_data.with_streaming_assembly();
+		}
+
+		records.into_iter()
+			.filter(|record| self.accepts(record))
+			.try_for_each(|record| calculation_data.add_record(record))
+			.map_err(|source| StatisticsBuilderError::AddRecord { source })?;
+
+		Self::into_presentation_data(calculation_data)
+	}
+
+	fn accepts(&self, record: &Record) -> bool {
+		self.record_filters.iter().all(|record_filter| record_filter(record))
+	}
+
+	fn create_calculation_data(&mut self, header: &Header) -> Result<CalculationData, StatisticsBuilderError> {
+		let calculation_data = CalculationData::new(header, self.bin_config, self.weight_multimappers, self.assembler_mode, self.coordinate_sorted)
+			.map_err(|source| StatisticsBuilderError::CalculationData { source })?
+			.with_zoom_levels(std::mem::take(&mut self.zoom_levels))
+			.with_metrics(self.metrics)
+			.with_rebinning(self.rebinning)
+			.with_metric_collectors(std::mem::take(&mut self.metric_collectors))
+			.with_split_read_export(self.keep_split_reads)
+			.with_quarantine_export(self.keep_quarantined_reads)
+			.with_mate_pair_skipping(self.skip_mate_pairs)
+			.with_event_sink(self.event_sink.clone())
+			.with_progress_observer(self.progress_observer.clone())
+			.with_cancellation_token(self.cancellation_token.clone());
+
+		Ok(calculation_data)
+	}
+
+	fn into_presentation_data(calculation_data: CalculationData) -> Result<PresentationData, StatisticsBuilderError> {
+		PresentationData::try_from(calculation_data)
+			.map_err(|source| StatisticsBuilderError::Presentation { source })
+	}
+}
+
+/// The same defaults as the backend.
+impl Default for StatisticsBuilder {
+	fn default() -> Self {
+		Self {
+			bin_config: BinConfig::LengthOfBins(NonZeroU32::new(10000).unwrap()),
+			zoom_levels: Vec::new(),
+			weight_multimappers: false,
+			assembler_mode: AssemblerMode::default(),
+			coordinate_sorted: false,
+			metrics: MetricSelection::all(),
+			rebinning: Rebinning::Exact,
+			keep_split_reads: false,
+			keep_quarantined_reads: false,
+			skip_mate_pairs: false,
+			record_filters: Vec::new(),
+			metric_collectors: MetricCollectorRegistry::new(),
+			event_sink: Arc::new(NullEventSink),
+			progress_observer: Arc::new(NullProgressObserver),
+			cancellation_token: CancellationToken::new()
+		}
+	}
+}
+
+impl Debug for StatisticsBuilder {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("StatisticsBuilder")
+			.field("bin_config", &self.bin_config)
+			.field("zoom_levels", &self.zoom_levels)
+			.field("weight_multimappers", &self.weight_multimappers)
+			.field("assembler_mode", &self.assembler_mode)
+			.field("coordinate_sorted", &self.coordinate_sorted)
+			.field("metrics", &self.metrics)
+			.field("rebinning", &self.rebinning)
+			.field("keep_split_reads", &self.keep_split_reads)
+			.field("keep_quarantined_reads", &self.keep_quarantined_reads)
+			.field("skip_mate_pairs", &self.skip_mate_pairs)
+			.field("record_filters", &self.record_filters.len())
+			.field("metric_collectors", &self.metric_collectors)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod statistics_builder_tests {
+	use std::num::NonZeroU32;
+
+	use bam::Record;
+
+	use crate::header::Header;
+	use crate::header::metadata_line::Metadata;
+	use crate::header::metadata_line::format_version::FormatVersion;
+	use crate::header::metadata_line::grouping_order::GroupingOrder;
+	use crate::header::metadata_line::sorting_order::SortingOrder;
+	use crate::header::metadata_line::sub_sorting_order::{SortingOrder as SubSortingOrderKind, SubSortingOrder};
+	use crate::header::reference_sequence_line::ReferenceSequence;
+	use crate::header::reference_sequence_line::molecule_topology::MoleculeTopology;
+	use crate::statistics::builder::{StatisticsBuilder, StatisticsBuilderError};
+	use crate::statistics::calculation::CalculationAddRecordError;
+	use crate::statistics::calculation::binned::BinConfig;
+	use crate::statistics::calculation::custom::tag_histogram::TagHistogramCollector;
+	use crate::statistics::shared::metric_selection::MetricSelection;
+	use crate::statistics::shared::rebinning::Rebinning;
+
+	fn header() -> Header {
+		Header {
+			metadata: None,
+			reference_sequences: vec![ReferenceSequence {
+				name: "chr1".to_string(),
+				length: 1000,
+				alternate_locus: None,
+				alternate_names: None,
+				assembly: None,
+				description: None,
+				checksum: None,
+				species: None,
+				topology: MoleculeTopology::Linear,
+				uri: None
+			}],
+			read_groups: Vec::new(),
+			programs: Vec::new()
+		}
+	}
+
+	fn record(mapq: u8, mapped: bool) -> Record {
+		let mut record = Record::new();
+		record.set_name(b"read".iter().cloned());
+		record.set_mapq(mapq);
+
+		if mapped {
+			record.set_ref_id(0);
+			record.set_start(100);
+			record.set_cigar(b"50M".iter().cloned()).unwrap();
+		}
+		else {
+			record.set_flag(0x4);
+		}
+
+		record
+	}
+
+	fn grouped_header() -> Header {
+		Header {
+			metadata: Some(Metadata {
+				format_version: FormatVersion { major: 1, minor: 6 },
+				sorting_order: SortingOrder::Unsorted,
+				grouping_order: GroupingOrder::Query,
+				sub_sorting_order: SubSortingOrder {
+					sorting_order: SubSortingOrderKind::Unsorted,
+					sub_sort: Vec::new()
+				}
+			}),
+			..header()
+		}
+	}
+
+	fn split_record(name: &[u8], flag: u16, start: i32, next_start: i32) -> Record {
+		let mut record = Record::new();
+		record.set_name(name.iter().cloned());
+		record.set_flag(0x1 | flag);
+		record.set_ref_id(0);
+		record.set_start(start);
+		record.set_mate_ref_id(0);
+		record.set_mate_start(next_start);
+		record.set_cigar(b"50M".iter().cloned()).unwrap();
+		record
+	}
+
+	fn records() -> Vec<Record> {
+		vec![record(0, true), record(30, true), record(30, true), record(0, false)]
+	}
+
+	fn mapped_count(builder: StatisticsBuilder) -> u64 {
+		builder.build(&header(), records()).unwrap()
+			.get_read_length_on_reference_map()
+			.get_frequency_sum()
+	}
+
+	#[test]
+	fn record_filter_test() {
+		assert_eq!(mapped_count(StatisticsBuilder::new()), 3);
+		assert_eq!(mapped_count(StatisticsBuilder::new().with_record_filter(|record| record.mapq() >= 10)), 2);
+	}
+
+	#[test]
+	fn metric_selection_test() {
+		assert_eq!(mapped_count(StatisticsBuilder::new().with_metrics(MetricSelection::all().with_per_reference(false))), 0);
+	}
+
+	#[test]
+	fn sequential_build_test() {
+		let presentation_data = StatisticsBuilder::new()
+			.build_sequential(&header(), records())
+			.unwrap();
+
+		assert_eq!(presentation_data.get_read_length_on_reference_map().get_frequency_sum(), 3);
+	}
+
+	#[test]
+	fn grouped_input_out_of_order_test() {
+		let records = || vec![
+			split_record(b"a", 0x40, 100, 300),
+			split_record(b"b", 0x40, 500, 700),
+			split_record(b"a", 0x80, 300, 100),
+			split_record(b"b", 0x80, 700, 500)
+		];
+
+		let result = StatisticsBuilder::new().build_sequential(&grouped_header(), records());
+
+		assert!(matches!(result, Err(StatisticsBuilderError::AddRecord {
+			source: CalculationAddRecordError::AssemblerStream { .. }
+		})));
+
+		let presentation_data = StatisticsBuilder::new()
+			.build(&grouped_header(), records())
+			.unwrap();
+
+		assert_eq!(presentation_data.get_split_read_data().get_split_count_map().get_frequency_sum(), 2);
+	}
+
+	#[test]
+	fn rebinning_test() {
+		let presentation_data = StatisticsBuilder::new()
+			.with_rebinning(Rebinning::FixedWidth(NonZeroU32::new(100).unwrap()))
+			.build(&header(), records())
+			.unwrap();
+
+		let read_length_map = presentation_data.get_read_length_on_reference_map();
+
+		assert_eq!(presentation_data.get_metadata().rebinning, Rebinning::FixedWidth(NonZeroU32::new(100).unwrap()));
+		assert_eq!(read_length_map.get_frequency_sum(), 3);
+		assert_eq!(read_length_map.get(&49), Some(3));
+	}
+
+	#[test]
+	fn depth_test() {
+		let presentation_data = StatisticsBuilder::new()
+			.build(&header(), records())
+			.unwrap();
+
+		assert_eq!(presentation_data.get_mean_depth(), 0.15);
+		assert_eq!(presentation_data.get_most_depth(), 0.15);
+		assert_eq!(presentation_data.get_mean_read_density(), 0.003);
+	}
+
+	#[test]
+	fn zoom_levels_test() {
+		let presentation_data = StatisticsBuilder::new()
+			.with_bin_config(BinConfig::NumberOfBins(NonZeroU32::new(4).unwrap()))
+			.with_zoom_levels(vec![
+				BinConfig::LengthOfBins(NonZeroU32::new(100).unwrap()),
+				BinConfig::LengthOfBins(NonZeroU32::new(10).unwrap())
+			])
+			.build(&header(), records())
+			.unwrap();
+
+		let reference = presentation_data.get_per_reference_data().next().unwrap();
+		let bin_sizes: Vec<u32> = std::iter::once(reference.get_binned_statistics())
+			.chain(reference.get_zoom_levels().iter().cloned())
+			.map(|binned_statistics| binned_statistics.get_bin_size().get())
+			.collect();
+		let read_counts: Vec<u64> = reference.get_zoom_levels().iter()
+			.map(|zoom_level| zoom_level.get_bins().map(|bin| bin.get_read_count()).sum())
+			.collect();
+
+		assert_eq!(presentation_data.get_metadata().get_bin_config(), BinConfig::NumberOfBins(NonZeroU32::new(4).unwrap()));
+		assert_eq!(presentation_data.get_metadata().bin_size.get(), 251);
+		assert_eq!(bin_sizes, vec![251, 100, 10]);
+		assert_eq!(reference.get_zoom_levels()[1].get_bin_count().get(), 101);
+		assert_eq!(read_counts, vec![3, 15]);
+	}
+
+	#[test]
+	fn metric_collector_test() {
+		let presentation_data = StatisticsBuilder::new()
+			.with_metric_collector(TagHistogramCollector::new(*b"XB"))
+			.build(&header(), records())
+			.unwrap();
+
+		assert_eq!(presentation_data.get_custom_metrics().len(), 1);
+		assert!(presentation_data.get_custom_metric_by_name("XB Tag").is_some());
+	}
+
+	#[test]
+	fn coordinate_sorted_requires_ordered_input_test() {
+		let result = StatisticsBuilder::new()
+			.with_coordinate_sorted(true)
+			.build(&header(), records());
+
+		assert!(matches!(result, Err(StatisticsBuilderError::OrderedInputRequired)));
+	}
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bam::record::Flag;
+
+#[derive(Debug, Default)]
+pub struct AlignmentCategoryCalculationData {
+	pub(crate) primary: AtomicUsize,
+	pub(crate) secondary: AtomicUsize,
+	pub(crate) supplementary: AtomicUsize,
+	pub(crate) duplicates: AtomicUsize,
+	pub(crate) qc_failed: AtomicUsize
+}
+
+impl AlignmentCategoryCalculationData {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Adds the counts of a worker once it is done.
+	pub fn add_local(&self, local: LocalAlignmentCategories) {
+		self.primary.fetch_add(local.primary, Ordering::Relaxed);
+		self.secondary.fetch_add(local.secondary, Ordering::Relaxed);
+		self.supplementary.fetch_add(local.supplementary, Ordering::Relaxed);
+		self.duplicates.fetch_add(local.duplicates, Ordering::Relaxed);
+		self.qc_failed.fetch_add(local.qc_failed, Ordering::Relaxed);
+	}
+}
+
+/// The categories counted by a single worker, added to an [`AlignmentCategoryCalculationData`] once it is done.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LocalAlignmentCategories {
+	pub(crate) primary: usize,
+	pub(crate) secondary: usize,
+	pub(crate) supplementary: usize,
+	pub(crate) duplicates: usize,
+	pub(crate) qc_failed: usize
+}
+
+impl LocalAlignmentCategories {
+	#[inline(always)]
+	pub fn add_flags(&mut self, flags: Flag) {
+		if flags.is_secondary() {
+			self.secondary += 1;
+		}
+		else if flags.is_supplementary() {
+			self.supplementary += 1;
+		}
+		else {
+			self.primary += 1;
+		}
+
+		if flags.is_duplicate() {
+			self.duplicates += 1;
+		}
+		if flags.fails_quality_controls() {
+			self.qc_failed += 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod alignment_category_tests {
+	use bam::record::Flag;
+	use rstest::rstest;
+
+	use crate::statistics::calculation::alignment_categories::{AlignmentCategoryCalculationData, LocalAlignmentCategories};
+	use crate::statistics::presentation::alignment_categories::AlignmentCategories;
+
+	#[rstest]
+	#[case(vec![0x0], (1, 0, 0, 0, 0))]
+	#[case(vec![0x100, 0x800], (0, 1, 1, 0, 0))]
+	#[case(vec![0x400, 0x200, 0x600], (3, 0, 0, 2, 2))]
+	#[case(vec![0x500, 0xA00, 0x1], (1, 1, 1, 1, 1))]
+	fn add_flags_test(#[case] flags: Vec<u16>, #[case] expected: (u64, u64, u64, u64, u64)) {
+		let data = AlignmentCategoryCalculationData::new();
+		let mut local = LocalAlignmentCategories::default();
+
+		for flag in flags {
+			local.add_flags(Flag(flag));
+		}
+
+		data.add_local(local);
+
+		let categories = AlignmentCategories::from(data);
+
+		assert_eq!(
+			(categories.primary, categories.secondary, categories.supplementary, categories.duplicates, categories.qc_failed),
+			expected
+		);
+	}
+}
\ No newline at end of file
@@ -1,72 +1,252 @@
 use std::collections::HashMap;
-use std::sync::{Mutex, RwLock};
+use std::sync::Mutex;
 
 use bam::Record;
-use crate::util::{get_record_t_len};
+use crate::statistics::presentation::record::flags::PresentationFlags;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::util::{get_record_integer_tag, get_record_name_hash};
 
-#[derive(Debug)]
-pub struct CalculationAssemblerMap {
-    pub(crate) normals: RwLock<HashMap<u32, Mutex<Vec<Record>>>>,
-    pub(crate) supplementaries: RwLock<HashMap<u32, Mutex<Vec<Record>>>>,
-    pub(crate) secondaries: RwLock<HashMap<u32, Mutex<Vec<Record>>>>,
-    pub(crate) duplicates: RwLock<HashMap<u32, Mutex<Vec<Record>>>>
-}
+/// The full QNAME and the template segment of a fragment, see [`get_group_key`].
+pub(crate) type FragmentGroupKey = (Vec<u8>, u8);
 
-impl CalculationAssemblerMap {
-    pub fn new() -> Self {
-        CalculationAssemblerMap {
-            normals: Default::default(),
-            supplementaries: Default::default(),
-            secondaries: Default::default(),
-            duplicates: Default::default()
-        }
-    }
+/// Records grouped by their QNAME and segment.
+pub(crate) type FragmentGroups = HashMap<FragmentGroupKey, Vec<Record>>;
 
-    pub fn add_record(&self, record: Record) {
+/// Fragment groups spread over a fixed number of shards by the hash of the QNAME to keep lock contention low.
+pub(crate) type AssemblerShards = Vec<Mutex<FragmentGroups>>;
+
+/// The groups a record is added to, a record that is neither supplementary, duplicate nor secondary is a normal fragment.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum FragmentCategory {
+    Normal,
+    Supplementary,
+    Secondary,
+    Duplicate
+}
+
+impl FragmentCategory {
+    /// Calls `add` once per category of the record, supplementaries the mode chains to their read count as normal fragments.
+    fn for_each<F: FnMut(FragmentCategory, Record)>(mode: AssemblerMode, record: Record, mut add: F) {
         let mut normal = true;
 
         if record.flag().is_supplementary() {
-            Self::insert(record.clone(), &self.supplementaries);
+            let category = if mode.chains_supplementary(&record) { FragmentCategory::Normal } else { FragmentCategory::Supplementary };
+            add(category, record.clone());
             normal = false;
         }
         if record.flag().is_duplicate() {
-            Self::insert(record.clone(), &self.duplicates);
+            add(FragmentCategory::Duplicate, record.clone());
             normal = false;
         }
         if record.flag().is_secondary() {
-            Self::insert(record.clone(), &self.secondaries);
+            add(FragmentCategory::Secondary, record.clone());
             normal = false;
         }
         if normal {
-            Self::insert(record, &self.normals);
+            add(FragmentCategory::Normal, record);
         }
     }
+}
 
-    fn insert(record: Record, map: &RwLock<HashMap<u32, Mutex<Vec<Record>>>>) {
-        let t_len = get_record_t_len(&record);
+/// Each mate of a template carries its own chain of segemehl split indices (`XQ`), so tagged records are also grouped by segment.
+/// Without split tags the first/last segment flags mark the ends of the split read, all fragments of a name then share segment 0.
+fn get_group_key(mode: AssemblerMode, record: &Record) -> FragmentGroupKey {
+    let segment_index = match mode {
+        AssemblerMode::SegemehlTags if get_record_integer_tag(record, b"XQ").is_some() =>
+            PresentationFlags::from(record.flag()).get_segment_index(),
+        _ => 0
+    };
 
-        let read_lock = map.read().unwrap();
-        if read_lock.contains_key(&t_len) {
-            let mut template_records = read_lock.get(&t_len).unwrap().lock().unwrap();
+    (record.name().to_vec(), segment_index)
+}
 
-            template_records.push(record);
-        }
-        else {
-            drop(read_lock);
-            Self::insert_new(t_len, record, map);
+#[derive(Debug)]
+pub struct CalculationAssemblerMap {
+    pub(crate) normals: AssemblerShards,
+    pub(crate) supplementaries: AssemblerShards,
+    pub(crate) secondaries: AssemblerShards,
+    pub(crate) duplicates: AssemblerShards,
+    pub(crate) mode: AssemblerMode,
+    pub(crate) skip_mate_pairs: bool
+}
+
+impl CalculationAssemblerMap {
+    pub const SHARD_COUNT: usize = 256;
+
+    pub fn new(mode: AssemblerMode) -> Self {
+        CalculationAssemblerMap {
+            normals: Self::new_shards(),
+            supplementaries: Self::new_shards(),
+            secondaries: Self::new_shards(),
+            duplicates: Self::new_shards(),
+            mode,
+            skip_mate_pairs: false
         }
     }
 
-    fn insert_new(t_len: u32, record: Record, map: &RwLock<HashMap<u32, Mutex<Vec<Record>>>>) {
-        let mut write_lock = map.write().unwrap();
+    /// Leaves ordinary paired-end mates out of the assembly, see [`is_mate_pair`](crate::util::is_mate_pair).
+    pub fn skip_mate_pairs(&mut self, skip_mate_pairs: bool) {
+        self.skip_mate_pairs = skip_mate_pairs;
+    }
+
+    fn new_shards() -> AssemblerShards {
+        (0..Self::SHARD_COUNT).map(|_| Mutex::new(HashMap::new())).collect()
+    }
+
+    #[deprecated(note = "collect into a LocalAssemblerMap and add it with add_local")]
+    pub fn add_record(&self, record: Record) {
+        let mut local = LocalAssemblerMap::default();
+        local.add_record(self.mode, record);
+        self.add_local(local);
+    }
 
-        if write_lock.contains_key(&t_len) {
-            let mut template_records = write_lock.get(&t_len).unwrap().lock().unwrap();
+    /// Adds the groups a worker collected on its own, fragments of the same group are appended.
+    pub fn add_local(&self, local: LocalAssemblerMap) {
+        let categories = [
+            (FragmentCategory::Normal, local.normals),
+            (FragmentCategory::Supplementary, local.supplementaries),
+            (FragmentCategory::Secondary, local.secondaries),
+            (FragmentCategory::Duplicate, local.duplicates)
+        ];
 
-            template_records.push(record);
+        for (category, groups) in categories {
+            for (key, records) in groups {
+                let shard = self.get_shard(category, &records[0]);
+
+                shard.lock().unwrap()
+                    .entry(key)
+                    .or_default()
+                    .extend(records);
+            }
         }
-        else {
-            write_lock.insert(t_len, Mutex::new(vec![record]));
+    }
+
+    fn get_shard(&self, category: FragmentCategory, record: &Record) -> &Mutex<FragmentGroups> {
+        let shards = match category {
+            FragmentCategory::Normal => &self.normals,
+            FragmentCategory::Supplementary => &self.supplementaries,
+            FragmentCategory::Secondary => &self.secondaries,
+            FragmentCategory::Duplicate => &self.duplicates
+        };
+
+        &shards[(get_record_name_hash(record) % Self::SHARD_COUNT as u64) as usize]
+    }
+}
+
+/// The fragments collected by a single worker, added to a [`CalculationAssemblerMap`] once it is done.
+#[derive(Debug, Default)]
+pub struct LocalAssemblerMap {
+    pub(crate) normals: FragmentGroups,
+    pub(crate) supplementaries: FragmentGroups,
+    pub(crate) secondaries: FragmentGroups,
+    pub(crate) duplicates: FragmentGroups
+}
+
+impl LocalAssemblerMap {
+    pub fn add_record(&mut self, mode: AssemblerMode, record: Record) {
+        FragmentCategory::for_each(mode, record, |category, record| {
+            let groups = match category {
+                FragmentCategory::Normal => &mut self.normals,
+                FragmentCategory::Supplementary => &mut self.supplementaries,
+                FragmentCategory::Secondary => &mut self.secondaries,
+                FragmentCategory::Duplicate => &mut self.duplicates
+            };
+
+            groups.entry(get_group_key(mode, &record))
+                .or_default()
+                .push(record);
+        });
+    }
+}
+
+#[cfg(test)]
+mod calculation_assembler_map_tests {
+    use bam::Record;
+
+    use crate::statistics::calculation::assembler::map::{CalculationAssemblerMap, LocalAssemblerMap};
+    use crate::statistics::shared::assembler_mode::AssemblerMode;
+
+    fn record(name: &[u8], template_length: i32) -> Record {
+        let mut record = Record::new();
+        record.set_name(name.iter().cloned());
+        record.set_template_len(template_length);
+        record
+    }
+
+    fn tagged_record(name: &[u8], flag: u16, split_index: i32) -> Record {
+        let mut record = record(name, 0);
+        record.set_flag(flag);
+        record.tags_mut().push_num(b"XQ", split_index);
+        record
+    }
+
+    fn collect(mode: AssemblerMode, records: Vec<Record>) -> CalculationAssemblerMap {
+        let map = CalculationAssemblerMap::new(mode);
+        let mut local = LocalAssemblerMap::default();
+
+        for record in records {
+            local.add_record(mode, record);
         }
+
+        map.add_local(local);
+        map
+    }
+
+    fn group_sizes(map: CalculationAssemblerMap) -> Vec<usize> {
+        let mut group_sizes: Vec<usize> = map.normals.into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap().into_values().map(|records| records.len()))
+            .collect();
+        group_sizes.sort_unstable();
+        group_sizes
+    }
+
+    #[test]
+    fn add_record_groups_by_name_test() {
+        let map = collect(AssemblerMode::Flags, vec![
+            record(b"a", 500),
+            record(b"a", -800),
+            record(b"b", 500),
+            record(b"ab", 500)
+        ]);
+
+        assert_eq!(group_sizes(map), vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn tagged_records_are_grouped_by_segment_test() {
+        let map = collect(AssemblerMode::SegemehlTags, vec![
+            tagged_record(b"a", 0x1 | 0x40, 0),
+            tagged_record(b"a", 0x1 | 0x40, 1),
+            tagged_record(b"a", 0x1 | 0x80, 0),
+            record(b"a", 500)
+        ]);
+
+        assert_eq!(group_sizes(map), vec![1, 3]);
+    }
+
+    #[test]
+    fn flags_mode_ignores_the_segment_test() {
+        let map = collect(AssemblerMode::Flags, vec![
+            tagged_record(b"a", 0x1 | 0x40, 0),
+            tagged_record(b"a", 0x1 | 0x80, 1)
+        ]);
+
+        assert_eq!(group_sizes(map), vec![2]);
+    }
+
+    #[test]
+    fn workers_are_merged_into_the_same_groups_test() {
+        let map = CalculationAssemblerMap::new(AssemblerMode::Flags);
+        let mut first_worker = LocalAssemblerMap::default();
+        let mut second_worker = LocalAssemblerMap::default();
+
+        first_worker.add_record(AssemblerMode::Flags, record(b"a", 500));
+        second_worker.add_record(AssemblerMode::Flags, record(b"a", -800));
+        second_worker.add_record(AssemblerMode::Flags, record(b"b", 500));
+
+        map.add_local(first_worker);
+        map.add_local(second_worker);
+
+        assert_eq!(group_sizes(map), vec![1, 2]);
     }
-}
\ No newline at end of file
+}
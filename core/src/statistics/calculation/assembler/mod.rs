@@ -1 +1,33 @@
-pub mod map;
\ No newline at end of file
+use crate::statistics::calculation::assembler::map::CalculationAssemblerMap;
+use crate::statistics::calculation::assembler::stream::CalculationAssemblerStream;
+
+pub mod map;
+pub mod stream;
+
+/// Buffers all split read records until the end, unless streaming was enabled for input that keeps the records of a read name together.
+#[derive(Debug)]
+pub enum CalculationAssembler {
+    Buffered(CalculationAssemblerMap),
+    Streaming(Box<CalculationAssemblerStream>)
+}
+
+impl CalculationAssembler {
+    /// Streaming requires the records to be added in file order.
+    pub fn is_streaming(&self) -> bool {
+        matches!(self, CalculationAssembler::Streaming(_))
+    }
+
+    pub fn skip_mate_pairs(&mut self, skip_mate_pairs: bool) {
+        match self {
+            CalculationAssembler::Buffered(map) => map.skip_mate_pairs(skip_mate_pairs),
+            CalculationAssembler::Streaming(stream) => stream.skip_mate_pairs(skip_mate_pairs)
+        }
+    }
+
+    /// Only the streaming assembler drops the reads while reading, the buffered one holds them until the end anyway.
+    pub fn keep_reads(&mut self, keep_split_reads: bool, keep_quarantined_reads: bool) {
+        if let CalculationAssembler::Streaming(stream) = self {
+            stream.keep_reads(keep_split_reads, keep_quarantined_reads);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use bam::Record;
+use thiserror::Error;
+use crate::statistics::presentation::assembler::PresentationAssembler;
+use crate::statistics::presentation::record::PresentationRecord;
+use crate::statistics::presentation::split_read::collection::SplitReadCollection;
+use crate::statistics::presentation::split_read::quarantine::SplitReadQuarantine;
+use crate::statistics::presentation::split_read::summary::SplitReadSummary;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::util::is_mate_pair;
+
+#[derive(Error, Debug)]
+pub enum AssemblerStreamAddRecordError {
+    #[error("the records are not grouped by name, read {name} showed up again after other reads")]
+    NameNotGrouped {
+        name: String
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AssemblerStreamState {
+    pub(crate) current_name: Vec<u8>,
+    pub(crate) finished_names: HashSet<Vec<u8>>,
+    pub(crate) normals: Vec<Record>,
+    pub(crate) supplementaries: Vec<Record>,
+    pub(crate) secondaries: Vec<Record>,
+    pub(crate) duplicates: Vec<Record>,
+    pub(crate) summary: SplitReadSummary
+}
+
+impl AssemblerStreamState {
+    /// Adds the assembled reads and the dropped fragments of the current name to the summary and drops the records.
+    fn finish_group(&mut self, mode: AssemblerMode, skip_mate_pairs: bool) {
+        let groups = [
+            (std::mem::take(&mut self.normals), true),
+            (std::mem::take(&mut self.supplementaries), true),
+            (std::mem::take(&mut self.secondaries), false),
+            (std::mem::take(&mut self.duplicates), false)
+        ];
+
+        for (records, is_candidate) in groups {
+            if records.is_empty() {
+                continue;
+            }
+
+            if skip_mate_pairs && is_mate_pair(&records) {
+                self.summary.skipped_mate_pairs += 1;
+                continue;
+            }
+
+            let records: Vec<PresentationRecord> = records.into_iter().map(|record| record.into()).collect();
+            let (split_reads, dropped_records): (SplitReadCollection, Vec<PresentationRecord>) = PresentationAssembler::new(records, mode).into();
+
+            for split_read in split_reads.into_inner() {
+                self.summary.add_split_read(split_read, is_candidate);
+            }
+
+            if !dropped_records.is_empty() {
+                self.summary.add_quarantine(SplitReadQuarantine::from_records(dropped_records));
+            }
+        }
+    }
+}
+
+/// Assembles the fragments of a template once the next read name shows up, instead of buffering every record.
+///
+/// Only valid for input that keeps all records of a QNAME adjacent, the records have to be added in file order.
+/// The names of finished templates are kept, so a name that shows up again is an error instead of a split template.
+#[derive(Debug)]
+pub struct CalculationAssemblerStream {
+    pub(crate) state: Mutex<AssemblerStreamState>,
+    pub(crate) mode: AssemblerMode,
+    pub(crate) skip_mate_pairs: bool
+}
+
+impl CalculationAssemblerStream {
+    pub fn new(mode: AssemblerMode) -> Self {
+        Self {
+            state: Mutex::new(AssemblerStreamState::default()),
+            mode,
+            skip_mate_pairs: false
+        }
+    }
+
+    /// Leaves ordinary paired-end mates out of the assembly, see [`is_mate_pair`].
+    pub fn skip_mate_pairs(&mut self, skip_mate_pairs: bool) {
+        self.skip_mate_pairs = skip_mate_pairs;
+    }
+
+    pub fn add_record(&self, record: Record) -> Result<(), AssemblerStreamAddRecordError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.current_name.as_slice() != record.name() {
+            if state.finished_names.contains(record.name()) {
+                return Err(AssemblerStreamAddRecordError::NameNotGrouped {
+                    name: String::from_utf8_lossy(record.name()).into_owned()
+                });
+            }
+
+            state.finish_group(self.mode, self.skip_mate_pairs);
+
+            let finished_name = std::mem::replace(&mut state.current_name, record.name().to_vec());
+            state.finished_names.insert(finished_name);
+        }
+
+        let mut normal = true;
+
+        if record.flag().is_supplementary() {
+            if self.mode.chains_supplementary(&record) {
+                state.normals.push(record.clone());
+            }
+            else {
+                state.supplementaries.push(record.clone());
+            }
+            normal = false;
+        }
+        if record.flag().is_duplicate() {
+            state.duplicates.push(record.clone());
+            normal = false;
+        }
+        if record.flag().is_secondary() {
+            state.secondaries.push(record.clone());
+            normal = false;
+        }
+        if normal {
+            state.normals.push(record);
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the assembled reads for the split read export and the dropped fragments for the quarantine export,
+    /// otherwise only their statistics are kept.
+    pub fn keep_reads(&mut self, keep_split_reads: bool, keep_quarantined_reads: bool) {
+        let summary = &mut self.state.get_mut().unwrap().summary;
+        summary.keep_split_reads = keep_split_reads;
+        summary.keep_quarantined_reads = keep_quarantined_reads;
+    }
+
+    /// Assembles the last template, which has no following read name to end it.
+    pub fn into_summary(self) -> SplitReadSummary {
+        let mut state = self.state.into_inner().unwrap();
+        state.finish_group(self.mode, self.skip_mate_pairs);
+        state.summary
+    }
+}
+
+#[cfg(test)]
+mod calculation_assembler_stream_tests {
+    use bam::Record;
+
+    use crate::statistics::calculation::assembler::stream::{AssemblerStreamAddRecordError, CalculationAssemblerStream};
+    use crate::statistics::presentation::split_read::statistics::SplitReadStatistics;
+    use crate::statistics::shared::assembler_mode::AssemblerMode;
+
+    fn record(name: &[u8], flag: u16, start: i32, next_start: i32) -> Record {
+        let mut record = Record::new();
+        record.set_name(name.iter().cloned());
+        record.set_flag(flag);
+        record.set_ref_id(0);
+        record.set_start(start);
+        record.set_mate_ref_id(0);
+        record.set_mate_start(next_start);
+        record.set_cigar(b"50M".iter().cloned()).unwrap();
+        record.set_template_len(500);
+        record
+    }
+
+    #[test]
+    fn groups_are_finished_when_the_name_changes_test() {
+        let stream = CalculationAssemblerStream::new(AssemblerMode::Flags);
+
+        stream.add_record(record(b"a", 0x1 | 0x40, 100, 300)).unwrap();
+        stream.add_record(record(b"a", 0x1 | 0x80, 300, 100)).unwrap();
+
+        assert_eq!(stream.state.lock().unwrap().normals.len(), 2);
+
+        stream.add_record(record(b"b", 0x1 | 0x40, 700, 900)).unwrap();
+
+        {
+            let state = stream.state.lock().unwrap();
+            assert_eq!(state.normals.len(), 1);
+            assert!(state.summary.split_reads.is_empty());
+        }
+
+        let summary = stream.into_summary();
+        let statistics: SplitReadStatistics = summary.statistics.into();
+
+        assert_eq!(statistics.get_split_count_map().get_frequency_sum(), 1);
+        assert_eq!(summary.quarantine.get_record_count(), 1);
+        assert!(summary.quarantine.get_reads().is_empty());
+    }
+
+    #[test]
+    fn name_after_other_names_is_an_error_test() {
+        let stream = CalculationAssemblerStream::new(AssemblerMode::Flags);
+
+        stream.add_record(record(b"a", 0x1 | 0x40, 100, 300)).unwrap();
+        stream.add_record(record(b"b", 0x1 | 0x40, 700, 900)).unwrap();
+
+        let result = stream.add_record(record(b"a", 0x1 | 0x80, 300, 100));
+
+        assert!(matches!(result, Err(AssemblerStreamAddRecordError::NameNotGrouped { name }) if name == "a"));
+    }
+
+    #[test]
+    fn kept_reads_test() {
+        let mut stream = CalculationAssemblerStream::new(AssemblerMode::Flags);
+        stream.keep_reads(true, true);
+
+        stream.add_record(record(b"a", 0x1 | 0x40, 100, 300)).unwrap();
+        stream.add_record(record(b"a", 0x1 | 0x80, 300, 100)).unwrap();
+        stream.add_record(record(b"b", 0x1 | 0x40, 700, 900)).unwrap();
+
+        let summary = stream.into_summary();
+
+        assert_eq!(summary.split_reads.len(), 1);
+        assert_eq!(summary.quarantine.get_reads().len(), 1);
+    }
+
+    #[test]
+    fn mate_pairs_are_only_skipped_on_request_test() {
+        for skip_mate_pairs in [false, true] {
+            let mut stream = CalculationAssemblerStream::new(AssemblerMode::Flags);
+            stream.skip_mate_pairs(skip_mate_pairs);
+
+            let mut last = record(b"a", 0x1 | 0x80, 300, 100);
+            last.set_template_len(-500);
+
+            stream.add_record(record(b"a", 0x1 | 0x40, 100, 300)).unwrap();
+            stream.add_record(last).unwrap();
+
+            let summary = stream.into_summary();
+            let statistics: SplitReadStatistics = summary.statistics.into();
+
+            assert_eq!(summary.skipped_mate_pairs, skip_mate_pairs as u64);
+            assert_eq!(statistics.get_split_count_map().get_frequency_sum(), !skip_mate_pairs as u64);
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+use crate::statistics::calculation::alignment_categories::AlignmentCategoryCalculationData;
+use crate::statistics::calculation::binned::local::LocalBinStatistics;
 use crate::util::length;
 
 #[derive(Debug)]
@@ -8,10 +12,14 @@ pub struct BinStatisticsCalculationData {
 	pub(crate) end: u32,
 	pub(crate) read_count: AtomicUsize,
 	pub(crate) total_read_length: AtomicUsize,
+	pub(crate) multimapper_read_lengths: Mutex<MultimapperReadLengths>,
 	pub(crate) alignment_matches: AtomicUsize,
 	pub(crate) insertions: AtomicUsize,
 	pub(crate) deletions: AtomicUsize,
 	pub(crate) skips: AtomicUsize,
+	pub(crate) soft_clips: AtomicUsize,
+	pub(crate) hard_clips: AtomicUsize,
+	pub(crate) alignment_categories: AlignmentCategoryCalculationData,
 }
 
 impl BinStatisticsCalculationData {
@@ -21,10 +29,14 @@ impl BinStatisticsCalculationData {
 			end,
 			read_count: Default::default(),
 			total_read_length: Default::default(),
+			multimapper_read_lengths: Default::default(),
 			alignment_matches: Default::default(),
 			insertions: Default::default(),
 			deletions: Default::default(),
-			skips: Default::default()
+			skips: Default::default(),
+			soft_clips: Default::default(),
+			hard_clips: Default::default(),
+			alignment_categories: AlignmentCategoryCalculationData::new()
 		}
 	}
 
@@ -53,8 +65,65 @@ impl BinStatisticsCalculationData {
 		self.total_read_length.load(Ordering::Relaxed)
 	}
 
+	#[inline(always)]
+	pub fn get_weighted_total_read_length(&self) -> f64 {
+		self.multimapper_read_lengths.lock().unwrap().get_weighted_total_read_length(self.get_total_read_length() as u64)
+	}
+
 	#[inline(always)]
 	pub fn get_coverage(&self) -> f64 {
-		self.total_read_length.load(Ordering::Relaxed) as f64 / self.get_length() as f64
+		self.get_weighted_total_read_length() / self.get_length() as f64
+	}
+
+	pub(crate) fn add_local(&self, local: LocalBinStatistics) {
+		self.read_count.fetch_add(local.read_count, Ordering::Relaxed);
+		self.total_read_length.fetch_add(local.total_read_length, Ordering::Relaxed);
+
+		if !local.multimapper_read_lengths.is_empty() {
+			self.multimapper_read_lengths.lock().unwrap().add_all(&local.multimapper_read_lengths);
+		}
+
+		self.alignment_matches.fetch_add(local.alignment_matches, Ordering::Relaxed);
+		self.insertions.fetch_add(local.insertions, Ordering::Relaxed);
+		self.deletions.fetch_add(local.deletions, Ordering::Relaxed);
+		self.skips.fetch_add(local.skips, Ordering::Relaxed);
+		self.soft_clips.fetch_add(local.soft_clips, Ordering::Relaxed);
+		self.hard_clips.fetch_add(local.hard_clips, Ordering::Relaxed);
+		self.alignment_categories.add_local(local.alignment_categories);
+	}
+}
+
+/// The bases of multimapped alignments per hit count.
+///
+/// Summing integers doesn't depend on the order the alignments were added in,
+/// so the weighted length derived from them is the same for every run, unlike a running `f64` sum.
+#[derive(Debug, Default, Clone)]
+pub struct MultimapperReadLengths {
+	lengths: BTreeMap<u32, u64>
+}
+
+impl MultimapperReadLengths {
+	#[inline(always)]
+	pub fn add(&mut self, hit_count: u32, length: u32) {
+		*self.lengths.entry(hit_count).or_insert(0) += length as u64;
+	}
+
+	pub fn add_all(&mut self, other: &MultimapperReadLengths) {
+		for (hit_count, length) in other.lengths.iter() {
+			*self.lengths.entry(*hit_count).or_insert(0) += length;
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.lengths.is_empty()
+	}
+
+	/// Every alignment of a read with `NH` hits contributes `1/NH` of its length, the remaining bases count fully.
+	pub fn get_weighted_total_read_length(&self, total_read_length: u64) -> f64 {
+		let multimapper_length: u64 = self.lengths.values().sum();
+
+		self.lengths.iter().fold((total_read_length - multimapper_length) as f64, |weighted_length, (hit_count, length)| {
+			weighted_length + *length as f64 / *hit_count as f64
+		})
 	}
 }
@@ -0,0 +1,75 @@
+use bam::record::Flag;
+use bam::record::cigar::Operation;
+
+use crate::statistics::calculation::alignment_categories::LocalAlignmentCategories;
+use crate::statistics::calculation::binned::data::MultimapperReadLengths;
+
+/// A bin as counted by a single worker, added to a [`BinStatisticsCalculationData`](crate::statistics::calculation::binned::data::BinStatisticsCalculationData) once the worker is done.
+#[derive(Debug, Default, Clone)]
+pub struct LocalBinStatistics {
+	pub(crate) read_count: usize,
+	pub(crate) total_read_length: usize,
+	pub(crate) multimapper_read_lengths: MultimapperReadLengths,
+	pub(crate) alignment_matches: usize,
+	pub(crate) insertions: usize,
+	pub(crate) deletions: usize,
+	pub(crate) skips: usize,
+	pub(crate) soft_clips: usize,
+	pub(crate) hard_clips: usize,
+	pub(crate) alignment_categories: LocalAlignmentCategories
+}
+
+/// The bins of a reference as counted by a single worker, grown up to the last bin the worker touched.
+#[derive(Debug, Default)]
+pub struct LocalBinnedStatistics {
+	pub(crate) bins: Vec<LocalBinStatistics>
+}
+
+impl LocalBinnedStatistics {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	#[inline(always)]
+	fn get_bin(&mut self, bin_index: usize) -> &mut LocalBinStatistics {
+		if bin_index >= self.bins.len() {
+			self.bins.resize_with(bin_index + 1, Default::default);
+		}
+
+		&mut self.bins[bin_index]
+	}
+
+	/// `hit_count` is the number of alignments the length is divided by, 1 unless multimappers are weighted.
+	#[inline(always)]
+	pub(crate) fn add_read(&mut self, bin_index: usize, length_in_bin: u32, hit_count: u32, flags: Flag) {
+		let bin = self.get_bin(bin_index);
+
+		bin.read_count += 1;
+		bin.total_read_length += length_in_bin as usize;
+		bin.alignment_categories.add_flags(flags);
+
+		if hit_count > 1 {
+			bin.multimapper_read_lengths.add(hit_count, length_in_bin);
+		}
+	}
+
+	pub(crate) fn add_cigars(&mut self, bin_index: usize, cigars: Vec<(u32, Operation)>) {
+		let bin = self.get_bin(bin_index);
+
+		for (len, op) in cigars {
+			let len = len as usize;
+
+			match op {
+				Operation::SeqMatch |
+				Operation::SeqMismatch |
+				Operation::AlnMatch => bin.alignment_matches += len,
+				Operation::Insertion => bin.insertions += len,
+				Operation::Deletion => bin.deletions += len,
+				Operation::Skip => bin.skips += len,
+				Operation::Soft => bin.soft_clips += len,
+				Operation::Hard => bin.hard_clips += len,
+				Operation::Padding => {}
+			}
+		}
+	}
+}
@@ -1,14 +1,13 @@
 use std::cmp::{max, min};
 use std::num::NonZeroU32;
-use std::sync::atomic::Ordering;
 
 use crate::statistics::calculation::binned::BinConfig;
 use crate::statistics::calculation::binned::data::BinStatisticsCalculationData;
-use crate::util::{calculate_bin, length, get_record_start, get_record_end, CigarMaxLengthIter};
+use crate::statistics::calculation::binned::local::LocalBinnedStatistics;
+use crate::util::{calculate_bin, length, get_record_start, get_record_end, get_record_clipping, get_record_hit_count, CigarMaxLengthIter};
 use bam::Record;
 use thiserror::Error;
 use bam::record::cigar::Operation;
-use std::sync::atomic::Ordering::Relaxed;
 
 #[derive(Error, Debug)]
 pub enum BinnedStatisticsCalculationMapNewError {
@@ -21,34 +20,20 @@ pub struct BinnedStatisticsCalculationMap {
 	pub(crate) bin_size: NonZeroU32,
 	pub(crate) bins: Vec<BinStatisticsCalculationData>,
 	pub(crate) start: u32,
-	pub(crate) end: u32
+	pub(crate) end: u32,
+	pub(crate) weight_multimappers: bool
 }
 
 impl BinnedStatisticsCalculationMap {
-	pub fn new(start: u32, end: u32, config: BinConfig) -> Result<Self, BinnedStatisticsCalculationMapNewError> {
+	pub fn new(start: u32, end: u32, config: BinConfig, weight_multimappers: bool) -> Result<Self, BinnedStatisticsCalculationMapNewError> {
 		if end < start {return Err(BinnedStatisticsCalculationMapNewError::InvalidRange);}
 
 		let length = length(start, end);
 
-		let (length_of_bins, number_of_bins) = match config {
-			BinConfig::NumberOfBins(num) => {
-				if length <= num.get() {
-					(NonZeroU32::new(1).unwrap(), NonZeroU32::new(length).unwrap())
-				}
-				else {
-					let length_of_bin = NonZeroU32::new((length as f64 / num.get() as f64).ceil() as u32).unwrap();
+		let length_of_bins = config.get_bin_size(NonZeroU32::new(length).unwrap());
+		let number_of_bins = (length as f64 / length_of_bins.get() as f64).ceil() as usize;
 
-					(length_of_bin, num)
-				}
-			}
-			BinConfig::LengthOfBins(len) => {
-				let num_of_bins = NonZeroU32::new((length as f64 / len.get() as f64).ceil() as u32).unwrap();
-
-				(len, num_of_bins)
-			}
-		};
-
-		let mut bins = Vec::<BinStatisticsCalculationData>::with_capacity(number_of_bins.get() as usize);
+		let mut bins = Vec::<BinStatisticsCalculationData>::with_capacity(number_of_bins);
 
 		let mut current_start = start;
 
@@ -70,15 +55,27 @@ impl BinnedStatisticsCalculationMap {
 			bin_size: length_of_bins,
 			bins,
 			start,
-			end
+			end,
+			weight_multimappers
 		})
 	}
 
+	#[deprecated(note = "count into a LocalBinnedStatistics with add_record_to and add it with add_local")]
 	pub fn add_record(&self, record: &Record) {
+		let mut local = LocalBinnedStatistics::new();
+		self.add_record_to(record, &mut local);
+		self.add_local(local);
+	}
+
+	/// Splits the record into the parts that fall into each bin of the worker's own statistics.
+	pub fn add_record_to(&self, record: &Record, local: &mut LocalBinnedStatistics) {
 		let start = get_record_start(record);
-		let end = get_record_end(record);
 
-		if end < start {return;}
+		// `get_record_end` is exclusive, the bins need the last aligned base.
+		let end = match get_record_end(record).checked_sub(1) {
+			Some(end) if end >= start => end,
+			_ => return
+		};
 
 		if start > self.end && end > self.end {return;}
 		if start < self.start && end < self.start {return;}
@@ -90,19 +87,29 @@ impl BinnedStatisticsCalculationMap {
 		let end_bin = calculate_bin(self.start, self.bin_size, end).unwrap();
 
 		let mut front_iterator = CigarMaxLengthIter::new(record);
-		let mut back_iterator = CigarMaxLengthIter::new(record);
+		let mut back_iterator = CigarMaxLengthIter::new_reversed(record);
+
+		let clipping = get_record_clipping(record);
+		let hit_count = self.get_weighting_hit_count(record);
+		let flags = record.flag();
+
+		local.add_cigars(start_bin.bin_index as usize, vec![
+			(clipping.leading_soft, Operation::Soft),
+			(clipping.leading_hard, Operation::Hard)
+		]);
+		local.add_cigars(end_bin.bin_index as usize, vec![
+			(clipping.trailing_soft, Operation::Soft),
+			(clipping.trailing_hard, Operation::Hard)
+		]);
 
 		if start_bin.bin_index == end_bin.bin_index {
 			let length_in_bin = length(start, end);
-
-			let bin = &self.bins[start_bin.bin_index as usize];
+			let bin_index = start_bin.bin_index as usize;
 
 			let cigars = front_iterator.collect();
 
-			Self::add_cigars_to_bin(bin, cigars);
-
-			bin.read_count.fetch_add(1, Ordering::Relaxed);
-			bin.total_read_length.fetch_add(length_in_bin as usize, Ordering::Relaxed);
+			local.add_cigars(bin_index, cigars);
+			local.add_read(bin_index, length_in_bin, hit_count, flags);
 
 			return;
 		}
@@ -110,40 +117,49 @@ impl BinnedStatisticsCalculationMap {
 		let length_in_start_bin = self.bin_size.get() - start_bin.position_in_bin;
 		let length_in_end_bin = end_bin.position_in_bin + 1;
 
-		let bin = &self.bins[start_bin.bin_index as usize];
-
-		bin.read_count.fetch_add(1, Ordering::Relaxed);
-		bin.total_read_length.fetch_add(length_in_start_bin as usize, Ordering::Relaxed);
+		local.add_read(start_bin.bin_index as usize, length_in_start_bin, hit_count, flags);
 
 		let start_bin_cigars = front_iterator.next_for_ref_length(length_in_start_bin);
 
-		Self::add_cigars_to_bin(bin, start_bin_cigars);
-
-		let bin = &self.bins[end_bin.bin_index as usize];
+		local.add_cigars(start_bin.bin_index as usize, start_bin_cigars);
 
-		bin.read_count.fetch_add(1, Ordering::Relaxed);
-		bin.total_read_length.fetch_add(length_in_end_bin as usize, Ordering::Relaxed);
+		local.add_read(end_bin.bin_index as usize, length_in_end_bin, hit_count, flags);
 
 		let end_bin_cigars = back_iterator.next_for_ref_length(length_in_end_bin);
 
-		Self::add_cigars_to_bin(bin, end_bin_cigars);
+		local.add_cigars(end_bin.bin_index as usize, end_bin_cigars);
 
 		let first_full_bin = start_bin.bin_index + 1;
 
 		if end_bin.bin_index <= first_full_bin {return;}
 
-		for bin in first_full_bin..end_bin.bin_index {
-			let bin = &self.bins[bin as usize];
+		for bin_index in first_full_bin..end_bin.bin_index {
+			let bin_length = self.bins[bin_index as usize].get_length();
 
-			bin.read_count.fetch_add(1, Ordering::Relaxed);
-			bin.total_read_length.fetch_add(self.bin_size.get() as usize, Ordering::Relaxed);
+			local.add_read(bin_index as usize, self.bin_size.get(), hit_count, flags);
 
-			let bin_cigars = front_iterator.next_for_ref_length(bin.get_length());
+			let bin_cigars = front_iterator.next_for_ref_length(bin_length);
+
+			local.add_cigars(bin_index as usize, bin_cigars);
+		}
+	}
 
-			Self::add_cigars_to_bin(bin, bin_cigars);
+	/// Adds the bins a worker counted on its own.
+	pub fn add_local(&self, local: LocalBinnedStatistics) {
+		for (bin, local_bin) in self.bins.iter().zip(local.bins) {
+			bin.add_local(local_bin);
 		}
 	}
 
+	/// With multimapper weighting every alignment of a read with `NH` hits contributes `1/NH`, otherwise the full length.
+	fn get_weighting_hit_count(&self, record: &Record) -> u32 {
+		if !self.weight_multimappers {
+			return 1;
+		}
+
+		get_record_hit_count(record).unwrap_or(1)
+	}
+
 	#[inline(always)]
 	pub fn get_bin_size(&self) -> NonZeroU32 {
 		self.bin_size
@@ -173,31 +189,6 @@ impl BinnedStatisticsCalculationMap {
 	pub fn get_bins(&self) -> impl Iterator<Item = &BinStatisticsCalculationData> {
 		self.bins.iter()
 	}
-
-	fn add_cigars_to_bin(bin: &BinStatisticsCalculationData, cigars: Vec<(u32, Operation)>) {
-		for (len, op) in cigars {
-			match op {
-				Operation::SeqMatch |
-				Operation::SeqMismatch |
-				Operation::AlnMatch => {
-					bin.alignment_matches.fetch_add(len as usize, Relaxed);
-				}
-				Operation::Insertion => {
-					bin.insertions.fetch_add(len as usize, Relaxed);
-				}
-				Operation::Deletion => {
-					bin.deletions.fetch_add(len as usize, Relaxed);
-				}
-				Operation::Skip => {
-					bin.skips.fetch_add(len as usize, Relaxed);
-				}
-				Operation::Soft |
-				Operation::Hard |
-				Operation::Padding => {
-				}
-			}
-		}
-	}
 }
 
 #[cfg(test)]
@@ -254,7 +245,7 @@ mod calculation_map_creation {
 		expected_number_of_bins: u32,
 		expected_length_of_last_bin: u32)
 	{
-		let map = BinnedStatisticsCalculationMap::new(map_start, map_end, bin_config).unwrap();
+		let map = BinnedStatisticsCalculationMap::new(map_start, map_end, bin_config, false).unwrap();
 
 		assert_eq!(expected_start, map.start, "Expected Start at {}, got {}", expected_start, map.start);
 		assert_eq!(expected_end, map.end, "Expected End at {}, got {}", expected_end, map.end);
@@ -356,4 +347,49 @@ mod calculation_map_creation {
 			bin.get_coverage()
 		);
 	}
+}
+
+#[cfg(test)]
+mod calculation_map_weighting {
+	use std::num::NonZeroU32;
+
+	use bam::header::HeaderEntry;
+	use rstest::rstest;
+
+	use crate::statistics::calculation::binned::BinConfig;
+	use crate::statistics::calculation::binned::local::LocalBinnedStatistics;
+	use crate::statistics::calculation::binned::map::BinnedStatisticsCalculationMap;
+
+	#[rstest]
+	#[case(false, "NH:i:4", 1.0)]
+	#[case(true, "NH:i:4", 0.25)]
+	#[case(true, "NH:i:1", 1.0)]
+	#[case(true, "XI:i:0", 1.0)]
+	fn multimapper_weighting_test(
+		#[case] weight_multimappers: bool,
+		#[case] tag: &str,
+		#[case] expected_weight: f64
+	) {
+		let mut header = bam::Header::new();
+		header.push_entry(HeaderEntry::ref_sequence("chr1".to_string(), 1000)).unwrap();
+
+		let sam = format!("read\t0\tchr1\t1\t255\t100M\t*\t0\t0\t*\t*\t{}", tag);
+
+		let mut record = bam::Record::new();
+		record.fill_from_sam(&sam, &header).unwrap();
+
+		let bin_config = BinConfig::LengthOfBins(NonZeroU32::new(1000).unwrap());
+		let map = BinnedStatisticsCalculationMap::new(0, 999, bin_config, weight_multimappers).unwrap();
+
+		let mut local = LocalBinnedStatistics::new();
+		map.add_record_to(&record, &mut local);
+		map.add_local(local);
+
+		let bin = &map.bins[0];
+
+		let expected_weighted_length = bin.get_total_read_length() as f64 * expected_weight;
+
+		assert_eq!(bin.get_read_count(), 1);
+		assert!((bin.get_weighted_total_read_length() - expected_weighted_length).abs() < f64::EPSILON);
+	}
 }
\ No newline at end of file
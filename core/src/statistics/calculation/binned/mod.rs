@@ -1,10 +1,27 @@
 use std::num::NonZeroU32;
 
+use serde_derive::{Deserialize, Serialize};
+
 pub mod data;
+pub mod local;
 pub mod map;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinConfig {
+	#[serde(rename = "n")]
 	NumberOfBins(NonZeroU32),
+	#[serde(rename = "l")]
 	LengthOfBins(NonZeroU32),
 }
+
+impl BinConfig {
+	/// Length of the bins a range of `length` bases is divided into, ranges shorter than the number of bins get single base bins.
+	pub fn get_bin_size(&self, length: NonZeroU32) -> NonZeroU32 {
+		match self {
+			BinConfig::NumberOfBins(number_of_bins) => {
+				NonZeroU32::new((length.get() as f64 / number_of_bins.get() as f64).ceil() as u32).unwrap()
+			}
+			BinConfig::LengthOfBins(length_of_bins) => *length_of_bins
+		}
+	}
+}
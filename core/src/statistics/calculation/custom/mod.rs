@@ -0,0 +1,161 @@
+use std::any::Any;
+use std::fmt::Debug;
+
+use bam::Record;
+
+use crate::statistics::presentation::custom::{CustomMetricPresentationData, MetricValue};
+
+pub mod tag_histogram;
+
+/// A metric that is not part of the built-in statistics, like a histogram of a lab specific tag.
+///
+/// Records are added from multiple threads at once, so the accumulation has to use atomics or locks,
+/// like the built-in calculation data does.
+pub trait MetricCollector: Debug + Send + Sync + 'static {
+	/// Identifies the metric in the presentation data, should be unique within one calculation.
+	fn get_name(&self) -> String;
+
+	/// Called for every record, mapped or not.
+	fn add_record(&self, record: &Record);
+
+	/// Combines the counts of two collectors of the same metric, for example of two input files.
+	fn merge(self, other: Self) -> Self where Self: Sized;
+
+	fn into_presentation(self) -> MetricValue where Self: Sized;
+}
+
+/// Object safe counterpart of [`MetricCollector`], so collectors of different types fit into one registry.
+trait RegisteredMetricCollector: Debug + Send + Sync {
+	fn add_record(&self, record: &Record);
+
+	fn merge(self: Box<Self>, other: Box<dyn RegisteredMetricCollector>) -> Box<dyn RegisteredMetricCollector>;
+
+	fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
+	fn into_presentation_data(self: Box<Self>) -> CustomMetricPresentationData;
+}
+
+impl<T: MetricCollector> RegisteredMetricCollector for T {
+	fn add_record(&self, record: &Record) {
+		MetricCollector::add_record(self, record);
+	}
+
+	fn merge(self: Box<Self>, other: Box<dyn RegisteredMetricCollector>) -> Box<dyn RegisteredMetricCollector> {
+		let other = other.into_any().downcast::<T>()
+			.expect("merged registries have to contain the same collectors in the same order");
+
+		Box::new(MetricCollector::merge(*self, *other))
+	}
+
+	fn into_any(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+
+	fn into_presentation_data(self: Box<Self>) -> CustomMetricPresentationData {
+		let name = self.get_name();
+		CustomMetricPresentationData::new(name, self.into_presentation())
+	}
+}
+
+/// The custom metrics of a calculation, kept in the order they were registered.
+#[derive(Debug, Default)]
+pub struct MetricCollectorRegistry {
+	collectors: Vec<Box<dyn RegisteredMetricCollector>>
+}
+
+impl MetricCollectorRegistry {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	pub fn register<C: MetricCollector>(&mut self, collector: C) {
+		self.collectors.push(Box::new(collector));
+	}
+
+	pub fn len(&self) -> usize {
+		self.collectors.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.collectors.is_empty()
+	}
+
+	pub fn add_record(&self, record: &Record) {
+		for collector in &self.collectors {
+			collector.add_record(record);
+		}
+	}
+
+	/// Both registries have to contain collectors of the same types in the same order.
+	pub fn merge(self, other: Self) -> Self {
+		assert_eq!(self.len(), other.len(), "merged registries have to contain the same number of collectors");
+
+		Self {
+			collectors: self.collectors.into_iter()
+				.zip(other.collectors)
+				.map(|(lhs, rhs)| lhs.merge(rhs))
+				.collect()
+		}
+	}
+
+	pub fn into_presentation_data(self) -> Vec<CustomMetricPresentationData> {
+		self.collectors.into_iter()
+			.map(|collector| collector.into_presentation_data())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod metric_collector_registry_tests {
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	use bam::Record;
+
+	use crate::statistics::calculation::custom::{MetricCollector, MetricCollectorRegistry};
+	use crate::statistics::presentation::custom::MetricValue;
+
+	#[derive(Debug, Default)]
+	struct RecordCounter {
+		count: AtomicU64
+	}
+
+	impl MetricCollector for RecordCounter {
+		fn get_name(&self) -> String {
+			"record_counter".to_string()
+		}
+
+		fn add_record(&self, _record: &Record) {
+			self.count.fetch_add(1, Ordering::Relaxed);
+		}
+
+		fn merge(self, other: Self) -> Self {
+			Self {
+				count: AtomicU64::new(self.count.into_inner() + other.count.into_inner())
+			}
+		}
+
+		fn into_presentation(self) -> MetricValue {
+			MetricValue::Count(self.count.into_inner())
+		}
+	}
+
+	fn registry(record_count: usize) -> MetricCollectorRegistry {
+		let mut registry = MetricCollectorRegistry::new();
+		registry.register(RecordCounter::default());
+
+		for _ in 0..record_count {
+			registry.add_record(&Record::new());
+		}
+
+		registry
+	}
+
+	#[test]
+	fn merge_test() {
+		let presentation_data = registry(2).merge(registry(3)).into_presentation_data();
+
+		assert_eq!(presentation_data.len(), 1);
+		assert_eq!(presentation_data[0].get_name(), "record_counter");
+		assert!(matches!(presentation_data[0].get_value(), MetricValue::Count(5)));
+	}
+}
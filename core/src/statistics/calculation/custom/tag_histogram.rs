@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bam::Record;
+use bam::record::tags::TagValue;
+
+use crate::statistics::calculation::custom::MetricCollector;
+use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
+use crate::statistics::presentation::custom::MetricValue;
+use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+
+/// Counts the values of one tag, array values are counted as missing.
+#[derive(Debug)]
+pub struct TagHistogramCollector {
+	tag: [u8; 2],
+	values: CalculationFrequencyMap<String>,
+	missing: AtomicU64
+}
+
+impl TagHistogramCollector {
+	pub fn new(tag: [u8; 2]) -> Self {
+		Self {
+			tag,
+			values: CalculationFrequencyMap::new(),
+			missing: AtomicU64::new(0)
+		}
+	}
+
+	fn get_value(&self, record: &Record) -> Option<String> {
+		match record.tags().get(&self.tag)? {
+			TagValue::Char(value) => Some((value as char).to_string()),
+			TagValue::Int(value, _) => Some(value.to_string()),
+			TagValue::Float(value) => Some(value.to_string()),
+			TagValue::String(value, _) => Some(String::from_utf8_lossy(value).to_string()),
+			TagValue::IntArray(_) | TagValue::FloatArray(_) => None
+		}
+	}
+}
+
+impl MetricCollector for TagHistogramCollector {
+	fn get_name(&self) -> String {
+		format!("{} Tag", String::from_utf8_lossy(&self.tag))
+	}
+
+	fn add_record(&self, record: &Record) {
+		match self.get_value(record) {
+			Some(value) => self.values.add_entry(value),
+			None => {
+				self.missing.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+	}
+
+	fn merge(self, other: Self) -> Self {
+		Self {
+			tag: self.tag,
+			values: self.values.merge(other.values),
+			missing: AtomicU64::new(self.missing.into_inner() + other.missing.into_inner())
+		}
+	}
+
+	fn into_presentation(self) -> MetricValue {
+		MetricValue::Table(vec![
+			("values".to_string(), MetricValue::Histogram(PresentationFrequencyMap::from(self.values))),
+			("missing".to_string(), MetricValue::Count(self.missing.into_inner()))
+		])
+	}
+}
+
+#[cfg(test)]
+mod tag_histogram_collector_tests {
+	use bam::Record;
+
+	use crate::statistics::calculation::custom::MetricCollector;
+	use crate::statistics::calculation::custom::tag_histogram::TagHistogramCollector;
+	use crate::statistics::presentation::custom::MetricValue;
+
+	fn record(tag_value: Option<&str>) -> Record {
+		let mut record = Record::new();
+
+		if let Some(tag_value) = tag_value {
+			record.tags_mut().push_string(b"XB", tag_value.as_bytes());
+		}
+
+		record
+	}
+
+	#[test]
+	fn tag_values_are_counted_test() {
+		let lhs = TagHistogramCollector::new(*b"XB");
+		let rhs = TagHistogramCollector::new(*b"XB");
+
+		lhs.add_record(&record(Some("AC")));
+		lhs.add_record(&record(None));
+		rhs.add_record(&record(Some("AC")));
+		rhs.add_record(&record(Some("GT")));
+
+		let merged = lhs.merge(rhs);
+
+		assert_eq!(merged.get_name(), "XB Tag");
+
+		match merged.into_presentation() {
+			MetricValue::Table(entries) => {
+				match (&entries[0].1, &entries[1].1) {
+					(MetricValue::Histogram(values), MetricValue::Count(missing)) => {
+						assert_eq!(values.get(&"AC".to_string()), Some(2));
+						assert_eq!(values.get(&"GT".to_string()), Some(1));
+						assert_eq!(*missing, 1);
+					}
+					_ => panic!("expected a histogram and a count")
+				}
+			}
+			_ => panic!("expected a table")
+		}
+	}
+}
@@ -4,6 +4,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLock;
 use std::collections::hash_map::IntoIter;
 
+use crate::statistics::presentation::frequency_map::get_weighted_quantile;
+
 #[derive(Debug)]
 pub struct CalculationFrequencyMap<T>
 	where T: Eq + Hash
@@ -37,6 +39,62 @@ impl<T: Eq + Hash> CalculationFrequencyMap<T> {
 			}
 		}
 	}
+
+	/// Adds the counts of a worker once it is done, taking the write lock at most once.
+	pub fn add_local(&self, local: LocalFrequencyMap<T>) {
+		if local.map.is_empty() {
+			return;
+		}
+
+		let mut map = self.map.write().unwrap();
+
+		for (entry, count) in local.map {
+			map.entry(entry)
+				.or_insert_with(|| AtomicU64::new(0))
+				.fetch_add(count, Ordering::Relaxed);
+		}
+	}
+
+	pub fn merge(self, other: Self) -> Self {
+		let mut map = self.map.into_inner().unwrap();
+
+		for (entry, count) in other {
+			map.entry(entry)
+				.or_insert_with(|| AtomicU64::new(0))
+				.fetch_add(count.into_inner(), Ordering::Relaxed);
+		}
+
+		CalculationFrequencyMap {
+			map: RwLock::new(map)
+		}
+	}
+}
+
+/// Plain counts of a single worker, added to a [`CalculationFrequencyMap`] once all records are counted.
+#[derive(Debug)]
+pub struct LocalFrequencyMap<T>
+	where T: Eq + Hash
+{
+	map: HashMap<T, u64>
+}
+
+impl<T: Eq + Hash> LocalFrequencyMap<T> {
+	pub fn new() -> Self {
+		LocalFrequencyMap {
+			map: Default::default()
+		}
+	}
+
+	#[inline(always)]
+	pub fn add_entry(&mut self, entry: T) {
+		*self.map.entry(entry).or_insert(0) += 1;
+	}
+}
+
+impl<T: Eq + Hash> Default for LocalFrequencyMap<T> {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl<T: Eq + Hash> IntoIterator for CalculationFrequencyMap<T> {
@@ -49,45 +107,35 @@ impl<T: Eq + Hash> IntoIterator for CalculationFrequencyMap<T> {
 }
 
 impl<T: Eq + Hash + Copy + Ord> CalculationFrequencyMap<T> {
+	/// The least frequent entry, the smallest one if several are equally frequent.
 	pub fn get_min_frequency(&self) -> Option<(T, u64)> {
 		let map = self.map.read().unwrap();
 
-		map.iter().reduce(|a, b| {
-			let a_count = a.1.load(Ordering::Relaxed);
-			let b_count = b.1.load(Ordering::Relaxed);
-
-			if a_count >= b_count {
-				a
-			}
-			else {
-				b
-			}
-		}).map(|(entry, count)| (*entry, count.load(Ordering::Relaxed)))
+		map.iter()
+			.map(|(entry, count)| (*entry, count.load(Ordering::Relaxed)))
+			.min_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)))
 	}
 
+	/// The most frequent entry, the smallest one if several are equally frequent.
 	pub fn get_max_frequency(&self) -> Option<(T, u64)> {
 		let map = self.map.read().unwrap();
 
-		map.iter().reduce(|a, b| {
-			let a_count = a.1.load(Ordering::Relaxed);
-			let b_count = b.1.load(Ordering::Relaxed);
-
-			if a_count <= b_count {
-				a
-			}
-			else {
-				b
-			}
-		}).map(|(entry, count)| (*entry, count.load(Ordering::Relaxed)))
+		map.iter()
+			.map(|(entry, count)| (*entry, count.load(Ordering::Relaxed)))
+			.max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
 	}
 
+	/// Median of the frequencies themselves, every distinct entry counts once.
 	pub fn get_median_frequency(&self) -> Option<f64> {
-		let min = self.get_min_frequency();
-		let max = self.get_max_frequency();
+		let map = self.map.read().unwrap();
 
-		let min_max = min.zip(max);
+		let mut frequencies: Vec<(f64, u64)> = map.values()
+			.map(|frequency| (frequency.load(Ordering::Relaxed) as f64, 1))
+			.collect();
 
-		min_max.map(|(min, max)| (min.1 + max.1) as f64 / 2.0)
+		frequencies.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+		get_weighted_quantile(&frequencies, 0.5)
 	}
 
 	pub fn get_mean_frequency(&self) -> Option<f64> {
@@ -105,3 +153,20 @@ impl<T: Eq + Hash + Copy + Ord> CalculationFrequencyMap<T> {
 		Some(mean)
 	}
 }
+
+#[cfg(test)]
+mod calculation_frequency_map_tests {
+	use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
+
+	#[test]
+	fn min_max_frequency_test() {
+		let map = CalculationFrequencyMap::new();
+
+		for entry in [4u32, 7, 7, 7, 2, 2, 9] {
+			map.add_entry(entry);
+		}
+
+		assert_eq!(map.get_min_frequency(), Some((4, 1)));
+		assert_eq!(map.get_max_frequency(), Some((7, 3)));
+	}
+}
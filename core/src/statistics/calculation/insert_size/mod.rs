@@ -0,0 +1,63 @@
+use bam::Record;
+
+use crate::header::read_group_line::ReadGroup;
+use crate::statistics::calculation::frequency_map::{CalculationFrequencyMap, LocalFrequencyMap};
+use crate::util::{get_record_read_group, get_record_t_len};
+
+#[derive(Debug)]
+pub struct InsertSizeCalculationData {
+	pub(crate) per_read_group: Vec<(String, CalculationFrequencyMap<u32>)>,
+	pub(crate) unassigned: CalculationFrequencyMap<u32>
+}
+
+impl InsertSizeCalculationData {
+	pub fn new(read_groups: &[ReadGroup]) -> Self {
+		let per_read_group = read_groups.iter()
+			.map(|read_group| (read_group.identifier.clone(), CalculationFrequencyMap::new()))
+			.collect();
+
+		Self {
+			per_read_group,
+			unassigned: CalculationFrequencyMap::new()
+		}
+	}
+
+	/// Index into `per_read_group`, [`None`] for records without a read group or with one missing in the header.
+	fn get_read_group_index(&self, record: &Record) -> Option<usize> {
+		let read_group = get_record_read_group(record)?;
+
+		self.per_read_group.iter()
+			.position(|(identifier, _)| *identifier == read_group)
+	}
+}
+
+/// The insert sizes counted by a single worker, the read groups are in the same order as in [`InsertSizeCalculationData`].
+#[derive(Debug, Default)]
+pub struct LocalInsertSizeData {
+	pub(crate) per_read_group: Vec<LocalFrequencyMap<u32>>,
+	pub(crate) unassigned: LocalFrequencyMap<u32>
+}
+
+impl LocalInsertSizeData {
+	/// The shared data only provides the read groups.
+	pub fn add_record(&mut self, shared: &InsertSizeCalculationData, record: &Record) {
+		let insert_size = get_record_t_len(record);
+
+		self.per_read_group.resize_with(shared.per_read_group.len(), Default::default);
+
+		let map = match shared.get_read_group_index(record) {
+			Some(index) => &mut self.per_read_group[index],
+			None => &mut self.unassigned
+		};
+
+		map.add_entry(insert_size);
+	}
+
+	pub fn merge_into(self, shared: &InsertSizeCalculationData) {
+		for ((_, map), local) in shared.per_read_group.iter().zip(self.per_read_group) {
+			map.add_local(local);
+		}
+
+		shared.unassigned.add_local(self.unassigned);
+	}
+}
\ No newline at end of file
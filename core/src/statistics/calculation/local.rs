@@ -0,0 +1,185 @@
+use bam::Record;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::statistics::calculation::{CalculationAddMappedRecordError, CalculationAddRecordError, CalculationData};
+use crate::statistics::calculation::assembler::CalculationAssembler;
+use crate::statistics::calculation::assembler::map::LocalAssemblerMap;
+use crate::statistics::calculation::insert_size::LocalInsertSizeData;
+use crate::statistics::calculation::per_reference::PerReferenceCalculation;
+use crate::statistics::calculation::per_reference::local::LocalPerReferenceData;
+use crate::statistics::calculation::unmapped::local::LocalUnmappedData;
+use crate::util::is_insert_size_record;
+
+/// Everything a single worker counts, added to the shared [`CalculationData`] once after the last record.
+#[derive(Debug, Default)]
+pub struct LocalCalculationData {
+    /// Indexed by reference id, references the worker never saw stay empty.
+    pub(crate) per_reference: Vec<LocalPerReferenceData>,
+    pub(crate) unmapped: LocalUnmappedData,
+    pub(crate) insert_size: LocalInsertSizeData,
+    pub(crate) split_read: LocalAssemblerMap
+}
+
+impl LocalCalculationData {
+    /// Everything but the streaming assembler and the custom metrics, see [`CalculationData::add_unordered_record`].
+    /// Sorted references are still added to the shared data, they are calculated one after another anyway.
+    pub fn add_record(&mut self, shared: &CalculationData, record: Record) -> Result<(), CalculationAddRecordError> {
+        let metrics = shared.meta.metrics;
+
+        if record.flag().is_paired() && metrics.split_reads && !shared.split_read.is_streaming() {
+            self.split_read.add_record(shared.meta.assembler_mode, record.clone());
+        }
+
+        if metrics.insert_size && is_insert_size_record(&record) {
+            self.insert_size.add_record(&shared.insert_size, &record);
+        }
+
+        if !record.flag().is_mapped() {
+            if metrics.unmapped {
+                self.unmapped.add_record(&record);
+            }
+            return Ok(());
+        }
+
+        if !metrics.per_reference {
+            return Ok(());
+        }
+
+        let ref_id = shared.get_ref_id(&record).map_err(|source| CalculationAddRecordError::AddMappedError {
+            source
+        })?;
+
+        let per_reference = match &shared.per_reference {
+            PerReferenceCalculation::Unsorted(per_reference) => per_reference,
+            PerReferenceCalculation::Sorted(sorted) => {
+                return sorted.add_record(record, shared.progress_observer.as_ref()).map_err(|source| CalculationAddRecordError::AddMappedError {
+                    source: CalculationAddMappedRecordError::Sorted {
+                        source
+                    }
+                });
+            }
+        };
+
+        self.per_reference.resize_with(per_reference.len(), Default::default);
+        self.per_reference[ref_id].add_record(&per_reference[ref_id], &record);
+
+        Ok(())
+    }
+
+    /// Adds the data of every worker, the references are merged in parallel since each worker may hold bins of every reference.
+    pub fn merge_all_into(local_data: Vec<LocalCalculationData>, shared: &CalculationData) {
+        let mut per_reference: Vec<Vec<LocalPerReferenceData>> = Vec::new();
+
+        for local in local_data {
+            let LocalCalculationData {
+                per_reference: local_per_reference,
+                unmapped,
+                insert_size,
+                split_read
+            } = local;
+
+            per_reference.resize_with(per_reference.len().max(local_per_reference.len()), Vec::new);
+
+            for (ref_id, local_reference) in local_per_reference.into_iter().enumerate() {
+                per_reference[ref_id].push(local_reference);
+            }
+
+            unmapped.merge_into(&shared.unmapped);
+            insert_size.merge_into(&shared.insert_size);
+
+            if let CalculationAssembler::Buffered(map) = &shared.split_read {
+                map.add_local(split_read);
+            }
+        }
+
+        if let PerReferenceCalculation::Unsorted(shared_per_reference) = &shared.per_reference {
+            shared_per_reference.par_iter()
+                .zip(per_reference.into_par_iter())
+                .for_each(|(shared_reference, local_references)| {
+                    for local_reference in local_references {
+                        local_reference.merge_into(shared_reference);
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod local_calculation_tests {
+    use std::convert::TryFrom;
+    use std::num::NonZeroU32;
+
+    use bam::header::HeaderEntry;
+    use bam::Record;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    use crate::header::Header;
+    use crate::statistics::calculation::CalculationData;
+    use crate::statistics::calculation::binned::BinConfig;
+    use crate::statistics::presentation::PresentationData;
+    use crate::statistics::shared::assembler_mode::AssemblerMode;
+
+    fn bam_header() -> bam::Header {
+        let mut header = bam::Header::new();
+        header.push_entry(HeaderEntry::ref_sequence("chr1".to_string(), 1000)).unwrap();
+        header.push_entry(HeaderEntry::ref_sequence("chr2".to_string(), 1000)).unwrap();
+        header
+    }
+
+    fn records(header: &bam::Header) -> Vec<Record> {
+        let sam_lines = [
+            "a\t0\tchr1\t1\t30\t100M\t*\t0\t0\t*\t*\tNH:i:1",
+            "h\t0\tchr1\t200\t60\t100M5S\t*\t0\t0\t*\t*\tNH:i:1",
+            "b\t16\tchr1\t50\t10\t5S120M3I40M2D10M\t*\t0\t0\t*\t*\tNH:i:3",
+            "c\t99\tchr1\t301\t60\t50M500N50M\t=\t901\t700\t*\t*\tNH:i:1",
+            "c\t147\tchr1\t901\t60\t3H50M\t=\t301\t-700\t*\t*\tNH:i:1",
+            "d\t256\tchr2\t10\t0\t20M\t*\t0\t0\t*\t*\tNH:i:3",
+            "e\t0\tchr2\t15\t0\t30M\t*\t0\t0\t*\t*\tNH:i:7",
+            "f\t65\tchr1\t100\t60\t30M\tchr2\t500\t0\t*\t*",
+            "f\t129\tchr2\t500\t60\t40M\tchr1\t100\t0\t*\t*",
+            "g\t4\t*\t0\t0\t*\t*\t0\t0\tACGTAAAAAAAA\tIIIIIIIIIIII"
+        ];
+
+        (0..50).flat_map(|index| {
+            sam_lines.iter().map(move |sam| {
+                let mut record = Record::new();
+                record.fill_from_sam(&format!("{}{}", index, sam), header).unwrap();
+                record
+            })
+        }).collect()
+    }
+
+    fn calculate(records: Vec<Record>, threads: usize) -> PresentationData {
+        let bam_header = bam_header();
+        let header = Header::try_from(&bam_header).unwrap();
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+
+        pool.install(|| {
+            let calculation_data = CalculationData::new(&header, BinConfig::LengthOfBins(NonZeroU32::new(100).unwrap()), true, AssemblerMode::Flags, false)
+                .unwrap()
+                .with_zoom_levels(vec![BinConfig::LengthOfBins(NonZeroU32::new(25).unwrap())]);
+
+            records.into_par_iter().for_each(|record| calculation_data.add_record(record).unwrap());
+
+            PresentationData::try_from(calculation_data).unwrap()
+        })
+    }
+
+    #[test]
+    fn workers_equal_single_worker_test() {
+        let records = records(&bam_header());
+        let mut reversed = records.clone();
+        reversed.reverse();
+
+        let single_worker = calculate(records.clone(), 1);
+
+        assert!(single_worker.get_three_prime_clip_length_map().get_frequencies().next().is_some());
+        assert!(single_worker.get_insert_size_map().get_frequencies().next().is_some());
+        assert!(single_worker.get_per_reference_data().all(|per_reference| per_reference.get_zoom_levels().len() == 1));
+
+        let single_worker = serde_json::to_value(single_worker).unwrap();
+
+        assert_eq!(serde_json::to_value(calculate(records, 4)).unwrap(), single_worker);
+        assert_eq!(serde_json::to_value(calculate(reversed, 4)).unwrap(), single_worker);
+    }
+}
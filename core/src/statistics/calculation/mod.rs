@@ -1,20 +1,40 @@
+use std::cell::RefCell;
 use std::num::{NonZeroU32};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use bam::Record;
 use thiserror::Error;
-use per_reference::PerReferenceCalculationData;
+use thread_local::ThreadLocal;
+use per_reference::{PerReferenceCalculation, PerReferenceCalculationData};
 
+use crate::event::{EventSink, NullEventSink};
+use crate::progress::{CancellationToken, NullProgressObserver, ProgressObserver, RECORD_PROGRESS_INTERVAL};
 use crate::header::Header;
 use binned::BinConfig;
+use crate::statistics::calculation::assembler::CalculationAssembler;
 use crate::statistics::calculation::assembler::map::CalculationAssemblerMap;
+use crate::statistics::calculation::assembler::stream::{AssemblerStreamAddRecordError, CalculationAssemblerStream};
+use crate::statistics::calculation::custom::{MetricCollector, MetricCollectorRegistry};
+use crate::statistics::calculation::insert_size::InsertSizeCalculationData;
 use crate::statistics::calculation::per_reference::PerReferenceCalculationNewError;
+use crate::statistics::calculation::local::LocalCalculationData;
+use crate::statistics::calculation::per_reference::sorted::{SortedPerReferenceAddRecordError, SortedPerReferenceCalculationData};
 use crate::statistics::calculation::unmapped::UnmappedCalculationData;
 use crate::statistics::shared::meta::Meta;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::statistics::shared::metric_selection::MetricSelection;
+use crate::statistics::shared::rebinning::Rebinning;
+use crate::util::length;
 
 pub mod assembler;
+pub mod custom;
 pub mod unmapped;
 pub mod per_reference;
 pub mod binned;
 pub mod frequency_map;
+pub mod insert_size;
+pub mod alignment_categories;
+pub mod local;
 
 #[derive(Error, Debug)]
 pub enum CalculationDataNewError {
@@ -22,7 +42,9 @@ pub enum CalculationDataNewError {
     PerReferenceError {
         reference_name: String,
         source: PerReferenceCalculationNewError
-    }
+    },
+    #[error("the header does not declare the input as coordinate sorted")]
+    NotCoordinateSorted
 }
 
 #[derive(Error, Debug)]
@@ -30,7 +52,13 @@ pub enum CalculationAddRecordError {
     #[error("could not add mapped record")]
     AddMappedError {
         source: CalculationAddMappedRecordError
-    }
+    },
+    #[error("could not assemble the split reads while reading")]
+    AssemblerStream {
+        source: AssemblerStreamAddRecordError
+    },
+    #[error("the calculation was cancelled")]
+    Cancelled
 }
 
 #[derive(Error, Debug)]
@@ -38,66 +66,254 @@ pub enum CalculationAddMappedRecordError {
     #[error("tried to add an unmapped record as a mapped record")]
     NegativeRefID,
     #[error("tried to add a mapped record to a reference that doesnt exist")]
-    InvalidRefID
+    InvalidRefID,
+    #[error("could not add record to the sorted references")]
+    Sorted {
+        source: SortedPerReferenceAddRecordError
+    }
 }
 
 #[derive(Debug)]
 pub struct CalculationData {
     pub(crate) unmapped: UnmappedCalculationData,
-    pub(crate) split_read: CalculationAssemblerMap,
-    pub(crate) per_reference: Vec<PerReferenceCalculationData>,
-    pub(crate) meta: Meta
+    pub(crate) split_read: CalculationAssembler,
+    pub(crate) insert_size: InsertSizeCalculationData,
+    pub(crate) per_reference: PerReferenceCalculation,
+    /// What every thread counted on its own, merged into the fields above before the conversion into presentation data.
+    pub(crate) local_data: ThreadLocal<RefCell<LocalCalculationData>>,
+    pub(crate) custom_metrics: MetricCollectorRegistry,
+    pub(crate) meta: Meta,
+    pub(crate) keep_split_reads: bool,
+    pub(crate) keep_quarantined_reads: bool,
+    pub(crate) skip_mate_pairs: bool,
+    pub(crate) event_sink: Arc<dyn EventSink>,
+    pub(crate) progress_observer: Arc<dyn ProgressObserver>,
+    pub(crate) cancellation_token: CancellationToken,
+    pub(crate) record_count: AtomicU64
 }
 
 impl CalculationData {
-    pub fn new(header: &Header, bin_size: NonZeroU32) -> Result<Self, CalculationDataNewError> {
+    /// With `coordinate_sorted`, the references are calculated one after another, which requires a coordinate sorted header.
+    pub fn new(header: &Header, bin_config: BinConfig, weight_multimappers: bool, assembler_mode: AssemblerMode, coordinate_sorted: bool) -> Result<Self, CalculationDataNewError> {
         let unmapped = UnmappedCalculationData::new();
-        let split_read = CalculationAssemblerMap::new();
-        let mut per_reference = Vec::new();
-
-        for ref_sequence in header.reference_sequences.iter() {
-            let per_reference_data = PerReferenceCalculationData::new(ref_sequence, BinConfig::LengthOfBins(bin_size))
-                .map_err(|source| {
-                    CalculationDataNewError::PerReferenceError {
-                        reference_name: ref_sequence.name.clone(),
-                        source
-                    }
-                })?;
-            per_reference.push(per_reference_data);
+        let split_read = CalculationAssembler::Buffered(CalculationAssemblerMap::new(assembler_mode));
+        let insert_size = InsertSizeCalculationData::new(&header.read_groups);
+        let per_reference = if coordinate_sorted {
+            let is_coordinate_sorted = header.metadata.as_ref()
+                .map(|metadata| metadata.is_coordinate_sorted())
+                .unwrap_or(false);
+
+            if !is_coordinate_sorted {
+                return Err(CalculationDataNewError::NotCoordinateSorted);
+            }
+
+            PerReferenceCalculation::Sorted(Box::new(
+                SortedPerReferenceCalculationData::new(&header.reference_sequences, bin_config, weight_multimappers)
+            ))
         }
+        else {
+            let mut per_reference = Vec::new();
+
+            for ref_sequence in header.reference_sequences.iter() {
+                let per_reference_data = PerReferenceCalculationData::new(ref_sequence, bin_config, weight_multimappers)
+                    .map_err(|source| {
+                        CalculationDataNewError::PerReferenceError {
+                            reference_name: ref_sequence.name.clone(),
+                            source
+                        }
+                    })?;
+                per_reference.push(per_reference_data);
+            }
+
+            PerReferenceCalculation::Unsorted(per_reference)
+        };
+        let longest_reference_length = header.reference_sequences.iter()
+            .map(|ref_sequence| length(0, ref_sequence.length))
+            .max()
+            .unwrap_or(1);
 
         Ok(Self {
             unmapped,
             split_read,
+            insert_size,
             per_reference,
+            local_data: ThreadLocal::new(),
+            custom_metrics: MetricCollectorRegistry::new(),
             meta: Meta {
-                bin_size
-            }
+                bin_size: bin_config.get_bin_size(NonZeroU32::new(longest_reference_length).unwrap()),
+                bin_count: match bin_config {
+                    BinConfig::NumberOfBins(bin_count) => Some(bin_count),
+                    BinConfig::LengthOfBins(_) => None
+                },
+                zoom_levels: Vec::new(),
+                weight_multimappers,
+                assembler_mode,
+                metrics: MetricSelection::all(),
+                rebinning: Rebinning::Exact
+            },
+            keep_split_reads: false,
+            keep_quarantined_reads: false,
+            skip_mate_pairs: false,
+            event_sink: Arc::new(NullEventSink),
+            progress_observer: Arc::new(NullProgressObserver),
+            cancellation_token: CancellationToken::new(),
+            record_count: AtomicU64::new(0)
         })
     }
 
+    /// Keeps the assembled split reads in the presentation data, so they can be exported afterwards.
+    pub fn with_split_read_export(mut self, keep_split_reads: bool) -> Self {
+        self.keep_split_reads = keep_split_reads;
+        self.split_read.keep_reads(self.keep_split_reads, self.keep_quarantined_reads);
+        self
+    }
+
+    /// Keeps the fragments the assembler could not merge in the presentation data, otherwise only their counts per reason remain.
+    pub fn with_quarantine_export(mut self, keep_quarantined_reads: bool) -> Self {
+        self.keep_quarantined_reads = keep_quarantined_reads;
+        self.split_read.keep_reads(self.keep_split_reads, self.keep_quarantined_reads);
+        self
+    }
+
+    /// Leaves templates out of the split read assembly that look like ordinary paired-end mates,
+    /// which are only counted in the split read statistics then.
+    /// Split reads without tags whose two fragments carry opposite template lengths look the same and are skipped as well.
+    pub fn with_mate_pair_skipping(mut self, skip_mate_pairs: bool) -> Self {
+        self.skip_mate_pairs = skip_mate_pairs;
+        self.split_read.skip_mate_pairs(skip_mate_pairs);
+        self
+    }
+
+    /// Records are still counted for disabled metrics, their statistics just stay empty.
+    pub fn with_metrics(mut self, metrics: MetricSelection) -> Self {
+        self.meta.metrics = metrics;
+        self
+    }
+
+    /// Compresses the gap and read length maps during the conversion to the presentation data.
+    pub fn with_rebinning(mut self, rebinning: Rebinning) -> Self {
+        self.meta.rebinning = rebinning;
+        self
+    }
+
+    /// Bins every reference additionally with each config, so the bins can be viewed at several resolutions.
+    pub fn with_zoom_levels(mut self, zoom_levels: Vec<BinConfig>) -> Self {
+        let weight_multimappers = self.meta.weight_multimappers;
+
+        self.per_reference = match self.per_reference {
+            PerReferenceCalculation::Unsorted(per_reference) => PerReferenceCalculation::Unsorted(
+                per_reference.into_iter()
+                    .map(|data| data.with_zoom_levels(&zoom_levels, weight_multimappers)
+                        .expect("the bins of a reference always start at zero, so creating them can't fail"))
+                    .collect()
+            ),
+            PerReferenceCalculation::Sorted(mut sorted) => {
+                sorted.zoom_levels = zoom_levels.clone();
+                PerReferenceCalculation::Sorted(sorted)
+            }
+        };
+        self.meta.zoom_levels = zoom_levels;
+        self
+    }
+
+    /// Adds a collector to the custom metrics, it receives every record regardless of the metric selection.
+    pub fn with_metric_collector<C: MetricCollector>(mut self, collector: C) -> Self {
+        self.custom_metrics.register(collector);
+        self
+    }
+
+    /// Replaces every registered collector.
+    pub fn with_metric_collectors(mut self, custom_metrics: MetricCollectorRegistry) -> Self {
+        self.custom_metrics = custom_metrics;
+        self
+    }
+
+    /// Assembles the split reads while reading instead of buffering every fragment until the end,
+    /// only for input that keeps the records of a read name together, see [`Metadata::is_grouped_by_name`](crate::header::metadata_line::Metadata::is_grouped_by_name).
+    ///
+    /// The records then have to pass [`CalculationData::add_ordered_record`] in file order.
+    /// A read name that shows up again after another name fails to be added instead of splitting its template.
+    pub fn with_streaming_assembly(mut self) -> Self {
+        let mut stream = CalculationAssemblerStream::new(self.meta.assembler_mode);
+        stream.keep_reads(self.keep_split_reads, self.keep_quarantined_reads);
+        stream.skip_mate_pairs(self.skip_mate_pairs);
+
+        self.split_read = CalculationAssembler::Streaming(Box::new(stream));
+        self
+    }
+
+    /// Sorted references are calculated one after another, which only works if the records are added in file order.
+    /// With streaming assembly, only [`CalculationData::add_ordered_record`] needs the records in file order.
+    pub fn requires_ordered_input(&self) -> bool {
+        self.per_reference.is_sorted()
+    }
+
+    /// Whether streaming assembly was enabled, see [`CalculationData::with_streaming_assembly`].
+    pub fn is_assembling_while_reading(&self) -> bool {
+        self.split_read.is_streaming()
+    }
+
+    /// Receives the events of the conversion into presentation data, which are dropped otherwise.
+    pub fn with_event_sink(mut self, event_sink: Arc<dyn EventSink>) -> Self {
+        self.event_sink = event_sink;
+        self
+    }
+
+    pub fn with_progress_observer(mut self, progress_observer: Arc<dyn ProgressObserver>) -> Self {
+        self.progress_observer = progress_observer;
+        self
+    }
+
+    /// Once the token is cancelled, adding records and converting into presentation data fail.
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Can be called in parallel, unless the references are sorted or streaming assembly was enabled.
+    ///
+    /// Every thread counts into its own [`LocalCalculationData`] without any lock, they are merged once during the conversion
+    /// into presentation data. A thread keeps the bins and zoom levels of every reference it added a record to until then,
+    /// so the memory grows with the number of threads.
     pub fn add_record(&self, record: Record) -> Result<(),CalculationAddRecordError> {
-        let is_mapped = record.flag().is_mapped();
-        let is_split = record.flag().is_paired();
+        self.add_ordered_record(&record)?;
+        self.add_unordered_record(record)
+    }
 
-        if is_split {
-            self.split_read.add_record(record.clone());
+    /// Adds the record to the streaming assembler, which needs the records in file order, without it nothing happens.
+    /// Every record also has to be passed to [`CalculationData::add_unordered_record`], which can happen in parallel.
+    pub fn add_ordered_record(&self, record: &Record) -> Result<(),CalculationAddRecordError> {
+        if let CalculationAssembler::Streaming(stream) = &self.split_read {
+            if record.flag().is_paired() && self.meta.metrics.split_reads {
+                stream.add_record(record.clone()).map_err(|source| CalculationAddRecordError::AssemblerStream {
+                    source
+                })?;
+            }
         }
 
-        if is_mapped {
-            self.add_mapped_record(record).map_err(|source|
-                CalculationAddRecordError::AddMappedError {
-                    source
-                }
-            )
+        Ok(())
+    }
+
+    /// Everything but the streaming assembler, the records can be added in any order unless the references are sorted.
+    pub fn add_unordered_record(&self, record: Record) -> Result<(),CalculationAddRecordError> {
+        if self.cancellation_token.is_cancelled() {
+            return Err(CalculationAddRecordError::Cancelled);
         }
-        else {
-            self.unmapped.add_record(record);
-            Ok(())
+
+        let record_count = self.record_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if record_count.is_multiple_of(RECORD_PROGRESS_INTERVAL) {
+            self.progress_observer.records_processed(record_count);
         }
+
+        self.custom_metrics.add_record(&record);
+
+        self.local_data.get_or_default()
+            .borrow_mut()
+            .add_record(self, record)
     }
 
-    fn add_mapped_record(&self, record: Record) -> Result<(),CalculationAddMappedRecordError> {
+    pub(crate) fn get_ref_id(&self, record: &Record) -> Result<usize, CalculationAddMappedRecordError> {
         let ref_id = record.ref_id();
 
         if ref_id < 0 {
@@ -110,9 +326,15 @@ impl CalculationData {
             return Err(CalculationAddMappedRecordError::InvalidRefID);
         }
 
-        let per_reference = &self.per_reference[ref_id as usize];
+        Ok(ref_id)
+    }
 
-        per_reference.add_record(record);
-        Ok(())
+    /// Adds what the workers counted on their own, has to happen once after the last record and before any statistic is read.
+    pub(crate) fn merge_local_data(&mut self) {
+        let local_data = std::mem::take(&mut self.local_data).into_iter()
+            .map(RefCell::into_inner)
+            .collect();
+
+        LocalCalculationData::merge_all_into(local_data, self);
     }
-}
\ No newline at end of file
+}
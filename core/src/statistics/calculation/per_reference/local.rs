@@ -0,0 +1,227 @@
+use bam::Record;
+
+use crate::statistics::calculation::alignment_categories::LocalAlignmentCategories;
+use crate::statistics::calculation::binned::local::LocalBinnedStatistics;
+use crate::statistics::calculation::frequency_map::LocalFrequencyMap;
+use crate::statistics::calculation::per_reference::PerReferenceCalculationData;
+use crate::statistics::calculation::per_reference::single_read::SingleReadPerReferenceCalculationData;
+use crate::util::{get_record_clipping, get_record_hit_count, get_record_length_on_reference, get_record_length_sequence, get_record_mapping_quality, get_record_t_len, is_insert_size_record};
+
+/// The reads of one reference counted by a single worker, added to a [`SingleReadPerReferenceCalculationData`] once it is done.
+#[derive(Debug, Default)]
+pub struct LocalSingleReadData {
+    pub(crate) quality_map: LocalFrequencyMap<u8>,
+    pub(crate) read_length_on_reference_map: LocalFrequencyMap<u32>,
+    pub(crate) read_length_sequence_map: LocalFrequencyMap<u32>,
+    pub(crate) five_prime_clip_length_map: LocalFrequencyMap<u32>,
+    pub(crate) three_prime_clip_length_map: LocalFrequencyMap<u32>,
+    pub(crate) binned_statistics: LocalBinnedStatistics
+}
+
+impl LocalSingleReadData {
+    /// The shared data only provides the bins.
+    pub fn add_record(&mut self, shared: &SingleReadPerReferenceCalculationData, record: &Record) {
+        let clipping = get_record_clipping(record);
+        let is_reverse_strand = record.flag().is_reverse_strand();
+
+        self.quality_map.add_entry(get_record_mapping_quality(record));
+        self.read_length_on_reference_map.add_entry(get_record_length_on_reference(record));
+        self.read_length_sequence_map.add_entry(get_record_length_sequence(record));
+        self.five_prime_clip_length_map.add_entry(clipping.get_five_prime_length(is_reverse_strand));
+        self.three_prime_clip_length_map.add_entry(clipping.get_three_prime_length(is_reverse_strand));
+        shared.binned_statistics.add_record_to(record, &mut self.binned_statistics);
+    }
+
+    pub fn merge_into(self, shared: &SingleReadPerReferenceCalculationData) {
+        shared.quality_map.add_local(self.quality_map);
+        shared.read_length_on_reference_map.add_local(self.read_length_on_reference_map);
+        shared.read_length_sequence_map.add_local(self.read_length_sequence_map);
+        shared.five_prime_clip_length_map.add_local(self.five_prime_clip_length_map);
+        shared.three_prime_clip_length_map.add_local(self.three_prime_clip_length_map);
+        shared.binned_statistics.add_local(self.binned_statistics);
+    }
+}
+
+/// One reference as counted by a single worker, added to a [`PerReferenceCalculationData`] once it is done.
+///
+/// The bins and zoom levels grow up to the last bin the worker touched, so every worker may hold a full copy of them.
+#[derive(Debug, Default)]
+pub struct LocalPerReferenceData {
+    pub(crate) read_length_map: LocalFrequencyMap<u32>,
+    pub(crate) insert_size_map: LocalFrequencyMap<u32>,
+    pub(crate) hit_count_map: LocalFrequencyMap<u32>,
+    pub(crate) alignment_categories: LocalAlignmentCategories,
+    pub(crate) single_read_data: LocalSingleReadData,
+    pub(crate) split_read_data: LocalSingleReadData,
+    pub(crate) zoom_levels: Vec<LocalBinnedStatistics>
+}
+
+impl LocalPerReferenceData {
+    pub fn add_record(&mut self, shared: &PerReferenceCalculationData, record: &Record) {
+        self.read_length_map.add_entry(get_record_length_on_reference(record));
+        self.alignment_categories.add_flags(record.flag());
+
+        if is_insert_size_record(record) {
+            self.insert_size_map.add_entry(get_record_t_len(record));
+        }
+
+        let is_primary = !record.flag().is_secondary() && !record.flag().is_supplementary();
+
+        if is_primary {
+            if let Some(hit_count) = get_record_hit_count(record) {
+                self.hit_count_map.add_entry(hit_count);
+            }
+        }
+
+        self.zoom_levels.resize_with(shared.zoom_levels.len(), LocalBinnedStatistics::new);
+
+        for (zoom_level, local) in shared.zoom_levels.iter().zip(self.zoom_levels.iter_mut()) {
+            zoom_level.add_record_to(record, local);
+        }
+
+        if record.flag().is_paired() {
+            self.split_read_data.add_record(&shared.split_read_data, record);
+        }
+        else {
+            self.single_read_data.add_record(&shared.single_read_data, record);
+        }
+    }
+
+    pub fn merge_into(self, shared: &PerReferenceCalculationData) {
+        shared.read_length_map.add_local(self.read_length_map);
+        shared.insert_size_map.add_local(self.insert_size_map);
+        shared.hit_count_map.add_local(self.hit_count_map);
+        shared.alignment_categories.add_local(self.alignment_categories);
+        self.single_read_data.merge_into(&shared.single_read_data);
+        self.split_read_data.merge_into(&shared.split_read_data);
+
+        for (zoom_level, local) in shared.zoom_levels.iter().zip(self.zoom_levels) {
+            zoom_level.add_local(local);
+        }
+    }
+}
+
+#[cfg(test)]
+mod local_per_reference_tests {
+    use std::num::NonZeroU32;
+
+    use bam::header::HeaderEntry;
+    use bam::Record;
+
+    use crate::header::reference_sequence_line::ReferenceSequence;
+    use crate::header::reference_sequence_line::molecule_topology::MoleculeTopology;
+    use crate::statistics::calculation::binned::BinConfig;
+    use crate::statistics::calculation::per_reference::PerReferenceCalculationData;
+    use crate::statistics::calculation::per_reference::local::LocalPerReferenceData;
+    use crate::statistics::presentation::binned::map::BinnedStatisticsPresentationMap;
+    use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+    use crate::statistics::presentation::per_reference::PerReferencePresentationData;
+
+    fn per_reference_data() -> Vec<PerReferenceCalculationData> {
+        ["chr1", "chr2"].iter().map(|name| {
+            let reference_sequence = ReferenceSequence {
+                name: name.to_string(),
+                length: 1000,
+                alternate_locus: None,
+                alternate_names: None,
+                assembly: None,
+                description: None,
+                checksum: None,
+                species: None,
+                topology: MoleculeTopology::Linear,
+                uri: None
+            };
+
+            PerReferenceCalculationData::new(&reference_sequence, BinConfig::LengthOfBins(NonZeroU32::new(100).unwrap()), true).unwrap()
+                .with_zoom_levels(&[BinConfig::LengthOfBins(NonZeroU32::new(25).unwrap())], true)
+                .unwrap()
+        }).collect()
+    }
+
+    fn records() -> Vec<Record> {
+        let mut header = bam::Header::new();
+        header.push_entry(HeaderEntry::ref_sequence("chr1".to_string(), 1000)).unwrap();
+        header.push_entry(HeaderEntry::ref_sequence("chr2".to_string(), 1000)).unwrap();
+
+        let sam_lines = [
+            "a\t0\tchr1\t1\t30\t100M\t*\t0\t0\t*\t*\tNH:i:1",
+            "b\t16\tchr1\t50\t10\t5S120M3I40M2D10M\t*\t0\t0\t*\t*\tNH:i:3",
+            "g\t0\tchr1\t400\t30\t7S100M4S\t*\t0\t0\t*\t*\tNH:i:1",
+            "c\t99\tchr1\t301\t60\t50M500N50M\t=\t901\t700\t*\t*\tNH:i:1",
+            "c\t147\tchr1\t901\t60\t3H50M\t=\t301\t-700\t*\t*\tNH:i:1",
+            "d\t256\tchr2\t10\t0\t20M\t*\t0\t0\t*\t*\tNH:i:2",
+            "e\t1024\tchr1\t700\t30\t80M\t*\t0\t0\t*\t*",
+            "f\t2048\tchr2\t990\t30\t10M5S\t*\t0\t0\t*\t*\tNH:i:1"
+        ];
+
+        sam_lines.iter().map(|sam| {
+            let mut record = Record::new();
+            record.fill_from_sam(sam, &header).unwrap();
+            record
+        }).collect()
+    }
+
+    fn sorted_frequencies<T: Copy + Ord + std::hash::Hash>(map: &PresentationFrequencyMap<T>) -> Vec<(T, u64)> {
+        let mut frequencies: Vec<(T, u64)> = map.get_frequencies().map(|(entry, count)| (*entry, count)).collect();
+        frequencies.sort();
+        frequencies
+    }
+
+    fn summary(per_reference: &PerReferencePresentationData) -> String {
+        let bins = |binned: &BinnedStatisticsPresentationMap| -> Vec<String> {
+            binned.get_bins()
+                .map(|bin| format!("{} {} {} {:?} {:?}", bin.get_read_count(), bin.get_total_read_length(), bin.get_weighted_total_read_length(), bin.get_cigar_operations(), bin.get_alignment_categories()))
+                .collect()
+        };
+
+        format!(
+            "{:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+            sorted_frequencies(&per_reference.get_read_length_on_reference_map()),
+            sorted_frequencies(&per_reference.get_read_length_sequence_map()),
+            sorted_frequencies(&per_reference.get_quality_frequency()),
+            sorted_frequencies(&per_reference.get_five_prime_clip_length_map()),
+            sorted_frequencies(&per_reference.get_three_prime_clip_length_map()),
+            sorted_frequencies(per_reference.get_insert_size_map()),
+            sorted_frequencies(per_reference.get_hit_count_map()),
+            per_reference.get_alignment_categories(),
+            bins(&per_reference.get_binned_statistics()),
+            per_reference.get_zoom_levels().iter().map(bins).collect::<Vec<_>>()
+        )
+    }
+
+    #[test]
+    fn split_workers_equal_single_worker_test() {
+        let single = per_reference_data();
+        let split = per_reference_data();
+        let mut single_worker: Vec<LocalPerReferenceData> = single.iter().map(|_| Default::default()).collect();
+        let mut workers: Vec<Vec<LocalPerReferenceData>> = (0..2).map(|_| split.iter().map(|_| Default::default()).collect()).collect();
+
+        for (index, record) in records().into_iter().enumerate() {
+            let ref_id = record.ref_id() as usize;
+
+            single_worker[ref_id].add_record(&single[ref_id], &record);
+            workers[index % 2][ref_id].add_record(&split[ref_id], &record);
+        }
+
+        for (local, shared) in single_worker.into_iter().zip(single.iter()) {
+            local.merge_into(shared);
+        }
+
+        for worker in workers {
+            for (local, shared) in worker.into_iter().zip(split.iter()) {
+                local.merge_into(shared);
+            }
+        }
+
+        let single: Vec<PerReferencePresentationData> = single.into_iter().map(PerReferencePresentationData::calculate_from_data).collect();
+        let split: Vec<PerReferencePresentationData> = split.into_iter().map(PerReferencePresentationData::calculate_from_data).collect();
+
+        assert!(single[0].get_three_prime_clip_length_map().get_frequencies().next().is_some());
+        assert!(single[0].get_insert_size_map().get_frequencies().next().is_some());
+        assert_eq!(single[0].get_zoom_levels().len(), 1);
+
+        for (single, split) in single.iter().zip(split.iter()) {
+            assert_eq!(summary(single), summary(split));
+        }
+    }
+}
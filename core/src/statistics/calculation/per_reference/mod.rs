@@ -1,12 +1,17 @@
+pub mod local;
 pub mod single_read;
+pub mod sorted;
 
 use crate::statistics::calculation::per_reference::single_read::{SingleReadPerReferenceCalculationData, SingleReadPerReferenceCalculationNewError};
 use crate::header::reference_sequence_line::ReferenceSequence;
 use bam::Record;
 use thiserror::Error;
 use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
-use crate::util::{get_record_length_on_reference};
 use crate::statistics::calculation::binned::BinConfig;
+use crate::statistics::calculation::binned::map::{BinnedStatisticsCalculationMap, BinnedStatisticsCalculationMapNewError};
+use crate::statistics::calculation::alignment_categories::AlignmentCategoryCalculationData;
+use crate::statistics::calculation::per_reference::local::LocalPerReferenceData;
+use crate::statistics::calculation::per_reference::sorted::SortedPerReferenceCalculationData;
 
 #[derive(Error, Debug)]
 pub enum PerReferenceCalculationNewError {
@@ -17,6 +22,10 @@ pub enum PerReferenceCalculationNewError {
     #[error("could not create single read calculation data")]
     SingleRead {
         source: SingleReadPerReferenceCalculationNewError
+    },
+    #[error("could not create the binned statistics of a zoom level")]
+    ZoomLevel {
+        source: BinnedStatisticsCalculationMapNewError
     }
 }
 
@@ -25,22 +34,30 @@ pub struct PerReferenceCalculationData {
     pub(crate) reference_name: String,
     pub(crate) reference_length: u32,
     pub(crate) read_length_map: CalculationFrequencyMap<u32>,
+    pub(crate) insert_size_map: CalculationFrequencyMap<u32>,
+    pub(crate) hit_count_map: CalculationFrequencyMap<u32>,
+    pub(crate) alignment_categories: AlignmentCategoryCalculationData,
     pub(crate) single_read_data: SingleReadPerReferenceCalculationData,
-    pub(crate) split_read_data: SingleReadPerReferenceCalculationData
+    pub(crate) split_read_data: SingleReadPerReferenceCalculationData,
+    /// Single and split reads binned with the additional resolutions, in the order they were requested.
+    pub(crate) zoom_levels: Vec<BinnedStatisticsCalculationMap>
 }
 
 impl PerReferenceCalculationData {
-    pub fn new(ref_line: &ReferenceSequence, bin_config: BinConfig) -> Result<Self, PerReferenceCalculationNewError> {
+    pub fn new(ref_line: &ReferenceSequence, bin_config: BinConfig, weight_multimappers: bool) -> Result<Self, PerReferenceCalculationNewError> {
         let reference_name = ref_line.name.clone();
         let reference_length = ref_line.length;
         let read_length_map = CalculationFrequencyMap::new();
-        let single_read_data = SingleReadPerReferenceCalculationData::new(ref_line, bin_config)
+        let insert_size_map = CalculationFrequencyMap::new();
+        let hit_count_map = CalculationFrequencyMap::new();
+        let alignment_categories = AlignmentCategoryCalculationData::new();
+        let single_read_data = SingleReadPerReferenceCalculationData::new(ref_line, bin_config, weight_multimappers)
             .map_err(|source|  {
                 PerReferenceCalculationNewError::SingleRead {
                     source
                 }
             })?;
-        let split_read_data = SingleReadPerReferenceCalculationData::new(ref_line, bin_config)
+        let split_read_data = SingleReadPerReferenceCalculationData::new(ref_line, bin_config, weight_multimappers)
             .map_err(|source| {
                 PerReferenceCalculationNewError::SplitRead {
                     source
@@ -51,22 +68,54 @@ impl PerReferenceCalculationData {
             reference_name,
             reference_length,
             read_length_map,
+            insert_size_map,
+            hit_count_map,
+            alignment_categories,
             single_read_data,
-            split_read_data
+            split_read_data,
+            zoom_levels: Vec::new()
         })
     }
 
+    /// Bins the reference additionally with every config, so the bins can be viewed at several resolutions.
+    pub fn with_zoom_levels(mut self, zoom_levels: &[BinConfig], weight_multimappers: bool) -> Result<Self, PerReferenceCalculationNewError> {
+        self.zoom_levels = zoom_levels.iter()
+            .map(|bin_config| BinnedStatisticsCalculationMap::new(0, self.reference_length, *bin_config, weight_multimappers))
+            .collect::<Result<_, _>>()
+            .map_err(|source| PerReferenceCalculationNewError::ZoomLevel { source })?;
+
+        Ok(self)
+    }
+
+    #[deprecated(note = "count into a LocalPerReferenceData and add it with merge_into")]
     pub fn add_record(&self, record: Record) {
-        let read_length = get_record_length_on_reference(&record);
+        let mut local = LocalPerReferenceData::default();
+        local.add_record(self, &record);
+        local.merge_into(self);
+    }
+}
 
-        self.read_length_map.add_entry(read_length);
+/// Holds every reference at once, unless the input is processed one reference after another.
+#[derive(Debug)]
+pub enum PerReferenceCalculation {
+    Unsorted(Vec<PerReferenceCalculationData>),
+    Sorted(Box<SortedPerReferenceCalculationData>)
+}
 
-        let split = record.flag().is_paired();
-        return if split {
-            self.split_read_data.add_record(record);
-        }
-        else {
-            self.single_read_data.add_record(record);
+impl PerReferenceCalculation {
+    pub fn len(&self) -> usize {
+        match self {
+            PerReferenceCalculation::Unsorted(per_reference) => per_reference.len(),
+            PerReferenceCalculation::Sorted(sorted) => sorted.reference_sequences.len()
         }
     }
-}
\ No newline at end of file
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sorted references require the records to be added in file order.
+    pub fn is_sorted(&self) -> bool {
+        matches!(self, PerReferenceCalculation::Sorted(_))
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::Mutex;
+
+use bam::Record;
+use thiserror::Error;
+use crate::header::reference_sequence_line::ReferenceSequence;
+use crate::statistics::calculation::binned::BinConfig;
+use crate::progress::ProgressObserver;
+use crate::statistics::calculation::per_reference::{PerReferenceCalculationData, PerReferenceCalculationNewError};
+use crate::statistics::calculation::per_reference::local::LocalPerReferenceData;
+use crate::statistics::presentation::per_reference::PerReferencePresentationData;
+
+#[derive(Error, Debug)]
+pub enum SortedPerReferenceAddRecordError {
+    #[error("the input is not coordinate sorted, reference {current} follows reference {previous}")]
+    UnsortedReference {
+        previous: String,
+        current: String
+    },
+    #[error("the input is not coordinate sorted, position {current} follows position {previous} on reference {reference_name}")]
+    UnsortedPosition {
+        reference_name: String,
+        previous: i32,
+        current: i32
+    },
+    #[error("could not create per reference calculation data for reference: {reference_name}")]
+    PerReferenceError {
+        reference_name: String,
+        source: PerReferenceCalculationNewError
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SortedPerReferenceState {
+    /// The reference that is currently read, counted into its own plain statistics until it is finished.
+    pub(crate) active: Option<(PerReferenceCalculationData, LocalPerReferenceData)>,
+    pub(crate) last_start: i32,
+    pub(crate) finished: Vec<PerReferencePresentationData>
+}
+
+/// Per reference statistics of coordinate sorted input, calculated one reference after another.
+///
+/// Only the reference that is currently read is kept as calculation data, every reference before it
+/// is already converted to presentation data. The records have to be added in file order.
+#[derive(Debug)]
+pub struct SortedPerReferenceCalculationData {
+    pub(crate) reference_sequences: Vec<ReferenceSequence>,
+    pub(crate) bin_config: BinConfig,
+    pub(crate) zoom_levels: Vec<BinConfig>,
+    pub(crate) weight_multimappers: bool,
+    pub(crate) state: Mutex<SortedPerReferenceState>
+}
+
+impl SortedPerReferenceCalculationData {
+    pub fn new(reference_sequences: &[ReferenceSequence], bin_config: BinConfig, weight_multimappers: bool) -> Self {
+        Self {
+            reference_sequences: reference_sequences.to_vec(),
+            bin_config,
+            zoom_levels: Vec::new(),
+            weight_multimappers,
+            state: Mutex::new(SortedPerReferenceState::default())
+        }
+    }
+
+    /// The caller has to make sure that the reference id is valid.
+    pub fn add_record(&self, record: Record, progress_observer: &dyn ProgressObserver) -> Result<(), SortedPerReferenceAddRecordError> {
+        let ref_id = record.ref_id() as usize;
+        let mut state = self.state.lock().unwrap();
+
+        let active_ref_id = state.finished.len();
+
+        if ref_id < active_ref_id || (ref_id == active_ref_id && record.start() < state.last_start) {
+            return Err(self.get_sort_error(&state, ref_id, record.start()));
+        }
+
+        while state.finished.len() < ref_id {
+            self.finish_active(&mut state, progress_observer)?;
+        }
+
+        if state.active.is_none() {
+            state.active = Some((self.new_calculation_data(ref_id)?, LocalPerReferenceData::default()));
+        }
+
+        state.last_start = record.start();
+
+        let (active, local) = state.active.as_mut().unwrap();
+        local.add_record(active, &record);
+
+        Ok(())
+    }
+
+    fn get_sort_error(&self, state: &SortedPerReferenceState, ref_id: usize, start: i32) -> SortedPerReferenceAddRecordError {
+        let active_reference_name = self.reference_sequences[state.finished.len()].name.clone();
+
+        if ref_id < state.finished.len() {
+            SortedPerReferenceAddRecordError::UnsortedReference {
+                previous: active_reference_name,
+                current: self.reference_sequences[ref_id].name.clone()
+            }
+        }
+        else {
+            SortedPerReferenceAddRecordError::UnsortedPosition {
+                reference_name: active_reference_name,
+                previous: state.last_start,
+                current: start
+            }
+        }
+    }
+
+    /// References without any record are finished as empty statistics.
+    fn finish_active(&self, state: &mut SortedPerReferenceState, progress_observer: &dyn ProgressObserver) -> Result<(), SortedPerReferenceAddRecordError> {
+        let active = match state.active.take() {
+            Some((active, local)) => {
+                local.merge_into(&active);
+                active
+            }
+            None => self.new_calculation_data(state.finished.len())?
+        };
+
+        let ref_id = state.finished.len();
+
+        state.finished.push(PerReferencePresentationData::calculate_from_data(active));
+        state.last_start = 0;
+
+        progress_observer.reference_finalized(ref_id, &self.reference_sequences[ref_id].name);
+
+        Ok(())
+    }
+
+    fn new_calculation_data(&self, ref_id: usize) -> Result<PerReferenceCalculationData, SortedPerReferenceAddRecordError> {
+        let ref_sequence = &self.reference_sequences[ref_id];
+
+        PerReferenceCalculationData::new(ref_sequence, self.bin_config, self.weight_multimappers)
+            .and_then(|data| data.with_zoom_levels(&self.zoom_levels, self.weight_multimappers))
+            .map_err(|source| SortedPerReferenceAddRecordError::PerReferenceError {
+                reference_name: ref_sequence.name.clone(),
+                source
+            })
+    }
+
+    /// Finishes the active reference and every reference after it.
+    pub fn into_presentation_data(self, progress_observer: &dyn ProgressObserver) -> Result<Vec<PerReferencePresentationData>, SortedPerReferenceAddRecordError> {
+        let mut state = self.state.lock().unwrap();
+
+        while state.finished.len() < self.reference_sequences.len() {
+            self.finish_active(&mut state, progress_observer)?;
+        }
+
+        Ok(std::mem::take(&mut state.finished))
+    }
+}
+
+#[cfg(test)]
+mod sorted_per_reference_calculation_tests {
+    use std::num::NonZeroU32;
+
+    use bam::Record;
+    use crate::header::reference_sequence_line::molecule_topology::MoleculeTopology;
+    use crate::progress::NullProgressObserver;
+    use crate::header::reference_sequence_line::ReferenceSequence;
+    use crate::statistics::calculation::binned::BinConfig;
+    use crate::statistics::calculation::per_reference::sorted::{SortedPerReferenceAddRecordError, SortedPerReferenceCalculationData};
+
+    fn reference_sequence(name: &str) -> ReferenceSequence {
+        ReferenceSequence {
+            name: name.to_string(),
+            length: 1000,
+            alternate_locus: None,
+            alternate_names: None,
+            assembly: None,
+            description: None,
+            checksum: None,
+            species: None,
+            topology: MoleculeTopology::Linear,
+            uri: None
+        }
+    }
+
+    fn record(ref_id: i32, start: i32) -> Record {
+        let mut record = Record::new();
+        record.set_name(b"read".iter().cloned());
+        record.set_ref_id(ref_id);
+        record.set_start(start);
+        record.set_cigar(b"50M".iter().cloned()).unwrap();
+        record
+    }
+
+    fn sorted_data() -> SortedPerReferenceCalculationData {
+        let reference_sequences = vec![reference_sequence("chr1"), reference_sequence("chr2"), reference_sequence("chr3")];
+        SortedPerReferenceCalculationData::new(&reference_sequences, BinConfig::LengthOfBins(NonZeroU32::new(100).unwrap()), false)
+    }
+
+    #[test]
+    fn references_are_finished_in_order_test() {
+        let data = sorted_data();
+
+        data.add_record(record(0, 100), &NullProgressObserver).unwrap();
+        data.add_record(record(0, 200), &NullProgressObserver).unwrap();
+        data.add_record(record(2, 50), &NullProgressObserver).unwrap();
+
+        assert_eq!(data.state.lock().unwrap().finished.len(), 2);
+
+        let per_reference = data.into_presentation_data(&NullProgressObserver).unwrap();
+        let record_counts: Vec<u64> = per_reference.iter()
+            .map(|reference| reference.get_read_length_on_reference_map().get_frequency_sum())
+            .collect();
+
+        assert_eq!(record_counts, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn unsorted_position_test() {
+        let data = sorted_data();
+
+        data.add_record(record(0, 200), &NullProgressObserver).unwrap();
+
+        assert!(matches!(
+            data.add_record(record(0, 100), &NullProgressObserver),
+            Err(SortedPerReferenceAddRecordError::UnsortedPosition { previous: 200, current: 100, .. })
+        ));
+    }
+
+    #[test]
+    fn unsorted_reference_test() {
+        let data = sorted_data();
+
+        data.add_record(record(1, 200), &NullProgressObserver).unwrap();
+
+        assert!(matches!(
+            data.add_record(record(0, 300), &NullProgressObserver),
+            Err(SortedPerReferenceAddRecordError::UnsortedReference { .. })
+        ));
+    }
+}
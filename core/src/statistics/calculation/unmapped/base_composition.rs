@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bam::record::sequence::Sequence;
+
+#[derive(Debug, Default)]
+pub struct BaseCompositionCalculationData {
+	pub(crate) a: AtomicU64,
+	pub(crate) c: AtomicU64,
+	pub(crate) g: AtomicU64,
+	pub(crate) t: AtomicU64,
+	pub(crate) n: AtomicU64
+}
+
+impl BaseCompositionCalculationData {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Adds counts in the order of [`get_base_counts`].
+	pub fn add_counts(&self, counts: [u64; 5]) {
+		self.a.fetch_add(counts[0], Ordering::Relaxed);
+		self.c.fetch_add(counts[1], Ordering::Relaxed);
+		self.g.fetch_add(counts[2], Ordering::Relaxed);
+		self.t.fetch_add(counts[3], Ordering::Relaxed);
+		self.n.fetch_add(counts[4], Ordering::Relaxed);
+	}
+}
+
+/// The number of A, C, G, T and N bases, every base other than A, C, G and T is counted as N.
+pub fn get_base_counts(sequence: &Sequence) -> [u64; 5] {
+	let mut counts = [0u64; 5];
+
+	for i in 0..sequence.len() {
+		let index = match sequence.at_acgtn_only(i) {
+			b'A' => 0,
+			b'C' => 1,
+			b'G' => 2,
+			b'T' => 3,
+			_ => 4
+		};
+
+		counts[index] += 1;
+	}
+
+	counts
+}
@@ -0,0 +1,85 @@
+use std::sync::atomic::Ordering;
+
+use bam::Record;
+
+use crate::statistics::calculation::frequency_map::LocalFrequencyMap;
+use crate::statistics::calculation::unmapped::UnmappedCalculationData;
+use crate::statistics::calculation::unmapped::base_composition::get_base_counts;
+use crate::statistics::calculation::unmapped::single_read::UnmappedSingleReadCalculationData;
+use crate::util::{get_record_length_sequence, get_record_mean_base_quality, get_longest_homopolymer, is_low_complexity_sequence};
+
+/// The unmapped reads counted by a single worker, added to an [`UnmappedSingleReadCalculationData`] once it is done.
+#[derive(Debug, Default)]
+pub struct LocalUnmappedSingleReadData {
+	pub(crate) read_length_map: LocalFrequencyMap<u32>,
+	pub(crate) base_counts: [u64; 5],
+	pub(crate) mean_quality_map: LocalFrequencyMap<u8>,
+	pub(crate) longest_homopolymer_map: LocalFrequencyMap<u32>,
+	pub(crate) low_complexity_count: u64,
+	pub(crate) mate_reference_map: LocalFrequencyMap<i32>,
+	pub(crate) mate_unmapped_count: u64
+}
+
+impl LocalUnmappedSingleReadData {
+	pub fn add_record(&mut self, record: &Record) {
+		self.read_length_map.add_entry(get_record_length_sequence(record));
+
+		let sequence = record.sequence().to_vec_acgtn_only();
+
+		for (count, base_count) in self.base_counts.iter_mut().zip(get_base_counts(record.sequence())) {
+			*count += base_count;
+		}
+
+		self.longest_homopolymer_map.add_entry(get_longest_homopolymer(&sequence));
+
+		if is_low_complexity_sequence(&sequence) {
+			self.low_complexity_count += 1;
+		}
+
+		if let Some(mean_quality) = get_record_mean_base_quality(record) {
+			self.mean_quality_map.add_entry(mean_quality);
+		}
+
+		if record.flag().is_paired() {
+			if record.flag().mate_is_mapped() && record.mate_ref_id() >= 0 {
+				self.mate_reference_map.add_entry(record.mate_ref_id());
+			}
+			else {
+				self.mate_unmapped_count += 1;
+			}
+		}
+	}
+
+	pub fn merge_into(self, shared: &UnmappedSingleReadCalculationData) {
+		shared.read_length_map.add_local(self.read_length_map);
+		shared.base_composition.add_counts(self.base_counts);
+		shared.mean_quality_map.add_local(self.mean_quality_map);
+		shared.longest_homopolymer_map.add_local(self.longest_homopolymer_map);
+		shared.low_complexity_count.fetch_add(self.low_complexity_count, Ordering::Relaxed);
+		shared.mate_reference_map.add_local(self.mate_reference_map);
+		shared.mate_unmapped_count.fetch_add(self.mate_unmapped_count, Ordering::Relaxed);
+	}
+}
+
+/// Single and split reads counted by a single worker, see [`LocalUnmappedSingleReadData`].
+#[derive(Debug, Default)]
+pub struct LocalUnmappedData {
+	pub(crate) single_read: LocalUnmappedSingleReadData,
+	pub(crate) split_read: LocalUnmappedSingleReadData
+}
+
+impl LocalUnmappedData {
+	pub fn add_record(&mut self, record: &Record) {
+		if record.flag().is_paired() {
+			self.split_read.add_record(record);
+		}
+		else {
+			self.single_read.add_record(record);
+		}
+	}
+
+	pub fn merge_into(self, shared: &UnmappedCalculationData) {
+		self.single_read.merge_into(&shared.single_read);
+		self.split_read.merge_into(&shared.split_read);
+	}
+}
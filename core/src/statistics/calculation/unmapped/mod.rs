@@ -1,6 +1,9 @@
+pub mod base_composition;
+pub mod local;
 pub mod single_read;
 
 use bam::Record;
+use crate::statistics::calculation::unmapped::local::LocalUnmappedData;
 use crate::statistics::calculation::unmapped::single_read::UnmappedSingleReadCalculationData;
 
 #[derive(Debug)]
@@ -17,12 +20,10 @@ impl UnmappedCalculationData {
 		}
 	}
 
+	#[deprecated(note = "count into a LocalUnmappedData and add it with merge_into")]
 	pub fn add_record(&self, record: Record) {
-		if record.flag().is_paired() {
-			self.split_read.add_record(record);
-		}
-		else {
-			self.single_read.add_record(record);
-		}
+		let mut local = LocalUnmappedData::default();
+		local.add_record(&record);
+		local.merge_into(self);
 	}
 }
\ No newline at end of file
@@ -1,21 +1,88 @@
+use std::sync::atomic::AtomicU64;
+
 use bam::Record;
 use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
-use crate::util::{get_record_length_sequence};
+use crate::statistics::calculation::unmapped::base_composition::BaseCompositionCalculationData;
+use crate::statistics::calculation::unmapped::local::LocalUnmappedSingleReadData;
 
 #[derive(Debug)]
 pub struct UnmappedSingleReadCalculationData {
 	pub(crate) read_length_map: CalculationFrequencyMap<u32>,
+	pub(crate) base_composition: BaseCompositionCalculationData,
+	pub(crate) mean_quality_map: CalculationFrequencyMap<u8>,
+	pub(crate) longest_homopolymer_map: CalculationFrequencyMap<u32>,
+	pub(crate) low_complexity_count: AtomicU64,
+	pub(crate) mate_reference_map: CalculationFrequencyMap<i32>,
+	pub(crate) mate_unmapped_count: AtomicU64
 }
 
 impl UnmappedSingleReadCalculationData {
 	pub fn new() -> Self {
 		Self {
-			read_length_map: CalculationFrequencyMap::new()
+			read_length_map: CalculationFrequencyMap::new(),
+			base_composition: BaseCompositionCalculationData::new(),
+			mean_quality_map: CalculationFrequencyMap::new(),
+			longest_homopolymer_map: CalculationFrequencyMap::new(),
+			low_complexity_count: AtomicU64::new(0),
+			mate_reference_map: CalculationFrequencyMap::new(),
+			mate_unmapped_count: AtomicU64::new(0)
 		}
 	}
 
+	#[deprecated(note = "count into a LocalUnmappedSingleReadData and add it with merge_into")]
 	pub fn add_record(&self, record: Record) {
-		let read_length = get_record_length_sequence(&record);
-		self.read_length_map.add_entry(read_length);
+		let mut local = LocalUnmappedSingleReadData::default();
+		local.add_record(&record);
+		local.merge_into(self);
+	}
+}
+
+impl Default for UnmappedSingleReadCalculationData {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod unmapped_single_read_calculation_tests {
+	use std::sync::atomic::Ordering;
+
+	use bam::Record;
+	use crate::statistics::calculation::unmapped::local::LocalUnmappedSingleReadData;
+	use crate::statistics::calculation::unmapped::single_read::UnmappedSingleReadCalculationData;
+	use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+
+	fn record(sequence: &[u8], quality: u8, flag: u16, mate_ref_id: i32) -> Record {
+		let mut record = Record::new();
+		record.set_name(b"read".iter().cloned());
+		record.set_flag(flag);
+		record.set_mate_ref_id(mate_ref_id);
+		record.set_seq_qual(sequence.iter().cloned(), sequence.iter().map(|_| quality)).unwrap();
+		record
+	}
+
+	fn sorted_frequencies<T: Copy + Ord + std::hash::Hash>(map: PresentationFrequencyMap<T>) -> Vec<(T, u64)> {
+		let mut frequencies: Vec<(T, u64)> = map.into_iter().collect();
+		frequencies.sort();
+		frequencies
+	}
+
+	#[test]
+	fn add_record_test() {
+		let data = UnmappedSingleReadCalculationData::new();
+		let mut local = LocalUnmappedSingleReadData::default();
+
+		local.add_record(&record(b"ACGTACGTAN", 30, 0x1 | 0x4, 2));
+		local.add_record(&record(b"AAAAAAAAAAAAAAAAAAAC", 30, 0x1 | 0x4 | 0x8, -1));
+		local.add_record(&record(b"GGCCTTAACG", 20, 0x1 | 0x4, 2));
+		local.add_record(&record(b"CATGCATGCA", 30, 0x1 | 0x4, 5));
+		local.merge_into(&data);
+
+		assert_eq!(data.base_composition.a.load(Ordering::Relaxed), 27);
+		assert_eq!(data.base_composition.n.load(Ordering::Relaxed), 1);
+		assert_eq!(data.low_complexity_count.load(Ordering::Relaxed), 1);
+		assert_eq!(data.mate_unmapped_count.load(Ordering::Relaxed), 1);
+		assert_eq!(sorted_frequencies(data.mate_reference_map.into()), vec![(2, 2), (5, 1)]);
+		assert_eq!(sorted_frequencies(data.mean_quality_map.into()), vec![(20, 1), (30, 3)]);
 	}
-}
\ No newline at end of file
+}
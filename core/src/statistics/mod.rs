@@ -1,3 +1,4 @@
 pub mod presentation;
 pub mod calculation;
 pub mod shared;
+pub mod builder;
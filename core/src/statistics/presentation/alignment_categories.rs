@@ -0,0 +1,70 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::statistics::calculation::alignment_categories::AlignmentCategoryCalculationData;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct AlignmentCategories {
+	#[serde(rename = "p")]
+	pub primary: u64,
+	#[serde(rename = "se")]
+	pub secondary: u64,
+	#[serde(rename = "su")]
+	pub supplementary: u64,
+	#[serde(rename = "du")]
+	pub duplicates: u64,
+	#[serde(rename = "qc")]
+	pub qc_failed: u64
+}
+
+impl AlignmentCategories {
+	pub fn merge(lhs: &Self, rhs: &Self) -> Self {
+		Self {
+			primary: lhs.primary + rhs.primary,
+			secondary: lhs.secondary + rhs.secondary,
+			supplementary: lhs.supplementary + rhs.supplementary,
+			duplicates: lhs.duplicates + rhs.duplicates,
+			qc_failed: lhs.qc_failed + rhs.qc_failed
+		}
+	}
+
+	/// Every alignment is exactly one of primary, secondary or supplementary.
+	pub fn get_total(&self) -> u64 {
+		self.primary + self.secondary + self.supplementary
+	}
+
+	pub fn get_secondary_rate(&self) -> f64 {
+		Self::rate(self.secondary, self.get_total())
+	}
+
+	pub fn get_supplementary_rate(&self) -> f64 {
+		Self::rate(self.supplementary, self.get_total())
+	}
+
+	pub fn get_duplicate_rate(&self) -> f64 {
+		Self::rate(self.duplicates, self.get_total())
+	}
+
+	pub fn get_qc_failed_rate(&self) -> f64 {
+		Self::rate(self.qc_failed, self.get_total())
+	}
+
+	fn rate(count: u64, total: u64) -> f64 {
+		if total == 0 {
+			return 0.0;
+		}
+
+		count as f64 / total as f64
+	}
+}
+
+impl From<AlignmentCategoryCalculationData> for AlignmentCategories {
+	fn from(value: AlignmentCategoryCalculationData) -> Self {
+		Self {
+			primary: value.primary.into_inner() as u64,
+			secondary: value.secondary.into_inner() as u64,
+			supplementary: value.supplementary.into_inner() as u64,
+			duplicates: value.duplicates.into_inner() as u64,
+			qc_failed: value.qc_failed.into_inner() as u64
+		}
+	}
+}
\ No newline at end of file
@@ -1,17 +1,18 @@
-use std::collections::HashMap;
-use std::sync::Mutex;
-use bam::Record;
+use std::sync::atomic::{AtomicU64, Ordering};
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
-use crate::statistics::calculation::assembler::map::CalculationAssemblerMap;
+use crate::statistics::calculation::assembler::map::{AssemblerShards, CalculationAssemblerMap};
 use crate::statistics::presentation::assembler::PresentationAssembler;
 use crate::statistics::presentation::record::PresentationRecord;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::util::is_mate_pair;
 
 pub struct PresentationAssemblerCollection {
     pub(crate) normals: Vec<PresentationAssembler>,
 	pub(crate) supplementaries: Vec<PresentationAssembler>,
 	pub(crate) secondaries: Vec<PresentationAssembler>,
-	pub(crate) duplicates: Vec<PresentationAssembler>
+	pub(crate) duplicates: Vec<PresentationAssembler>,
+	pub(crate) skipped_mate_pairs: u64
 }
 
 impl PresentationAssemblerCollection {
@@ -31,13 +32,28 @@ impl PresentationAssemblerCollection {
 		&self.duplicates
 	}
 
-	fn unwrap_mutex_map(mutex_map: HashMap<u32, Mutex<Vec<Record>>>) -> Vec<PresentationAssembler> {
-		mutex_map.into_par_iter()
-			.map(|(_, value)| {
-				let values = value.into_inner().unwrap();
-				let values: Vec<PresentationRecord> = values.into_par_iter().map(|item| item.into()).collect();
+	pub fn get_skipped_mate_pairs(&self) -> u64 {
+		self.skipped_mate_pairs
+	}
+
+	/// Creates one assembler per read name and segment.
+	/// With `skip_mate_pairs`, ordinary paired-end mates are left out and counted in `skipped_mate_pairs`.
+	fn unwrap_shards(shards: AssemblerShards, mode: AssemblerMode, skip_mate_pairs: bool, skipped_mate_pairs: &AtomicU64) -> Vec<PresentationAssembler> {
+		shards.into_par_iter()
+			.flat_map_iter(|shard| shard.into_inner().unwrap().into_iter())
+			.filter(|(_, values)| {
+				let is_skipped = skip_mate_pairs && is_mate_pair(values);
+
+				if is_skipped {
+					skipped_mate_pairs.fetch_add(1, Ordering::Relaxed);
+				}
+
+				!is_skipped
+			})
+			.map(|(_, values)| {
+				let values: Vec<PresentationRecord> = values.into_iter().map(|item| item.into()).collect();
 
-				values.into()
+				PresentationAssembler::new(values, mode)
 			})
 			.collect()
 	}
@@ -45,24 +61,22 @@ impl PresentationAssemblerCollection {
 
 impl From<CalculationAssemblerMap> for PresentationAssemblerCollection {
 	fn from(value: CalculationAssemblerMap) -> Self {
-		let normals = value.normals.into_inner().unwrap();
-		let supplementaries = value.supplementaries.into_inner().unwrap();
-		let secondaries = value.secondaries.into_inner().unwrap();
-		let duplicates = value.duplicates.into_inner().unwrap();
+		let skipped_mate_pairs = AtomicU64::new(0);
 
-		let normals = Self::unwrap_mutex_map(normals);
+		let normals = Self::unwrap_shards(value.normals, value.mode, value.skip_mate_pairs, &skipped_mate_pairs);
 
-		let supplementaries = Self::unwrap_mutex_map(supplementaries);
+		let supplementaries = Self::unwrap_shards(value.supplementaries, value.mode, value.skip_mate_pairs, &skipped_mate_pairs);
 
-		let secondaries = Self::unwrap_mutex_map(secondaries);
+		let secondaries = Self::unwrap_shards(value.secondaries, value.mode, value.skip_mate_pairs, &skipped_mate_pairs);
 
-		let duplicates = Self::unwrap_mutex_map(duplicates);
+		let duplicates = Self::unwrap_shards(value.duplicates, value.mode, value.skip_mate_pairs, &skipped_mate_pairs);
 
 		Self {
 			normals,
 			supplementaries,
 			secondaries,
-			duplicates
+			duplicates,
+			skipped_mate_pairs: skipped_mate_pairs.into_inner()
 		}
 	}
 }
\ No newline at end of file
@@ -1,15 +1,24 @@
 use crate::statistics::presentation::record::PresentationRecord;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
 
 pub mod collection;
 
 pub struct PresentationAssembler {
-    pub(crate) associated_records: Vec<PresentationRecord>
+    pub(crate) associated_records: Vec<PresentationRecord>,
+    pub(crate) mode: AssemblerMode
 }
 
-impl From<Vec<PresentationRecord>> for PresentationAssembler {
-    fn from(value: Vec<PresentationRecord>) -> Self {
+impl PresentationAssembler {
+    pub fn new(associated_records: Vec<PresentationRecord>, mode: AssemblerMode) -> Self {
         Self {
-            associated_records: value
+            associated_records,
+            mode
         }
     }
+}
+
+impl From<Vec<PresentationRecord>> for PresentationAssembler {
+    fn from(value: Vec<PresentationRecord>) -> Self {
+        Self::new(value, AssemblerMode::Flags)
+    }
 }
\ No newline at end of file
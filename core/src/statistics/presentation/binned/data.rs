@@ -3,6 +3,7 @@ use crate::util::length;
 
 use serde_derive::{Deserialize, Serialize};
 use crate::statistics::presentation::cigar_operations::CigarOperations;
+use crate::statistics::presentation::alignment_categories::AlignmentCategories;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BinStatisticsPresentationData {
@@ -14,8 +15,15 @@ pub struct BinStatisticsPresentationData {
 	read_count: u64,
 	#[serde(rename = "cta")]
 	total_read_length: u64,
+	/// `None` in statistics written before multimappers could be weighted.
+	#[serde(rename = "wta", default)]
+	weighted_total_read_length: Option<f64>,
 	#[serde(rename = "co")]
-	cigar_operations: CigarOperations
+	cigar_operations: CigarOperations,
+	#[serde(rename = "ac", default)]
+	alignment_categories: AlignmentCategories,
+	#[serde(rename = "bp", default)]
+	breakpoints: u64
 }
 
 impl BinStatisticsPresentationData {
@@ -28,7 +36,10 @@ impl BinStatisticsPresentationData {
 			end: lhs.end,
 			read_count: lhs.read_count + rhs.read_count,
 			total_read_length: lhs.total_read_length + rhs.total_read_length,
-			cigar_operations: CigarOperations::merge(&lhs.cigar_operations, &rhs.cigar_operations)
+			weighted_total_read_length: Some(lhs.get_weighted_total_read_length() + rhs.get_weighted_total_read_length()),
+			cigar_operations: CigarOperations::merge(&lhs.cigar_operations, &rhs.cigar_operations),
+			alignment_categories: AlignmentCategories::merge(&lhs.alignment_categories, &rhs.alignment_categories),
+			breakpoints: lhs.breakpoints + rhs.breakpoints
 		})
 	}
 
@@ -57,14 +68,42 @@ impl BinStatisticsPresentationData {
 		self.total_read_length
 	}
 
+	/// Equals the total read length unless multimappers were weighted by `1/NH`.
+	#[inline(always)]
+	pub fn get_weighted_total_read_length(&self) -> f64 {
+		self.weighted_total_read_length.unwrap_or(self.total_read_length as f64)
+	}
+
 	#[inline(always)]
 	pub fn get_cigar_operations(&self) -> CigarOperations {
 		self.cigar_operations
 	}
 
+	#[inline(always)]
+	pub fn get_alignment_categories(&self) -> AlignmentCategories {
+		self.alignment_categories
+	}
+
+	/// Number of split read fragment ends inside the bin that continue in another fragment.
+	#[inline(always)]
+	pub fn get_breakpoints(&self) -> u64 {
+		self.breakpoints
+	}
+
+	pub(crate) fn add_breakpoints(&mut self, count: u64) {
+		self.breakpoints += count;
+	}
+
+	/// Bases aligned with `M`, `=` or `X` per base of the bin, which leaves out deletions and skipped introns like `samtools depth`.
+	#[inline(always)]
+	pub fn get_depth(&self) -> f64 {
+		self.cigar_operations.alignment_matches as f64 / self.get_length() as f64
+	}
+
+	/// Reference span of the reads per base of the bin, deletions and skipped introns count as covered.
 	#[inline(always)]
 	pub fn get_coverage(&self) -> f64 {
-		self.total_read_length as f64 / self.get_length() as f64
+		self.get_weighted_total_read_length() / self.get_length() as f64
 	}
 }
 
@@ -75,24 +114,35 @@ impl Default for BinStatisticsPresentationData {
 			end: 0,
 			read_count: Default::default(),
 			total_read_length: Default::default(),
+			weighted_total_read_length: Default::default(),
 			cigar_operations: Default::default(),
+			alignment_categories: Default::default(),
+			breakpoints: Default::default(),
 		}
 	}
 }
 
 impl From<BinStatisticsCalculationData> for BinStatisticsPresentationData {
 	fn from(data: BinStatisticsCalculationData) -> Self {
+		let weighted_total_read_length = data.get_weighted_total_read_length();
+
 		BinStatisticsPresentationData {
 			start: data.start,
 			end: data.end,
 			read_count: data.read_count.into_inner() as u64,
 			total_read_length: data.total_read_length.into_inner() as u64,
+			weighted_total_read_length: Some(weighted_total_read_length),
 			cigar_operations: CigarOperations {
 				alignment_matches: data.alignment_matches.into_inner() as u64,
 				insertions: data.insertions.into_inner() as u64,
 				deletions: data.deletions.into_inner() as u64,
 				skips: data.skips.into_inner() as u64,
-			}
+				soft_clips: data.soft_clips.into_inner() as u64,
+				hard_clips: data.hard_clips.into_inner() as u64,
+			},
+			alignment_categories: data.alignment_categories.into(),
+			// The split reads are only assembled during the conversion, see `BinnedStatisticsPresentationMap::add_breakpoints`.
+			breakpoints: 0
 		}
 	}
 }
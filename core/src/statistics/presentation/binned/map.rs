@@ -2,6 +2,7 @@ use std::num::NonZeroU32;
 
 use crate::statistics::presentation::binned::data::BinStatisticsPresentationData;
 use crate::statistics::calculation::binned::map::BinnedStatisticsCalculationMap;
+use crate::util::calculate_bin;
 
 use serde_derive::{Deserialize, Serialize};
 
@@ -67,6 +68,19 @@ impl BinnedStatisticsPresentationMap {
 	pub fn get_bins(&self) -> impl Iterator<Item = &BinStatisticsPresentationData> {
 		self.bins.iter()
 	}
+
+	/// Breakpoints are only known once the split reads are assembled, which happens during the conversion,
+	/// so they are added to the presentation bins instead of being counted in the calculation map.
+	/// A breakpoint at the exclusive end of the last fragment is counted in the last bin.
+	pub(crate) fn add_breakpoints(&mut self, position: u32, count: u64) {
+		if position < self.start || position > self.end + 1 { return; }
+
+		if let Ok(coordinates) = calculate_bin(self.start, self.bin_size, position.min(self.end)) {
+			if let Some(bin) = self.bins.get_mut(coordinates.bin_index as usize) {
+				bin.add_breakpoints(count);
+			}
+		}
+	}
 }
 
 impl From<BinnedStatisticsCalculationMap> for BinnedStatisticsPresentationMap {
@@ -79,3 +93,26 @@ impl From<BinnedStatisticsCalculationMap> for BinnedStatisticsPresentationMap {
 		}
 	}
 }
+
+#[cfg(test)]
+mod presentation_map_breakpoints {
+	use std::num::NonZeroU32;
+
+	use crate::statistics::calculation::binned::BinConfig;
+	use crate::statistics::calculation::binned::map::BinnedStatisticsCalculationMap;
+	use crate::statistics::presentation::binned::map::BinnedStatisticsPresentationMap;
+
+	#[test]
+	fn add_breakpoint_test() {
+		let calculation_map = BinnedStatisticsCalculationMap::new(0, 99, BinConfig::LengthOfBins(NonZeroU32::new(10).unwrap()), false).unwrap();
+		let mut map: BinnedStatisticsPresentationMap = calculation_map.into();
+
+		for (position, count) in [(0, 1), (9, 2), (15, 1), (100, 3), (200, 1)] {
+			map.add_breakpoints(position, count);
+		}
+
+		let breakpoints: Vec<u64> = map.get_bins().map(|bin| bin.get_breakpoints()).collect();
+
+		assert_eq!(breakpoints, vec![3, 1, 0, 0, 0, 0, 0, 0, 0, 3]);
+	}
+}
@@ -9,7 +9,11 @@ pub struct CigarOperations {
 	#[serde(rename = "d")]
 	pub deletions: u64,
 	#[serde(rename = "sk")]
-	pub skips: u64
+	pub skips: u64,
+	#[serde(rename = "sc", default)]
+	pub soft_clips: u64,
+	#[serde(rename = "hc", default)]
+	pub hard_clips: u64
 }
 
 impl CigarOperations {
@@ -18,7 +22,13 @@ impl CigarOperations {
 			alignment_matches: lhs.alignment_matches + rhs.alignment_matches,
 			insertions: lhs.insertions + rhs.insertions,
 			deletions: lhs.deletions + rhs.deletions,
-			skips: lhs.skips + rhs.skips
+			skips: lhs.skips + rhs.skips,
+			soft_clips: lhs.soft_clips + rhs.soft_clips,
+			hard_clips: lhs.hard_clips + rhs.hard_clips
 		}
 	}
+
+	pub fn get_clipped_bases(&self) -> u64 {
+		self.soft_clips + self.hard_clips
+	}
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+
+/// The presentation form of a custom metric, limited to values every output format can hold.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum MetricValue {
+	Count(u64),
+	Number(f64),
+	Text(String),
+	Histogram(PresentationFrequencyMap<String>),
+	/// Named sub values, in the order the collector created them.
+	Table(Vec<(String, MetricValue)>)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CustomMetricPresentationData {
+	#[serde(rename = "n")]
+	name: String,
+	#[serde(rename = "v")]
+	value: MetricValue
+}
+
+impl CustomMetricPresentationData {
+	pub fn new(name: String, value: MetricValue) -> Self {
+		Self {
+			name,
+			value
+		}
+	}
+
+	pub fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn get_value(&self) -> &MetricValue {
+		&self.value
+	}
+}
@@ -4,9 +4,10 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
 use std::collections::hash_map::IntoIter;
-use std::iter::Sum;
 use num_traits::{AsPrimitive};
 
+use crate::statistics::shared::rebinning::Rebinning;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PresentationFrequencyMap<T>
 	where T: Eq + Hash
@@ -75,31 +76,165 @@ impl<T: Eq + Hash + Copy + Into<u64>> PresentationFrequencyMap<T> {
 	}
 }
 
-impl<T: Eq + Hash + Copy + Ord + AsPrimitive<f64>> PresentationFrequencyMap<T> {
-	pub fn get_median_entry(&self) -> Option<f64> {
-		let min = self.get_min_entry();
-		let max = self.get_max_entry();
+impl<T> PresentationFrequencyMap<T>
+	where T: Eq + Hash + Copy + AsPrimitive<f64>, f64: AsPrimitive<T>
+{
+	/// Merges the entries that fall into the same bin, see [`Rebinning`] for the error bounds.
+	pub fn rebin(&mut self, rebinning: Rebinning) {
+		if rebinning.is_exact() {
+			return;
+		}
 
-		let min_max = min.zip(max)?;
+		let mut map = HashMap::with_capacity(self.map.len());
 
-		let min = min_max.0.0.as_();
-		let max = min_max.1.0.as_();
+		for (entry, frequency) in self.map.drain() {
+			let representative: T = rebinning.get_representative(entry.as_()).as_();
+			*map.entry(representative).or_insert(0) += frequency;
+		}
 
-		let median = (min + max) / 2.0;
-		Some(median)
+		self.map = map;
 	}
 }
 
-impl<T: Eq + Hash + Copy + AsPrimitive<f64> + Sum> PresentationFrequencyMap<T> {
+/// Frequency weighted statistics, every entry counts as often as it was added.
+impl<T: Eq + Hash + Copy + AsPrimitive<f64>> PresentationFrequencyMap<T> {
+	fn get_sorted_entries(&self) -> Vec<(f64, u64)> {
+		let mut entries: Vec<(f64, u64)> = self.map.iter()
+			.filter(|(_, frequency)| **frequency > 0)
+			.map(|(entry, frequency)| (entry.as_(), *frequency))
+			.collect();
+
+		entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		entries
+	}
+
+	/// Sorts the entries once for all quantiles, `None` if the map is empty.
+	pub fn get_quantiles(&self, quantiles: &[f64]) -> Option<Vec<f64>> {
+		let entries = self.get_sorted_entries();
+
+		quantiles.iter()
+			.map(|quantile| get_weighted_quantile(&entries, *quantile))
+			.collect()
+	}
+
+	/// `quantile` between 0 and 1, values between two entries are interpolated linearly.
+	pub fn get_quantile(&self, quantile: f64) -> Option<f64> {
+		get_weighted_quantile(&self.get_sorted_entries(), quantile)
+	}
+
+	/// `percentile` between 0 and 100.
+	pub fn get_percentile(&self, percentile: f64) -> Option<f64> {
+		self.get_quantile(percentile / 100.0)
+	}
+
+	pub fn get_median(&self) -> Option<f64> {
+		self.get_quantile(0.5)
+	}
+
+	pub fn get_interquartile_range(&self) -> Option<f64> {
+		self.get_quantiles(&[0.25, 0.75])
+			.map(|quartiles| quartiles[1] - quartiles[0])
+	}
+
+	pub fn get_mean(&self) -> Option<f64> {
+		let count = self.get_frequency_sum();
+
+		if count == 0 {
+			return None;
+		}
+
+		let sum: f64 = self.map.iter()
+			.map(|(entry, frequency)| entry.as_() * *frequency as f64)
+			.sum();
+
+		Some(sum / count as f64)
+	}
+
+	/// Used to be the midpoint between the smallest and the largest entry, which ignored the frequencies.
+	#[deprecated(note = "use get_median, which weights every entry by its frequency")]
+	pub fn get_median_entry(&self) -> Option<f64> {
+		self.get_median()
+	}
+
+	/// Used to be the mean of the distinct entries, which ignored the frequencies.
+	#[deprecated(note = "use get_mean, which weights every entry by its frequency")]
 	pub fn get_mean_entry(&self) -> Option<f64> {
-		let sum: T = self.map.keys()
-			.map(|item| *item)
+		self.get_mean()
+	}
+
+	/// Population variance, the entries are the complete data and not a sample of it.
+	pub fn get_variance(&self) -> Option<f64> {
+		let mean = self.get_mean()?;
+		let count = self.get_frequency_sum();
+
+		let squared_deviations: f64 = self.map.iter()
+			.map(|(entry, frequency)| (entry.as_() - mean).powi(2) * *frequency as f64)
 			.sum();
 
-		let sum: f64 = sum.as_();
-		let count = self.map.len();
+		Some(squared_deviations / count as f64)
+	}
 
-		Some(sum / count as f64)
+	pub fn get_standard_deviation(&self) -> Option<f64> {
+		self.get_variance().map(f64::sqrt)
+	}
+
+	pub fn get_median_absolute_deviation(&self) -> Option<f64> {
+		let median = self.get_median()?;
+
+		let mut deviations: Vec<(f64, u64)> = self.get_sorted_entries().into_iter()
+			.map(|(entry, frequency)| ((entry - median).abs(), frequency))
+			.collect();
+
+		deviations.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+		get_weighted_quantile(&deviations, 0.5)
+	}
+}
+
+impl<T: Eq + Hash + Copy + Ord> PresentationFrequencyMap<T> {
+	/// The most frequent entry, the smallest one if several are equally frequent.
+	pub fn get_mode(&self) -> Option<T> {
+		self.map.iter()
+			.max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+			.map(|(entry, _)| *entry)
+	}
+}
+
+/// Quantile of `(value, frequency)` pairs sorted by value, interpolated linearly between the closest ranks.
+///
+/// A quantile of 0.5 is the median, which is the mean of both middle values for an even count.
+pub fn get_weighted_quantile(sorted_entries: &[(f64, u64)], quantile: f64) -> Option<f64> {
+	let total: u64 = sorted_entries.iter().map(|(_, frequency)| *frequency).sum();
+
+	if total == 0 {
+		return None;
+	}
+
+	let position = (total - 1) as f64 * quantile.clamp(0.0, 1.0);
+	let lower_rank = position.floor() as u64;
+	let upper_rank = position.ceil() as u64;
+
+	let mut lower = None;
+	let mut covered = 0u64;
+
+	for (value, frequency) in sorted_entries {
+		covered += frequency;
+
+		if lower.is_none() && covered > lower_rank {
+			lower = Some(*value);
+		}
+		if covered > upper_rank {
+			let lower = lower.unwrap();
+			return Some(lower + (value - lower) * (position - lower_rank as f64));
+		}
+	}
+
+	None
+}
+
+impl<T: Eq + Hash> Default for PresentationFrequencyMap<T> {
+	fn default() -> Self {
+		Self::new()
 	}
 }
 
@@ -113,35 +248,29 @@ impl<T: Eq + Hash> IntoIterator for PresentationFrequencyMap<T> {
 }
 
 impl<T: Eq + Hash + Copy + Ord> PresentationFrequencyMap<T> {
+	/// The least frequent entry, the smallest one if several are equally frequent.
 	pub fn get_min_frequency(&self) -> Option<(T, u64)> {
-		self.map.iter().reduce(|a, b| {
-			if a.1 >= b.1 {
-				a
-			}
-			else {
-				b
-			}
-		}).map(|(entry, count)| (*entry, *count))
+		self.map.iter()
+			.min_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)))
+			.map(|(entry, count)| (*entry, *count))
 	}
 
+	/// The most frequent entry, the smallest one if several are equally frequent.
 	pub fn get_max_frequency(&self) -> Option<(T, u64)> {
-		self.map.iter().reduce(|a, b| {
-			if a.1 <= b.1 {
-				a
-			}
-			else {
-				b
-			}
-		}).map(|(entry, count)| (*entry, *count))
+		self.map.iter()
+			.max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+			.map(|(entry, count)| (*entry, *count))
 	}
 
+	/// Median of the frequencies themselves, every distinct entry counts once.
 	pub fn get_median_frequency(&self) -> Option<f64> {
-		let min = self.get_min_frequency();
-		let max = self.get_max_frequency();
+		let mut frequencies: Vec<(f64, u64)> = self.map.values()
+			.map(|frequency| (*frequency as f64, 1))
+			.collect();
 
-		let min_max = min.zip(max);
+		frequencies.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-		min_max.map(|(min, max)| (min.1 + max.1) as f64 / 2.0)
+		get_weighted_quantile(&frequencies, 0.5)
 	}
 
 	pub fn get_mean_frequency(&self) -> Option<f64> {
@@ -202,3 +331,103 @@ impl<T: Eq + Hash> From<CalculationFrequencyMap<T>> for PresentationFrequencyMap
 		}
 	}
 }
+
+#[cfg(test)]
+mod presentation_frequency_map_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+	use crate::statistics::shared::rebinning::Rebinning;
+
+	fn map(entries: Vec<u32>) -> PresentationFrequencyMap<u32> {
+		let mut map = PresentationFrequencyMap::new();
+
+		for entry in entries {
+			map.add_entry(entry);
+		}
+
+		map
+	}
+
+	#[rstest]
+	#[case(vec![1, 2, 3, 4], 0.0, 1.0)]
+	#[case(vec![1, 2, 3, 4], 0.25, 1.75)]
+	#[case(vec![1, 2, 3, 4], 0.5, 2.5)]
+	#[case(vec![1, 2, 3, 4], 1.0, 4.0)]
+	#[case(vec![1, 1, 1, 5], 0.5, 1.0)]
+	#[case(vec![10, 20, 20, 20, 90], 0.75, 20.0)]
+	#[case(vec![7], 0.9, 7.0)]
+	fn quantile_test(#[case] entries: Vec<u32>, #[case] quantile: f64, #[case] expected: f64) {
+		assert_eq!(map(entries).get_quantile(quantile), Some(expected));
+	}
+
+	#[test]
+	fn empty_map_test() {
+		let map = map(vec![]);
+
+		assert_eq!(map.get_median(), None);
+		assert_eq!(map.get_mean(), None);
+		assert_eq!(map.get_standard_deviation(), None);
+		assert_eq!(map.get_mode(), None);
+		assert_eq!(map.get_median_frequency(), None);
+	}
+
+	#[test]
+	fn dispersion_test() {
+		let map = map(vec![2, 4, 4, 4, 5, 5, 7, 9]);
+
+		assert_eq!(map.get_mean(), Some(5.0));
+		assert_eq!(map.get_standard_deviation(), Some(2.0));
+		assert_eq!(map.get_percentile(50.0), Some(4.5));
+		assert_eq!(map.get_interquartile_range(), Some(1.5));
+		assert_eq!(map.get_median_absolute_deviation(), Some(0.5));
+	}
+
+	#[test]
+	#[allow(deprecated)]
+	fn deprecated_entry_statistics_test() {
+		let map = map(vec![2, 4, 4, 4, 5, 5, 7, 9]);
+
+		assert_eq!(map.get_median_entry(), map.get_median());
+		assert_eq!(map.get_mean_entry(), map.get_mean());
+	}
+
+	#[rstest]
+	#[case(vec![3, 1, 1, 3, 2], 1)]
+	#[case(vec![5, 5, 1], 5)]
+	fn mode_test(#[case] entries: Vec<u32>, #[case] expected: u32) {
+		assert_eq!(map(entries).get_mode(), Some(expected));
+	}
+
+	#[rstest]
+	#[case(vec![4, 7, 7, 7, 2, 2, 9], (4, 1), (7, 3))]
+	#[case(vec![5, 3, 3, 5, 8, 8, 8], (3, 2), (8, 3))]
+	fn min_max_frequency_test(#[case] entries: Vec<u32>, #[case] expected_min: (u32, u64), #[case] expected_max: (u32, u64)) {
+		let map = map(entries);
+
+		assert_eq!(map.get_min_frequency(), Some(expected_min));
+		assert_eq!(map.get_max_frequency(), Some(expected_max));
+	}
+
+	#[test]
+	fn median_frequency_test() {
+		assert_eq!(map(vec![1, 2, 2, 3, 3, 3, 3, 3]).get_median_frequency(), Some(2.0));
+		assert_eq!(map(vec![1, 2, 2, 3, 3, 3]).get_median_frequency(), Some(2.0));
+		assert_eq!(map(vec![1, 2, 2, 2]).get_median_frequency(), Some(2.0));
+	}
+
+	#[test]
+	fn rebin_test() {
+		let mut map = map((1..=100_000).collect());
+		let exact_quartiles = map.get_quantiles(&[0.25, 0.5, 0.75]).unwrap();
+
+		map.rebin(Rebinning::logarithmic(0.01).unwrap());
+
+		assert_eq!(map.get_frequency_sum(), 100_000);
+		assert!(map.get_frequencies().count() < 1000);
+
+		for (exact, rebinned) in exact_quartiles.iter().zip(map.get_quantiles(&[0.25, 0.5, 0.75]).unwrap()) {
+			assert!((rebinned - exact).abs() <= 0.01 * exact, "{} was estimated as {}", exact, rebinned);
+		}
+	}
+}
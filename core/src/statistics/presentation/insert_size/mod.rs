@@ -0,0 +1,117 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::statistics::calculation::insert_size::InsertSizeCalculationData;
+use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InsertSizePresentationData {
+	per_read_group: Vec<(String, PresentationFrequencyMap<u32>)>,
+	unassigned: PresentationFrequencyMap<u32>
+}
+
+impl InsertSizePresentationData {
+	pub fn get_read_groups(&self) -> impl Iterator<Item = &String> {
+		self.per_read_group.iter().map(|(read_group, _)| read_group)
+	}
+
+	pub fn get_per_read_group_maps(&self) -> impl Iterator<Item = (&String, &PresentationFrequencyMap<u32>)> {
+		self.per_read_group.iter().map(|(read_group, map)| (read_group, map))
+	}
+
+	pub fn get_read_group_map(&self, read_group: &str) -> Option<&PresentationFrequencyMap<u32>> {
+		self.per_read_group.iter()
+			.find(|(identifier, _)| identifier == read_group)
+			.map(|(_, map)| map)
+	}
+
+	pub fn get_unassigned_map(&self) -> &PresentationFrequencyMap<u32> {
+		&self.unassigned
+	}
+
+	pub fn get_insert_size_map(&self) -> PresentationFrequencyMap<u32> {
+		self.per_read_group.iter()
+			.map(|(_, map)| map)
+			.fold(self.unassigned.clone(), |a, b| PresentationFrequencyMap::merge(&a, b))
+	}
+}
+
+impl From<InsertSizeCalculationData> for InsertSizePresentationData {
+	fn from(value: InsertSizeCalculationData) -> Self {
+		let per_read_group = value.per_read_group.into_iter()
+			.map(|(read_group, map)| (read_group, map.into()))
+			.collect();
+
+		Self {
+			per_read_group,
+			unassigned: value.unassigned.into()
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Default)]
+pub struct InsertSizeStatistics {
+	pub count: u64,
+	pub mean: f64,
+	pub median: f64,
+	pub median_absolute_deviation: f64
+}
+
+impl From<&PresentationFrequencyMap<u32>> for InsertSizeStatistics {
+	fn from(map: &PresentationFrequencyMap<u32>) -> Self {
+		let count = map.get_frequency_sum();
+
+		if count == 0 {
+			return Default::default();
+		}
+
+		let mean = map.get_mean().unwrap_or(0.0);
+		let median = map.get_median().unwrap_or(0.0);
+		let median_absolute_deviation = map.get_median_absolute_deviation().unwrap_or(0.0);
+
+		Self {
+			count,
+			mean,
+			median,
+			median_absolute_deviation
+		}
+	}
+}
+
+#[cfg(test)]
+mod insert_size_statistics_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+	use crate::statistics::presentation::insert_size::InsertSizeStatistics;
+
+	#[rstest]
+	#[case(vec![], 0.0, 0.0, 0.0)]
+	#[case(vec![300], 300.0, 300.0, 0.0)]
+	#[case(vec![100, 200], 150.0, 150.0, 50.0)]
+	#[case(vec![100, 200, 200, 600], 275.0, 200.0, 50.0)]
+	#[case(vec![250, 250, 250, 300, 1000], 410.0, 250.0, 0.0)]
+	fn from_frequency_map_test(
+		#[case] insert_sizes: Vec<u32>,
+		#[case] expected_mean: f64,
+		#[case] expected_median: f64,
+		#[case] expected_median_absolute_deviation: f64
+	) {
+		let mut map = PresentationFrequencyMap::new();
+
+		for insert_size in insert_sizes {
+			map.add_entry(insert_size);
+		}
+
+		let statistics = InsertSizeStatistics::from(&map);
+
+		assert_eq!(expected_mean, statistics.mean, "Got Wrong Mean, Expected: {}, Actual: {}", expected_mean, statistics.mean);
+		assert_eq!(expected_median, statistics.median, "Got Wrong Median, Expected: {}, Actual: {}", expected_median, statistics.median);
+		assert_eq!(
+			expected_median_absolute_deviation,
+			statistics.median_absolute_deviation,
+			"Got Wrong Median Absolute Deviation, Expected: {}, Actual: {}",
+			expected_median_absolute_deviation,
+			statistics.median_absolute_deviation
+		);
+	}
+}
\ No newline at end of file
@@ -1,15 +1,30 @@
+use std::convert::TryFrom;
 use serde_derive::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::event::{Event, Stage};
 use crate::statistics::calculation::CalculationData;
+use crate::statistics::calculation::assembler::CalculationAssembler;
+use crate::statistics::calculation::per_reference::PerReferenceCalculation;
+use crate::statistics::calculation::per_reference::sorted::SortedPerReferenceAddRecordError;
 use crate::statistics::presentation::assembler::collection::PresentationAssemblerCollection;
 use crate::statistics::presentation::cigar_operations::CigarOperations;
-use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+use crate::statistics::presentation::custom::CustomMetricPresentationData;
+use crate::statistics::presentation::alignment_categories::AlignmentCategories;
+use crate::statistics::presentation::frequency_map::{get_weighted_quantile, PresentationFrequencyMap};
+use crate::statistics::presentation::insert_size::{InsertSizePresentationData, InsertSizeStatistics};
 use crate::statistics::presentation::per_reference::PerReferencePresentationData;
+use crate::statistics::presentation::split_read::circular::CircularRnaCandidates;
 use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::export::SplitReadExport;
+use crate::statistics::presentation::split_read::quarantine::SplitReadQuarantine;
+use crate::statistics::presentation::split_read::summary::SplitReadSummary;
+use crate::statistics::presentation::split_read::fusion::FusionCandidates;
 use crate::statistics::presentation::split_read::statistics::SplitReadStatistics;
+use crate::statistics::presentation::uniformity::CoverageUniformity;
 use crate::statistics::presentation::unmapped::UnmappedPresentationData;
 use crate::statistics::shared::meta::Meta;
-use crate::util::get_quality_frequency_map;
+use crate::util::{get_quality_frequency_map, get_unique_mapping_fraction};
 pub mod frequency_map;
 pub mod binned;
 pub mod per_reference;
@@ -18,12 +33,38 @@ pub mod assembler;
 pub mod record;
 pub mod cigar_operations;
 pub mod split_read;
+pub mod insert_size;
+pub mod alignment_categories;
+pub mod custom;
+pub mod uniformity;
+
+#[derive(Error, Debug)]
+pub enum PresentationDataError {
+    #[error("the calculation was cancelled")]
+    Cancelled,
+    #[error("could not finish the per reference statistics")]
+    PerReference {
+        source: SortedPerReferenceAddRecordError
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PresentationData {
     per_reference: Vec<PerReferencePresentationData>,
     unmapped: UnmappedPresentationData,
     split_read: SplitReadStatistics,
+    #[serde(default)]
+    circular_rna: CircularRnaCandidates,
+    #[serde(default)]
+    fusion: FusionCandidates,
+    #[serde(skip)]
+    split_read_export: SplitReadExport,
+    #[serde(skip)]
+    split_read_quarantine: SplitReadQuarantine,
+    #[serde(default)]
+    insert_size: InsertSizePresentationData,
+    #[serde(default)]
+    custom_metrics: Vec<CustomMetricPresentationData>,
     meta: Meta,
 }
 
@@ -59,6 +100,37 @@ impl PresentationData {
             )
     }
 
+    pub fn get_hit_count_map(&self) -> PresentationFrequencyMap<u32> {
+        self.per_reference.iter()
+            .map(|item| item.get_hit_count_map())
+            .fold(PresentationFrequencyMap::<u32>::new(),
+                  |a, b|
+                      PresentationFrequencyMap::<u32>::merge(&a, b)
+            )
+    }
+
+    pub fn get_unique_mapping_fraction(&self) -> f64 {
+        get_unique_mapping_fraction(&self.get_hit_count_map())
+    }
+
+    pub fn get_five_prime_clip_length_map(&self) -> PresentationFrequencyMap<u32> {
+        self.per_reference.iter()
+            .map(|item| item.get_five_prime_clip_length_map())
+            .fold(PresentationFrequencyMap::<u32>::new(),
+                  |a, b|
+                      PresentationFrequencyMap::<u32>::merge(&a, &b)
+            )
+    }
+
+    pub fn get_three_prime_clip_length_map(&self) -> PresentationFrequencyMap<u32> {
+        self.per_reference.iter()
+            .map(|item| item.get_three_prime_clip_length_map())
+            .fold(PresentationFrequencyMap::<u32>::new(),
+                  |a, b|
+                      PresentationFrequencyMap::<u32>::merge(&a, &b)
+            )
+    }
+
     pub fn get_assembler_length_map(&self) -> PresentationFrequencyMap<u32> {
         self.split_read.get_total_length_map().clone()
     }
@@ -83,6 +155,37 @@ impl PresentationData {
         &self.split_read
     }
 
+    pub fn get_circular_rna_candidates(&self) -> &CircularRnaCandidates {
+        &self.circular_rna
+    }
+
+    pub fn get_fusion_candidates(&self) -> &FusionCandidates {
+        &self.fusion
+    }
+
+    /// Fragments the assembler could not merge into split reads, empty after deserialization.
+    /// Only the counts per reason are filled unless the calculation data was created with the quarantine export enabled.
+    pub fn get_split_read_quarantine(&self) -> &SplitReadQuarantine {
+        &self.split_read_quarantine
+    }
+
+    /// Empty unless the calculation data was created with the split read export enabled.
+    pub fn get_split_read_export(&self) -> &SplitReadExport {
+        &self.split_read_export
+    }
+
+    pub fn get_insert_size_data(&self) -> &InsertSizePresentationData {
+        &self.insert_size
+    }
+
+    pub fn get_insert_size_map(&self) -> PresentationFrequencyMap<u32> {
+        self.insert_size.get_insert_size_map()
+    }
+
+    pub fn get_insert_size_statistics(&self) -> InsertSizeStatistics {
+        InsertSizeStatistics::from(&self.insert_size.get_insert_size_map())
+    }
+
     pub fn get_complete_quality_frequency_map(&self) -> Vec<(u8,u64)> {
         get_quality_frequency_map(&self.get_complete_quality_frequency())
     }
@@ -105,16 +208,45 @@ impl PresentationData {
         return None;
     }
 
+    /// The metrics of the collectors registered on the calculation data, in the order of registration.
+    pub fn get_custom_metrics(&self) -> &[CustomMetricPresentationData] {
+        &self.custom_metrics
+    }
+
+    pub fn get_custom_metric_by_name(&self, name: &str) -> Option<&CustomMetricPresentationData> {
+        self.custom_metrics.iter().find(|metric| metric.get_name() == name)
+    }
+
     pub fn get_unmapped_data(&self) -> &UnmappedPresentationData {
         &self.unmapped
     }
 
+    /// Reference names the mates of unmapped paired reads are aligned to, with the number of reads, most frequent first.
+    pub fn get_unmapped_mate_references(&self) -> Vec<(String, u64)> {
+        let mut mate_references: Vec<(String, u64)> = self.unmapped.get_split_read().get_mate_reference_map()
+            .get_frequencies()
+            .filter_map(|(ref_id, count)| {
+                self.per_reference.get(*ref_id as usize)
+                    .map(|reference| (reference.get_reference_name(), count))
+            })
+            .collect();
+
+        mate_references.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        mate_references
+    }
+
     pub fn get_cigar_operations(&self) -> CigarOperations {
         self.per_reference.iter().fold(Default::default(), |a,b|{
             CigarOperations::merge(&a, &b.get_cigar_operations())
         })
     }
 
+    pub fn get_alignment_categories(&self) -> AlignmentCategories {
+        self.per_reference.iter().fold(Default::default(), |a,b|{
+            AlignmentCategories::merge(&a, &b.get_alignment_categories())
+        })
+    }
+
     pub fn get_least_read_count(&self) -> u64 {
         self.per_reference.iter()
             .map(|item| item.get_single_read_data())
@@ -133,7 +265,7 @@ impl PresentationData {
             .unwrap_or(0)
     }
 
-    pub fn get_mean_read_count(&self) -> f64 {
+    fn get_read_count_map(&self) -> PresentationFrequencyMap<u64> {
         let mut read_count_map = PresentationFrequencyMap::<u64>::new();
 
         let iter = self.per_reference.iter()
@@ -145,187 +277,241 @@ impl PresentationData {
             read_count_map.add_entry(item);
         }
 
-        read_count_map.get_mean_entry().unwrap()
+        read_count_map
     }
 
-    pub fn get_median_read_count(&self) -> f64 {
-        let mut read_count_map = PresentationFrequencyMap::<u64>::new();
-
-        let iter = self.per_reference.iter()
-            .map(|item| item.get_single_read_data())
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum());
-
-        for item in iter {
-            read_count_map.add_entry(item);
-        }
+    pub fn get_mean_read_count(&self) -> f64 {
+        self.get_read_count_map().get_mean().unwrap_or(0.0)
+    }
 
-        read_count_map.get_median_entry().unwrap_or(0.0)
+    pub fn get_median_read_count(&self) -> f64 {
+        self.get_read_count_map().get_median().unwrap_or(0.0)
     }
 
     pub fn get_mode_read_count(&self) -> u64 {
-        let mut read_count_map = PresentationFrequencyMap::<u64>::new();
-
-        let iter = self.per_reference.iter()
-            .map(|item| item.get_single_read_data())
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum());
+        self.get_read_count_map().get_mode().unwrap_or(0)
+    }
 
-        for item in iter {
-            read_count_map.add_entry(item);
-        }
+    /// Reads per reference base of every reference, see [`PerReferencePresentationData::get_read_density`].
+    fn get_read_density_map(&self) -> Vec<(f64, u64)> {
+        let mut read_densities: Vec<(f64, u64)> = self.get_per_reference_data()
+            .map(|item| (item.get_read_density(), 1))
+            .collect();
 
-        read_count_map.get_max_frequency().unwrap_or((0,0)).0
+        read_densities.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        read_densities
     }
 
-    pub fn get_median_coverage(&self) -> f64 {
-        let total_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum())
-            .map(|item| item as f64).collect();
+    /// Mean depth of every reference, see [`PerReferencePresentationData::get_mean_depth`].
+    fn get_depth_map(&self) -> Vec<(f64, u64)> {
+        let mut depths: Vec<(f64, u64)> = self.get_per_reference_data()
+            .map(|item| (item.get_mean_depth(), 1))
+            .collect();
 
-        let reference_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_reference_length())
-            .map(|item| item as f64).collect();
+        depths.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        depths
+    }
 
-        let coverage_data: Vec<f64> = total_length_data.iter()
-            .zip(reference_length_data.iter())
-            .map(|(total_length, reference_length)| {
-                *total_length / *reference_length
-            })
-            .collect();
+    pub fn get_median_read_density(&self) -> f64 {
+        get_weighted_quantile(&self.get_read_density_map(), 0.5).unwrap_or(0.0)
+    }
 
-        let min_coverage = coverage_data.iter().reduce(|a,b| {
-            if *a >= *b {
-                b
-            }
-            else {
-                a
-            }
-        }).map(|item| *item).unwrap_or(0.0);
+    /// Every reference counts once, regardless of its length.
+    pub fn get_mean_read_density(&self) -> f64 {
+        let read_densities = self.get_read_density_map();
 
-        let max_coverage = coverage_data.iter().reduce(|a,b| {
-            if *a >= *b {
-                a
-            }
-            else {
-                b
-            }
-        }).map(|item| *item).unwrap_or(0.0);
+        if read_densities.is_empty() {
+            return 0.0;
+        }
 
-        return (min_coverage + max_coverage) / 2.0;
+        read_densities.iter().map(|(read_density, _)| read_density).sum::<f64>() / read_densities.len() as f64
     }
 
-    pub fn get_mean_coverage(&self) -> f64 {
-        let total_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum())
-            .map(|item| item as f64).collect();
+    pub fn get_least_read_density(&self) -> f64 {
+        get_weighted_quantile(&self.get_read_density_map(), 0.0).unwrap_or(0.0)
+    }
 
-        let reference_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_reference_length())
-            .map(|item| item as f64).collect();
+    pub fn get_most_read_density(&self) -> f64 {
+        get_weighted_quantile(&self.get_read_density_map(), 1.0).unwrap_or(0.0)
+    }
 
-        let coverage_data: Vec<f64> = total_length_data.iter()
-            .zip(reference_length_data.iter())
-            .map(|(total_length, reference_length)| {
-                *total_length / *reference_length
-            })
-            .collect();
+    /// Aligned bases of all references divided by their total length, the mean of `samtools depth -a`.
+    pub fn get_mean_depth(&self) -> f64 {
+        let total_reference_length: u64 = self.get_per_reference_data()
+            .map(|item| item.get_reference_length() as u64)
+            .sum();
 
-        let reference_count = self.per_reference.len();
+        if total_reference_length == 0 {
+            return 0.0;
+        }
 
-        let sum: f64 = coverage_data.iter().sum();
+        let aligned_base_count: u64 = self.get_per_reference_data()
+            .map(|item| item.get_aligned_base_count())
+            .sum();
 
-        return sum / reference_count as f64;
+        aligned_base_count as f64 / total_reference_length as f64
     }
 
-    pub fn get_least_coverage(&self) -> f64 {
-        let total_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum())
-            .map(|item| item as f64).collect();
+    /// Median of the mean depths of the references.
+    pub fn get_median_depth(&self) -> f64 {
+        get_weighted_quantile(&self.get_depth_map(), 0.5).unwrap_or(0.0)
+    }
+
+    pub fn get_least_depth(&self) -> f64 {
+        get_weighted_quantile(&self.get_depth_map(), 0.0).unwrap_or(0.0)
+    }
 
-        let reference_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_reference_length())
-            .map(|item| item as f64).collect();
+    pub fn get_most_depth(&self) -> f64 {
+        get_weighted_quantile(&self.get_depth_map(), 1.0).unwrap_or(0.0)
+    }
 
-        let coverage_data: Vec<f64> = total_length_data.iter()
-            .zip(reference_length_data.iter())
-            .map(|(total_length, reference_length)| {
-                *total_length / *reference_length
-            })
+    /// Uniformity of the bins of all references together.
+    pub fn get_coverage_uniformity(&self) -> CoverageUniformity {
+        let bins = self.get_per_reference_data()
+            .flat_map(|item| item.get_bin_coverages())
             .collect();
 
-        coverage_data.iter().reduce(|a,b| {
-            if *a >= *b {
-                b
-            }
-            else {
-                a
-            }
-        }).map(|item| *item).unwrap_or(0.0)
+        CoverageUniformity::from_bins(bins)
     }
+}
 
-    pub fn get_most_coverage(&self) -> f64 {
-        let total_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_read_length_on_reference_map())
-            .map(|item| item.get_frequency_sum())
-            .map(|item| item as f64).collect();
+impl TryFrom<CalculationData> for PresentationData {
+    type Error = PresentationDataError;
 
-        let reference_length_data: Vec<f64> = self.get_per_reference_data()
-            .map(|item| item.get_reference_length())
-            .map(|item| item as f64).collect();
+    fn try_from(mut value: CalculationData) -> Result<Self, Self::Error> {
+        value.merge_local_data();
 
-        let coverage_data: Vec<f64> = total_length_data.iter()
-            .zip(reference_length_data.iter())
-            .map(|(total_length, reference_length)| {
-                *total_length / *reference_length
-            })
-            .collect();
+        let event_sink = value.event_sink;
+        let progress_observer = value.progress_observer;
+        let cancellation_token = value.cancellation_token;
 
-        coverage_data.iter().reduce(|a,b| {
-            if *a >= *b {
-                a
-            }
-            else {
-                b
+        let start_stage = |stage: Stage| -> Result<(), PresentationDataError> {
+            if cancellation_token.is_cancelled() {
+                return Err(PresentationDataError::Cancelled);
             }
-        }).map(|item| *item).unwrap_or(0.0)
-    }
-}
 
-impl From<CalculationData> for PresentationData {
-    fn from(value: CalculationData) -> Self {
+            event_sink.handle(Event::StageStarted { stage });
+            progress_observer.stage_started(stage);
+            Ok(())
+        };
+
+        start_stage(Stage::PerReferenceStatistics)?;
+
+        let mut per_reference = match value.per_reference {
+            PerReferenceCalculation::Unsorted(per_reference) => per_reference
+                .into_iter()
+                .enumerate()
+                .map(|(ref_id, per_reference)| {
+                    let per_reference = PerReferencePresentationData::calculate_from_data(per_reference);
+                    progress_observer.reference_finalized(ref_id, &per_reference.get_reference_name());
+                    per_reference
+                })
+                .collect::<Vec<PerReferencePresentationData>>(),
+            PerReferenceCalculation::Sorted(sorted) => sorted.into_presentation_data(progress_observer.as_ref())
+                .map_err(|source| PresentationDataError::PerReference { source })?
+        };
+
+        for reference in per_reference.iter_mut() {
+            reference.rebin(value.meta.rebinning);
+        }
 
-        let per_reference = value.per_reference
-            .into_iter()
-            .map(|per_reference| {
-                let value = PerReferencePresentationData::calculate_from_data(per_reference);
-                value
-            })
-            .collect::<Vec<PerReferencePresentationData>>();
+        start_stage(Stage::UnmappedStatistics)?;
 
         let unmapped = value.unmapped.into();
 
-        let presentation_assembler_collection: PresentationAssemblerCollection = value.split_read.into();
-        let (
-            split_read_collections,
-            dropped_reads
-        ): (SplitReadCollections, usize)
-            = presentation_assembler_collection.into();
+        let insert_size = value.insert_size.into();
+
+        let custom_metrics = value.custom_metrics.into_presentation_data();
+
+        start_stage(Stage::SplitReadAssembly)?;
+
+        let split_read_summary = match value.split_read {
+            CalculationAssembler::Buffered(map) => {
+                let presentation_assembler_collection: PresentationAssemblerCollection = map.into();
+                let skipped_mate_pairs = presentation_assembler_collection.get_skipped_mate_pairs();
+                let (split_read_collections, split_read_quarantine) = SplitReadCollections::assemble(presentation_assembler_collection, &cancellation_token)
+                    .ok_or(PresentationDataError::Cancelled)?;
+
+                SplitReadSummary {
+                    skipped_mate_pairs,
+                    ..SplitReadSummary::from_collections(split_read_collections, split_read_quarantine, value.keep_split_reads, value.keep_quarantined_reads)
+                }
+            }
+            CalculationAssembler::Streaming(stream) => stream.into_summary()
+        };
+
+        let SplitReadSummary {
+            statistics,
+            circular_rna,
+            fusion,
+            breakpoints,
+            quarantine: split_read_quarantine,
+            split_reads,
+            skipped_mate_pairs,
+            ..
+        } = split_read_summary;
+
+        if split_read_quarantine.get_record_count() > 0 {
+            event_sink.handle(Event::UnmergeableReadsDropped { record_count: split_read_quarantine.get_record_count() });
+        }
+
+        let reference_names: Vec<String> = per_reference.iter()
+            .map(|item| item.get_reference_name())
+            .collect();
+
+        let circular_rna = circular_rna.into_candidates(&reference_names);
+        let fusion = fusion.into_candidates(&reference_names);
 
-        if dropped_reads > 0 {
-            println!("WARN: {} unmergeable reads dropped", dropped_reads);
+        for ((ref_id, position), count) in breakpoints {
+            if let Some(reference) = per_reference.get_mut(ref_id as usize) {
+                reference.add_split_read_breakpoints(position, count);
+            }
         }
 
-        let split_read = split_read_collections.into();
+        let split_read_export = SplitReadExport::new(split_reads, &reference_names);
+
+        start_stage(Stage::SplitReadStatistics)?;
 
-        Self {
+        let mut split_read = SplitReadStatistics::from(statistics)
+            .with_quarantine(&split_read_quarantine)
+            .with_skipped_mate_pairs(skipped_mate_pairs);
+
+        split_read.rebin(value.meta.rebinning);
+
+        Ok(Self {
             split_read,
+            circular_rna,
+            fusion,
+            split_read_export,
+            split_read_quarantine,
             per_reference,
             unmapped,
+            insert_size,
+            custom_metrics,
             meta: value.meta
-        }
+        })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod presentation_data_tests {
+    use crate::statistics::presentation::PresentationData;
+    use crate::statistics::presentation::split_read::quarantine::QuarantineReason;
+
+    /// Written before any of the optional statistics existed.
+    const OLDEST_STATISTICS: &str = r#"{"per_reference":[{"reference_name":"chr1","reference_length":20,"single_read_data":{"quality_map":{"m":{"30":2}},"read_length_on_reference_map":{"m":{"6":1,"7":1}},"read_length_sequence_map":{"m":{"8":1,"5":1}},"binned_statistics":{"bs":10,"b":[{"st":0,"e":9,"c":2,"cta":9,"co":{"m":8,"i":0,"d":0,"sk":0}},{"st":10,"e":19,"c":1,"cta":4,"co":{"m":4,"i":0,"d":0,"sk":0}},{"st":20,"e":20,"c":0,"cta":0,"co":{"m":0,"i":0,"d":0,"sk":0}}],"s":0,"e":20}},"split_read_data":{"quality_map":{"m":{}},"read_length_on_reference_map":{"m":{}},"read_length_sequence_map":{"m":{}},"binned_statistics":{"bs":10,"b":[{"st":0,"e":9,"c":0,"cta":0,"co":{"m":0,"i":0,"d":0,"sk":0}},{"st":10,"e":19,"c":0,"cta":0,"co":{"m":0,"i":0,"d":0,"sk":0}},{"st":20,"e":20,"c":0,"cta":0,"co":{"m":0,"i":0,"d":0,"sk":0}}],"s":0,"e":20}}}],"unmapped":{"single_read":{"read_length_map":{"m":{}}},"split_read":{"read_length_map":{"m":{}}}},"split_read":{"gap_length_map":{"m":{}},"total_length_map":{"m":{}},"split_count_map":{"m":{}},"split_count_unmapped_map":{"m":{}},"unmapped_count_map":{"m":{}}},"meta":{"bin_size":10}}"#;
+
+    #[test]
+    fn oldest_statistics_test() {
+        let presentation_data: PresentationData = serde_json::from_str(OLDEST_STATISTICS).unwrap();
+        let reference = presentation_data.get_per_reference_data().next().unwrap();
+        let coverages: Vec<f64> = reference.get_binned_statistics().get_bins()
+            .map(|bin| bin.get_coverage())
+            .collect();
+
+        assert_eq!(presentation_data.get_read_length_on_reference_map().get_frequency_sum(), 2);
+        assert_eq!(coverages, vec![0.9, 0.4, 0.0]);
+        assert_eq!(presentation_data.get_split_read_data().get_quarantine_count(QuarantineReason::Unclassified), 0);
+        assert_eq!(reference.get_alignment_categories().primary, 0);
+    }
+}
@@ -6,14 +6,26 @@ use crate::statistics::presentation::per_reference::single_read::SingleReadPerRe
 use crate::statistics::calculation::per_reference::PerReferenceCalculationData;
 use crate::statistics::presentation::binned::map::BinnedStatisticsPresentationMap;
 use crate::statistics::presentation::cigar_operations::CigarOperations;
-use crate::util::get_quality_frequency_map;
+use crate::statistics::presentation::insert_size::InsertSizeStatistics;
+use crate::statistics::presentation::alignment_categories::AlignmentCategories;
+use crate::statistics::presentation::uniformity::CoverageUniformity;
+use crate::statistics::shared::rebinning::Rebinning;
+use crate::util::{get_quality_frequency_map, get_unique_mapping_fraction};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PerReferencePresentationData {
     reference_name: String,
     reference_length: u32,
+    #[serde(default)]
+    insert_size_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    hit_count_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    alignment_categories: AlignmentCategories,
     single_read_data: SingleReadPerReferencePresentationData,
-    split_read_data: SingleReadPerReferencePresentationData
+    split_read_data: SingleReadPerReferencePresentationData,
+    #[serde(default)]
+    zoom_levels: Vec<BinnedStatisticsPresentationMap>
 }
 
 impl PerReferencePresentationData {
@@ -25,6 +37,48 @@ impl PerReferencePresentationData {
         self.reference_length
     }
 
+    pub fn get_insert_size_map(&self) -> &PresentationFrequencyMap<u32> {
+        &self.insert_size_map
+    }
+
+    pub fn get_insert_size_statistics(&self) -> InsertSizeStatistics {
+        InsertSizeStatistics::from(&self.insert_size_map)
+    }
+
+    pub fn get_hit_count_map(&self) -> &PresentationFrequencyMap<u32> {
+        &self.hit_count_map
+    }
+
+    pub fn get_unique_mapping_fraction(&self) -> f64 {
+        get_unique_mapping_fraction(&self.hit_count_map)
+    }
+
+    pub fn get_alignment_categories(&self) -> AlignmentCategories {
+        self.alignment_categories
+    }
+
+    /// Primary, secondary, supplementary, duplicate and QC-fail counts of each bin, in bin order.
+    pub fn get_alignment_categories_per_bin(&self) -> Vec<AlignmentCategories> {
+        self.get_binned_statistics().get_bins()
+            .map(|bin| bin.get_alignment_categories())
+            .collect()
+    }
+
+    /// Split read breakpoints of each bin, in bin order.
+    pub fn get_breakpoints_per_bin(&self) -> Vec<u64> {
+        self.get_binned_statistics().get_bins()
+            .map(|bin| bin.get_breakpoints())
+            .collect()
+    }
+
+    pub(crate) fn add_split_read_breakpoints(&mut self, position: u32, count: u64) {
+        self.split_read_data.add_breakpoints(position, count);
+
+        for zoom_level in self.zoom_levels.iter_mut() {
+            zoom_level.add_breakpoints(position, count);
+        }
+    }
+
     pub fn get_single_read_data(&self) -> &SingleReadPerReferencePresentationData {
         &self.single_read_data
     }
@@ -47,6 +101,20 @@ impl PerReferencePresentationData {
         )
     }
 
+    pub fn get_five_prime_clip_length_map(&self) -> PresentationFrequencyMap<u32> {
+        PresentationFrequencyMap::merge(
+            self.single_read_data.get_five_prime_clip_length_map(),
+            self.split_read_data.get_five_prime_clip_length_map()
+        )
+    }
+
+    pub fn get_three_prime_clip_length_map(&self) -> PresentationFrequencyMap<u32> {
+        PresentationFrequencyMap::merge(
+            self.single_read_data.get_three_prime_clip_length_map(),
+            self.split_read_data.get_three_prime_clip_length_map()
+        )
+    }
+
     pub fn get_quality_frequency(&self) -> PresentationFrequencyMap<u8> {
         PresentationFrequencyMap::merge(
             self.single_read_data.get_quality_frequency(),
@@ -65,6 +133,46 @@ impl PerReferencePresentationData {
         ).unwrap()
     }
 
+    /// Single and split reads binned with the additional resolutions, in the order of [`crate::statistics::shared::meta::Meta::zoom_levels`].
+    pub fn get_zoom_levels(&self) -> &[BinnedStatisticsPresentationMap] {
+        &self.zoom_levels
+    }
+
+    /// Depth and length of each bin, in bin order.
+    /// The depth counts aligned bases like [`PerReferencePresentationData::get_mean_depth`], so spliced reads don't cover their introns.
+    pub fn get_bin_coverages(&self) -> Vec<(f64, u64)> {
+        self.get_binned_statistics().get_bins()
+            .map(|bin| (bin.get_depth(), bin.get_length() as u64))
+            .collect()
+    }
+
+    pub fn get_coverage_uniformity(&self) -> CoverageUniformity {
+        CoverageUniformity::from_bins(self.get_bin_coverages())
+    }
+
+    /// Reads per reference base, which is not the depth unless every read is one base long.
+    pub fn get_read_density(&self) -> f64 {
+        if self.reference_length == 0 {
+            return 0.0;
+        }
+
+        self.get_read_length_on_reference_map().get_frequency_sum() as f64 / self.reference_length as f64
+    }
+
+    /// Bases aligned with `M`, `=` or `X`, deletions and skipped introns are not covered like in `samtools depth`.
+    pub fn get_aligned_base_count(&self) -> u64 {
+        self.get_cigar_operations().alignment_matches
+    }
+
+    /// Aligned bases per reference base.
+    pub fn get_mean_depth(&self) -> f64 {
+        if self.reference_length == 0 {
+            return 0.0;
+        }
+
+        self.get_aligned_base_count() as f64 / self.reference_length as f64
+    }
+
     pub fn get_cigar_operations(&self) -> CigarOperations {
         CigarOperations::merge(
             &self.single_read_data.get_cigar_operations(),
@@ -72,21 +180,88 @@ impl PerReferencePresentationData {
         )
     }
 
+    pub(crate) fn rebin(&mut self, rebinning: Rebinning) {
+        self.single_read_data.rebin(rebinning);
+        self.split_read_data.rebin(rebinning);
+    }
+
     pub fn calculate_from_data(value: PerReferenceCalculationData)
         -> PerReferencePresentationData
     {
         let reference_name = value.reference_name;
         let reference_length = value.reference_length;
+        let insert_size_map = value.insert_size_map.into();
+        let hit_count_map = value.hit_count_map.into();
+        let alignment_categories = value.alignment_categories.into();
 
         let single_read_data = value.single_read_data.into();
 
         let split_read_data = value.split_read_data.into();
 
+        let zoom_levels = value.zoom_levels.into_iter()
+            .map(|zoom_level| zoom_level.into())
+            .collect();
+
         Self {
             reference_name,
             reference_length,
+            insert_size_map,
+            hit_count_map,
+            alignment_categories,
             single_read_data,
-            split_read_data
+            split_read_data,
+            zoom_levels
+        }
+    }
+}
+#[cfg(test)]
+mod per_reference_presentation_tests {
+    use std::num::NonZeroU32;
+
+    use bam::header::HeaderEntry;
+    use bam::Record;
+
+    use crate::header::reference_sequence_line::ReferenceSequence;
+    use crate::header::reference_sequence_line::molecule_topology::MoleculeTopology;
+    use crate::statistics::calculation::binned::BinConfig;
+    use crate::statistics::calculation::per_reference::PerReferenceCalculationData;
+    use crate::statistics::calculation::per_reference::local::LocalPerReferenceData;
+    use crate::statistics::presentation::per_reference::PerReferencePresentationData;
+
+    #[test]
+    fn bin_coverages_leave_out_introns_test() {
+        let reference_sequence = ReferenceSequence {
+            name: "chr1".to_string(),
+            length: 1000,
+            alternate_locus: None,
+            alternate_names: None,
+            assembly: None,
+            description: None,
+            checksum: None,
+            species: None,
+            topology: MoleculeTopology::Linear,
+            uri: None
+        };
+        let data = PerReferenceCalculationData::new(&reference_sequence, BinConfig::LengthOfBins(NonZeroU32::new(100).unwrap()), false).unwrap();
+        let mut local = LocalPerReferenceData::default();
+
+        let mut header = bam::Header::new();
+        header.push_entry(HeaderEntry::ref_sequence("chr1".to_string(), 1000)).unwrap();
+
+        for sam in ["a\t0\tchr1\t1\t60\t100M500N100M\t*\t0\t0\t*\t*", "b\t0\tchr1\t801\t60\t100M\t*\t0\t0\t*\t*"] {
+            let mut record = Record::new();
+            record.fill_from_sam(sam, &header).unwrap();
+            local.add_record(&data, &record);
         }
+
+        local.merge_into(&data);
+
+        let per_reference = PerReferencePresentationData::calculate_from_data(data);
+        let bin_coverages = per_reference.get_bin_coverages();
+        let depths: Vec<f64> = bin_coverages.iter().map(|(depth, _)| *depth).collect();
+        let mean_bin_depth = bin_coverages.iter().map(|(depth, length)| depth * *length as f64).sum::<f64>() / 1000.0;
+
+        assert_eq!(depths, vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(mean_bin_depth, per_reference.get_mean_depth());
     }
-}
\ No newline at end of file
+}
@@ -4,12 +4,17 @@ use crate::statistics::presentation::binned::map::BinnedStatisticsPresentationMa
 use crate::statistics::calculation::per_reference::single_read::SingleReadPerReferenceCalculationData;
 use crate::util::get_quality_frequency_map;
 use crate::statistics::presentation::cigar_operations::CigarOperations;
+use crate::statistics::shared::rebinning::Rebinning;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct SingleReadPerReferencePresentationData {
     quality_map: PresentationFrequencyMap<u8>,
     read_length_on_reference_map: PresentationFrequencyMap<u32>,
     read_length_sequence_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    five_prime_clip_length_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    three_prime_clip_length_map: PresentationFrequencyMap<u32>,
     binned_statistics: BinnedStatisticsPresentationMap
 }
 
@@ -30,10 +35,28 @@ impl SingleReadPerReferencePresentationData {
         &self.read_length_sequence_map
     }
 
+    pub fn get_five_prime_clip_length_map(&self) -> &PresentationFrequencyMap<u32> {
+        &self.five_prime_clip_length_map
+    }
+
+    pub fn get_three_prime_clip_length_map(&self) -> &PresentationFrequencyMap<u32> {
+        &self.three_prime_clip_length_map
+    }
+
     pub fn get_binned_statistics(&self) -> &BinnedStatisticsPresentationMap {
         &self.binned_statistics
     }
 
+    pub(crate) fn add_breakpoints(&mut self, position: u32, count: u64) {
+        self.binned_statistics.add_breakpoints(position, count);
+    }
+
+    /// Only the read length maps, clip lengths and qualities have few distinct values anyway.
+    pub(crate) fn rebin(&mut self, rebinning: Rebinning) {
+        self.read_length_on_reference_map.rebin(rebinning);
+        self.read_length_sequence_map.rebin(rebinning);
+    }
+
     pub fn get_cigar_operations(&self) -> CigarOperations {
         self.binned_statistics.get_bins().fold(Default::default(), |a,b|
             CigarOperations::merge(&a, &b.get_cigar_operations())
@@ -47,12 +70,16 @@ impl From<SingleReadPerReferenceCalculationData> for SingleReadPerReferencePrese
 
         let read_length_sequence_map = value.read_length_sequence_map.into();
         let read_length_on_reference_map = value.read_length_on_reference_map.into();
+        let five_prime_clip_length_map = value.five_prime_clip_length_map.into();
+        let three_prime_clip_length_map = value.three_prime_clip_length_map.into();
         let binned_statistics = value.binned_statistics.into();
 
         Self {
             quality_map,
             read_length_sequence_map,
             read_length_on_reference_map,
+            five_prime_clip_length_map,
+            three_prime_clip_length_map,
             binned_statistics
         }
     }
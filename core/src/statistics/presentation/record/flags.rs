@@ -37,6 +37,15 @@ impl PresentationFlags {
         self.is_first_mate
     }
 
+    /// Index of the template segment, 0 for the first and 1 for the last one, 2 if neither or both flags are set.
+    pub fn get_segment_index(&self) -> u8 {
+        match (self.is_first_mate, self.is_last_mate) {
+            (true, false) => 0,
+            (false, true) => 1,
+            _ => 2
+        }
+    }
+
     pub fn get_is_supplementary(&self) -> bool {
         self.is_supplementary
     }
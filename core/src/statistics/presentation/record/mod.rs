@@ -1,10 +1,12 @@
 use crate::statistics::presentation::record::flags::PresentationFlags;
+use crate::statistics::presentation::record::split_tags::SplitTags;
 
 use serde_derive::{Deserialize, Serialize};
 use bam::Record;
 use crate::util::{get_record_name_as_string, get_record_mapping_quality, get_record_length_on_reference, get_record_start, get_record_end};
 
 pub mod flags;
+pub mod split_tags;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PresentationRecord {
@@ -17,7 +19,9 @@ pub struct PresentationRecord {
     end: u32,
     template_length: u32,
     ref_id: i32,
-    r_next: i32
+    r_next: i32,
+    #[serde(default)]
+    split_tags: Option<SplitTags>
 }
 
 impl PresentationRecord {
@@ -41,10 +45,16 @@ impl PresentationRecord {
             end,
             template_length,
             ref_id,
-            r_next
+            r_next,
+            split_tags: None
         }
     }
 
+    pub fn with_split_tags(mut self, split_tags: SplitTags) -> Self {
+        self.split_tags = Some(split_tags);
+        self
+    }
+
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
@@ -84,6 +94,10 @@ impl PresentationRecord {
     pub fn get_r_next(&self) -> i32 {
         self.r_next
     }
+
+    pub fn get_split_tags(&self) -> Option<&SplitTags> {
+        self.split_tags.as_ref()
+    }
 }
 
 impl From<Record> for PresentationRecord {
@@ -98,6 +112,7 @@ impl From<Record> for PresentationRecord {
         let ref_id = record.ref_id();
         let r_next = record.mate_ref_id();
         let template_length= record.template_len().abs() as u32;
+        let split_tags = SplitTags::from_record(&record);
 
         Self {
             name,
@@ -109,7 +124,8 @@ impl From<Record> for PresentationRecord {
             end,
             template_length,
             ref_id,
-            r_next
+            r_next,
+            split_tags
         }
     }
 }
\ No newline at end of file
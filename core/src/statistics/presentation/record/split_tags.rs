@@ -0,0 +1,88 @@
+use serde_derive::{Deserialize, Serialize};
+use bam::Record;
+use crate::util::{get_record_integer_tag, get_record_string_tag};
+
+/// Split information written by segemehl for every fragment of a split read.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SplitTags {
+    read_start: u32,
+    read_end: u32,
+    index: u32,
+    previous_reference: Option<String>,
+    previous_position: Option<u32>,
+    next_position: Option<u32>
+}
+
+impl SplitTags {
+    pub fn new(read_start: u32,
+               read_end: u32,
+               index: u32,
+               previous_reference: Option<String>,
+               previous_position: Option<u32>,
+               next_position: Option<u32>) -> Self {
+        Self {
+            read_start,
+            read_end,
+            index,
+            previous_reference,
+            previous_position,
+            next_position
+        }
+    }
+
+    /// Returns `None` if the record carries no split index (`XQ`).
+    pub fn from_record(record: &Record) -> Option<Self> {
+        let index = get_record_integer_tag(record, b"XQ")?;
+
+        if index < 0 {
+            return None;
+        }
+
+        let read_start = get_record_integer_tag(record, b"XX").unwrap_or(0).max(0) as u32;
+        let read_end = get_record_integer_tag(record, b"XY").unwrap_or(0).max(0) as u32;
+        let previous_reference = get_record_string_tag(record, b"XC");
+        let previous_position = get_record_integer_tag(record, b"XP").map(|value| value.max(0) as u32);
+        let next_position = get_record_integer_tag(record, b"XN").map(|value| value.max(0) as u32);
+
+        Some(Self {
+            read_start,
+            read_end,
+            index: index as u32,
+            previous_reference,
+            previous_position,
+            next_position
+        })
+    }
+
+    pub fn get_read_start(&self) -> u32 {
+        self.read_start
+    }
+
+    pub fn get_read_end(&self) -> u32 {
+        self.read_end
+    }
+
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_previous_reference(&self) -> Option<&String> {
+        self.previous_reference.as_ref()
+    }
+
+    pub fn get_previous_position(&self) -> Option<u32> {
+        self.previous_position
+    }
+
+    pub fn get_next_position(&self) -> Option<u32> {
+        self.next_position
+    }
+
+    pub fn has_previous(&self) -> bool {
+        self.previous_position.is_some() || self.previous_reference.is_some()
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.next_position.is_some()
+    }
+}
\ No newline at end of file
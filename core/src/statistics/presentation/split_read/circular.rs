@@ -0,0 +1,242 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use serde_derive::{Deserialize, Serialize};
+use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::SplitRead;
+
+/// Reference id, spanned start and end and strand of a back-splice junction.
+type JunctionKey = (i32, u32, u32, bool);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircularRnaCandidate {
+	reference_name: String,
+	start: u32,
+	end: u32,
+	is_reverse_strand: bool,
+	supporting_reads: u64,
+	unique_positions: u64
+}
+
+impl CircularRnaCandidate {
+	pub fn get_reference_name(&self) -> &str {
+		&self.reference_name
+	}
+
+	pub fn get_start(&self) -> u32 {
+		self.start
+	}
+
+	pub fn get_end(&self) -> u32 {
+		self.end
+	}
+
+	pub fn get_strand(&self) -> char {
+		if self.is_reverse_strand { '-' } else { '+' }
+	}
+
+	pub fn get_supporting_reads(&self) -> u64 {
+		self.supporting_reads
+	}
+
+	/// Number of distinct fragment positions among the supporting reads, a low value hints at PCR duplicates.
+	pub fn get_unique_positions(&self) -> u64 {
+		self.unique_positions
+	}
+}
+
+/// Back-splice junctions counted one split read at a time, the reads don't have to be kept.
+#[derive(Debug, Default)]
+pub struct CircularRnaJunctions {
+	junctions: HashMap<JunctionKey, (u64, HashSet<(u32, u32)>)>,
+	supporting_read_count: u64
+}
+
+impl CircularRnaJunctions {
+	pub fn add_split_read(&mut self, split_read: &SplitRead) {
+		let mut keys = HashSet::new();
+
+		for junction in split_read.get_junctions().iter().filter(|junction| junction.is_back_splice()) {
+			let (start, end) = junction.get_spanned_range();
+			let key = (junction.previous.ref_id, start, end, junction.previous.is_reverse_strand);
+
+			if keys.insert(key) {
+				let (count, positions) = self.junctions.entry(key).or_default();
+				*count += 1;
+				positions.insert((junction.previous.start, junction.next.end));
+			}
+		}
+
+		if !keys.is_empty() {
+			self.supporting_read_count += 1;
+		}
+	}
+
+	pub fn into_candidates(self, reference_names: &[String]) -> CircularRnaCandidates {
+		let mut candidates: Vec<CircularRnaCandidate> = self.junctions.into_iter()
+			.filter_map(|((ref_id, start, end, is_reverse_strand), (supporting_reads, positions))| {
+				let reference_name = reference_names.get(ref_id as usize)?.clone();
+
+				Some(CircularRnaCandidate {
+					reference_name,
+					start,
+					end,
+					is_reverse_strand,
+					supporting_reads,
+					unique_positions: positions.len() as u64
+				})
+			})
+			.collect();
+
+		candidates.sort_by(|a, b| {
+			b.supporting_reads.cmp(&a.supporting_reads)
+				.then_with(|| a.reference_name.cmp(&b.reference_name))
+				.then_with(|| a.start.cmp(&b.start))
+				.then_with(|| a.end.cmp(&b.end))
+		});
+
+		let top_candidates = candidates.iter()
+			.take(CircularRnaCandidates::TOP_CANDIDATE_COUNT)
+			.cloned()
+			.collect();
+
+		CircularRnaCandidates {
+			candidate_count: candidates.len() as u64,
+			supporting_read_count: self.supporting_read_count,
+			top_candidates,
+			candidates
+		}
+	}
+}
+
+/// Back-splice junctions aggregated over all split reads.
+///
+/// Only the best supported candidates are serialized, the complete table is kept for the export.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CircularRnaCandidates {
+	candidate_count: u64,
+	supporting_read_count: u64,
+	top_candidates: Vec<CircularRnaCandidate>,
+	#[serde(skip)]
+	candidates: Vec<CircularRnaCandidate>
+}
+
+impl CircularRnaCandidates {
+	pub const TOP_CANDIDATE_COUNT: usize = 100;
+
+	/// Duplicates and secondary alignments are left out, so they can't inflate the support of a candidate.
+	pub fn from_collections(collections: &SplitReadCollections, reference_names: &[String]) -> Self {
+		let split_reads = collections.normals.inner().iter()
+			.chain(collections.supplementaries.inner().iter());
+
+		Self::from_split_reads(split_reads, reference_names)
+	}
+
+	pub fn from_split_reads<'a>(split_reads: impl Iterator<Item = &'a SplitRead>, reference_names: &[String]) -> Self {
+		let mut junctions = CircularRnaJunctions::default();
+
+		for split_read in split_reads {
+			junctions.add_split_read(split_read);
+		}
+
+		junctions.into_candidates(reference_names)
+	}
+
+	pub fn get_candidate_count(&self) -> u64 {
+		self.candidate_count
+	}
+
+	pub fn get_supporting_read_count(&self) -> u64 {
+		self.supporting_read_count
+	}
+
+	/// Candidates sorted by descending support.
+	pub fn get_top_candidates(&self) -> &Vec<CircularRnaCandidate> {
+		&self.top_candidates
+	}
+
+	/// Empty after deserialization, the complete table is only available right after the calculation.
+	pub fn get_candidates(&self) -> &Vec<CircularRnaCandidate> {
+		&self.candidates
+	}
+
+	pub fn write_tsv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+		writeln!(writer, "reference\tstart\tend\tstrand\tsupporting_reads\tunique_positions")?;
+
+		for candidate in &self.candidates {
+			writeln!(
+				writer,
+				"{}\t{}\t{}\t{}\t{}\t{}",
+				candidate.reference_name,
+				candidate.start,
+				candidate.end,
+				candidate.get_strand(),
+				candidate.supporting_reads,
+				candidate.unique_positions
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// The score column holds the supporting reads, capped at 1000 as required by BED.
+	pub fn write_bed<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+		for (i, candidate) in self.candidates.iter().enumerate() {
+			writeln!(
+				writer,
+				"{}\t{}\t{}\tcirc_{}\t{}\t{}",
+				candidate.reference_name,
+				candidate.start,
+				candidate.end,
+				i + 1,
+				candidate.supporting_reads.min(1000),
+				candidate.get_strand()
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod circular_rna_candidate_tests {
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::circular::CircularRnaCandidates;
+	use crate::statistics::presentation::split_read::SplitRead;
+
+	fn split_read(fragments: Vec<(u32, bool)>) -> SplitRead {
+		let records = fragments.into_iter()
+			.map(|(start, is_reverse_strand)| {
+				let flags = PresentationFlags::new(true, true, is_reverse_strand, false, false, false, false, false, false, false, false);
+				PresentationRecord::new("read".to_string(), flags, 255, 50, 0, start, start + 50, 0, 0, 0)
+			})
+			.collect();
+
+		SplitRead::from((records, 0))
+	}
+
+	#[test]
+	fn from_split_reads_test() {
+		let split_reads = [
+			split_read(vec![(1000, false), (100, false)]),
+			split_read(vec![(1010, false), (110, false)]),
+			split_read(vec![(1010, false), (110, false)]),
+			split_read(vec![(100, true), (1000, true)]),
+			split_read(vec![(100, false), (1000, false)])
+		];
+		let reference_names = vec!["chr1".to_string()];
+
+		let candidates = CircularRnaCandidates::from_split_reads(split_reads.iter(), &reference_names);
+
+		assert_eq!(candidates.get_candidate_count(), 3);
+		assert_eq!(candidates.get_supporting_read_count(), 4);
+
+		let best = &candidates.get_top_candidates()[0];
+
+		assert_eq!(best.get_reference_name(), "chr1");
+		assert_eq!((best.get_start(), best.get_end()), (110, 1060));
+		assert_eq!(best.get_strand(), '+');
+		assert_eq!(best.get_supporting_reads(), 2);
+		assert_eq!(best.get_unique_positions(), 1);
+	}
+}
\ No newline at end of file
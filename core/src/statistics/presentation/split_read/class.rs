@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+use serde_derive::{Deserialize, Serialize};
+use crate::statistics::presentation::split_read::junction::SplitJunction;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SplitReadClass {
+	/// All fragments on the same reference and strand, in order along the reference.
+	Collinear,
+	/// Fragments on different references.
+	InterChromosomalChimera,
+	/// Fragments on the same reference, but on different strands.
+	StrandSwitch,
+	/// A later fragment maps upstream of an earlier one on the same strand (circular RNA).
+	BackSplice,
+	/// Less than two mapped fragments.
+	Unclassified
+}
+
+impl SplitReadClass {
+	pub const ALL: [SplitReadClass; 5] = [
+		SplitReadClass::Collinear,
+		SplitReadClass::InterChromosomalChimera,
+		SplitReadClass::StrandSwitch,
+		SplitReadClass::BackSplice,
+		SplitReadClass::Unclassified
+	];
+
+	/// Classifies a split read by the junctions between its mapped fragments.
+	pub fn classify(junctions: &[SplitJunction]) -> Self {
+		if junctions.is_empty() {
+			return SplitReadClass::Unclassified;
+		}
+
+		if junctions.iter().any(|junction| junction.is_inter_chromosomal()) {
+			return SplitReadClass::InterChromosomalChimera;
+		}
+
+		if junctions.iter().any(|junction| junction.is_strand_switch()) {
+			return SplitReadClass::StrandSwitch;
+		}
+
+		if junctions.iter().any(|junction| junction.is_back_splice()) {
+			return SplitReadClass::BackSplice;
+		}
+
+		SplitReadClass::Collinear
+	}
+}
+
+impl Display for SplitReadClass {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			SplitReadClass::Collinear => "collinear",
+			SplitReadClass::InterChromosomalChimera => "inter_chromosomal_chimera",
+			SplitReadClass::StrandSwitch => "strand_switch",
+			SplitReadClass::BackSplice => "back_splice",
+			SplitReadClass::Unclassified => "unclassified"
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+#[cfg(test)]
+mod split_read_class_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::class::SplitReadClass;
+	use crate::statistics::presentation::split_read::junction::SplitJunction;
+
+	fn fragment(ref_id: i32, start: u32, is_reverse_strand: bool) -> PresentationRecord {
+		let flags = PresentationFlags::new(true, true, is_reverse_strand, false, false, false, false, false, false, false, false);
+
+		PresentationRecord::new("read".to_string(), flags, 255, 50, 0, start, start + 49, 0, ref_id, ref_id)
+	}
+
+	#[rstest]
+	#[case(vec![(0, 100, false)], SplitReadClass::Unclassified)]
+	#[case(vec![(0, 100, false), (0, 1000, false)], SplitReadClass::Collinear)]
+	#[case(vec![(0, 1000, true), (0, 100, true)], SplitReadClass::Collinear)]
+	#[case(vec![(0, 100, false), (1, 1000, false)], SplitReadClass::InterChromosomalChimera)]
+	#[case(vec![(0, 100, false), (0, 1000, true)], SplitReadClass::StrandSwitch)]
+	#[case(vec![(0, 1000, false), (0, 100, false)], SplitReadClass::BackSplice)]
+	#[case(vec![(0, 100, true), (0, 1000, true)], SplitReadClass::BackSplice)]
+	#[case(vec![(0, 1000, false), (0, 100, true), (1, 50, true)], SplitReadClass::InterChromosomalChimera)]
+	fn classify_test(#[case] fragments: Vec<(i32, u32, bool)>, #[case] expected: SplitReadClass) {
+		let fragments: Vec<PresentationRecord> = fragments.into_iter()
+			.map(|(ref_id, start, is_reverse_strand)| fragment(ref_id, start, is_reverse_strand))
+			.collect();
+		let fragments: Vec<&PresentationRecord> = fragments.iter().collect();
+		let junctions = SplitJunction::from_fragments(&fragments);
+
+		assert_eq!(SplitReadClass::classify(&junctions), expected);
+	}
+}
\ No newline at end of file
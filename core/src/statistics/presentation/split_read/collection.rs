@@ -2,13 +2,18 @@ use std::collections::HashMap;
 use std::convert::TryInto;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde_derive::{Serialize, Deserialize};
+use crate::progress::CancellationToken;
 use crate::statistics::presentation::assembler::collection::PresentationAssemblerCollection;
 use crate::statistics::presentation::assembler::PresentationAssembler;
 use crate::statistics::presentation::split_read::partial::map::PartialSplitReadMap;
 use crate::statistics::presentation::split_read::partial::PartialSplitRead;
+use crate::statistics::presentation::split_read::quarantine::SplitReadQuarantine;
+use crate::statistics::presentation::record::PresentationRecord;
 use crate::statistics::presentation::split_read::SplitRead;
+use crate::statistics::presentation::split_read::tagged::assemble_tagged_records;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SplitReadCollection {
 	split_reads: Vec<SplitRead>
 }
@@ -31,12 +36,19 @@ impl SplitReadCollection {
 	}
 }
 
-impl Into<(SplitReadCollection, usize)> for PresentationAssembler {
-	fn into(self) -> (SplitReadCollection, usize) {
+impl Into<(SplitReadCollection, Vec<PresentationRecord>)> for PresentationAssembler {
+	/// Returns the assembled split reads together with the records of all fragments that could not be merged.
+	fn into(self) -> (SplitReadCollection, Vec<PresentationRecord>) {
 		let PresentationAssembler {
-			associated_records
+			associated_records,
+			mode
 		} = self;
 
+		let (mut split_reads, associated_records) = match mode {
+			AssemblerMode::Flags => (vec![], associated_records),
+			AssemblerMode::SegemehlTags => assemble_tagged_records(associated_records)
+		};
+
 		let mut partial_split_read_map = PartialSplitReadMap::from(associated_records);
 
 		let (
@@ -44,13 +56,18 @@ impl Into<(SplitReadCollection, usize)> for PresentationAssembler {
 			dropped_reads
 		) = merge_partial_split_read_map(&mut partial_split_read_map, false);
 
-		let split_reads: Vec<SplitRead> = completed_split_reads.into_iter().map(|completed_split_read| {
-			completed_split_read.try_into().unwrap()
-		}).collect();
+		split_reads.extend(completed_split_reads.into_iter().map(|completed_split_read| {
+			let split_read: SplitRead = completed_split_read.try_into().unwrap();
+			split_read
+		}));
+
+		let dropped_records = dropped_reads.into_iter()
+			.flat_map(|dropped_read| dropped_read.into_records())
+			.collect();
 
 		(SplitReadCollection {
 			split_reads
-		}, dropped_reads)
+		}, dropped_records)
 	}
 }
 
@@ -89,46 +106,55 @@ fn find_next_complete_read(partial_split_read_map: &PartialSplitReadMap) -> Opti
 	None
 }
 
-fn merge_partial_split_read_map(partial_split_read_map: &mut PartialSplitReadMap, has_recursed: bool) -> (Vec<PartialSplitRead>, usize) {
+fn merge_partial_split_read_map(partial_split_read_map: &mut PartialSplitReadMap, has_recursed: bool) -> (Vec<PartialSplitRead>, Vec<PartialSplitRead>) {
 	let mut completed_split_reads: Vec<PartialSplitRead> = remove_complete_reads(partial_split_read_map);
+	let mut dropped_reads: Vec<PartialSplitRead> = vec![];
 
 	while !partial_split_read_map.is_empty() {
 		let pair = get_next_pair(partial_split_read_map);
 
 		match pair {
 			Some((first_key, second_key)) => {
-				let completed_read = merge_pair(first_key, second_key, partial_split_read_map);
+				let completed_read = merge_pair(first_key, second_key, partial_split_read_map, &mut dropped_reads);
 				if let Some(completed) = completed_read {
 					completed_split_reads.push(completed);
 				}
 			}
 			None => {
-				let (completed, dropped_reads) =
+				let (completed, unmergeable_reads) =
 					handle_unmergeables(partial_split_read_map, has_recursed);
 				completed_split_reads.extend(completed);
+				dropped_reads.extend(unmergeable_reads);
 				return (completed_split_reads, dropped_reads);
 			}
 		}
 	}
 
-	return (completed_split_reads, 0);
+	(completed_split_reads, dropped_reads)
 }
 
-fn merge_pair(first_key: (i32, u32, usize), second_key: (i32, u32), partial_split_read_map: &mut PartialSplitReadMap) -> Option<PartialSplitRead> {
+fn merge_pair(first_key: (i32, u32, usize), second_key: (i32, u32), partial_split_read_map: &mut PartialSplitReadMap, dropped_reads: &mut Vec<PartialSplitRead>) -> Option<PartialSplitRead> {
 	if (first_key.0, first_key.1) == second_key {
-		merge_pair_at_same_position(first_key, partial_split_read_map)
+		merge_pair_at_same_position(first_key, partial_split_read_map, dropped_reads)
 	}
 	else {
-		merge_pair_at_different_positions(first_key, second_key, partial_split_read_map)
+		merge_pair_at_different_positions(first_key, second_key, partial_split_read_map, dropped_reads)
 	}
 }
 
-fn merge_pair_at_same_position(key: (i32, u32, usize), partial_split_read_map: &mut PartialSplitReadMap) -> Option<PartialSplitRead> {
+fn merge_pair_at_same_position(key: (i32, u32, usize), partial_split_read_map: &mut PartialSplitReadMap, dropped_reads: &mut Vec<PartialSplitRead>) -> Option<PartialSplitRead> {
 	let mut vec = partial_split_read_map.remove(&(key.0, key.1)).unwrap();
 	let first = vec.remove(key.2);
 	let second = vec.remove(0);
 
-	let combined = PartialSplitRead::combine(first, second).unwrap();
+	let combined = match PartialSplitRead::combine(first, second) {
+		Ok(combined) => combined,
+		Err((first, second)) => {
+			dropped_reads.push(first);
+			dropped_reads.push(second);
+			return None;
+		}
+	};
 
 	return if combined.is_complete() {
 		Some(combined)
@@ -139,7 +165,7 @@ fn merge_pair_at_same_position(key: (i32, u32, usize), partial_split_read_map: &
 	}
 }
 
-fn merge_pair_at_different_positions(first_key: (i32, u32, usize), second_key: (i32, u32), partial_split_read_map: &mut PartialSplitReadMap) -> Option<PartialSplitRead> {
+fn merge_pair_at_different_positions(first_key: (i32, u32, usize), second_key: (i32, u32), partial_split_read_map: &mut PartialSplitReadMap, dropped_reads: &mut Vec<PartialSplitRead>) -> Option<PartialSplitRead> {
 	let mut first_vec = partial_split_read_map.remove(&(first_key.0, first_key.1)).unwrap();
 	let first = first_vec.remove(first_key.2);
 	let mut second_vec = partial_split_read_map.remove(&second_key).unwrap();
@@ -149,7 +175,14 @@ fn merge_pair_at_different_positions(first_key: (i32, u32, usize), second_key: (
 		partial_split_read_map.insert((first_key.0, first_key.1), first_vec);
 	}
 
-	let combined = PartialSplitRead::combine(first, second).unwrap();
+	let combined = match PartialSplitRead::combine(first, second) {
+		Ok(combined) => combined,
+		Err((first, second)) => {
+			dropped_reads.push(first);
+			dropped_reads.push(second);
+			return None;
+		}
+	};
 
 	if combined.is_complete() {
 		return Some(combined);
@@ -168,30 +201,23 @@ fn merge_pair_at_different_positions(first_key: (i32, u32, usize), second_key: (
 	None
 }
 
-fn handle_unmergeables(partial_split_read_map: &mut PartialSplitReadMap, has_recursed: bool) -> (Vec<PartialSplitRead>, usize) {
+fn handle_unmergeables(partial_split_read_map: &mut PartialSplitReadMap, has_recursed: bool) -> (Vec<PartialSplitRead>, Vec<PartialSplitRead>) {
 	return if has_recursed {
-		let mut count = 0;
-		for (_, value) in partial_split_read_map.iter_mut() {
-			count += value.len();
-		}
-
-		return if let Ok(read) = brute_force_merge(partial_split_read_map) {
-			(vec![read], 0)
-		}
-		else {
-			(vec![], count)
+		match brute_force_merge(partial_split_read_map) {
+			Ok(read) => (vec![read], vec![]),
+			Err(dropped_reads) => (vec![], dropped_reads)
 		}
 	}
 	else {
 		let split_maps = split_by_name(partial_split_read_map);
 
 		let mut completed = vec![];
-		let mut dropped = 0;
+		let mut dropped = vec![];
 
 		for (_, mut entry) in split_maps {
-			let (mut completed_part,dropped_part) = merge_partial_split_read_map(&mut entry, true);
+			let (mut completed_part, mut dropped_part) = merge_partial_split_read_map(&mut entry, true);
 			completed.append(&mut completed_part);
-			dropped += dropped_part;
+			dropped.append(&mut dropped_part);
 		}
 
 		(completed, dropped)
@@ -221,6 +247,7 @@ fn split_by_name(partial_split_read_map: &mut PartialSplitReadMap) -> HashMap<St
 			else {
 				let mut new_map = PartialSplitReadMap::new();
 				new_map.insert(next_reads_key, vec![read]);
+				new_maps.insert(name, new_map);
 			}
 		}
 	}
@@ -228,33 +255,31 @@ fn split_by_name(partial_split_read_map: &mut PartialSplitReadMap) -> HashMap<St
 	new_maps
 }
 
-fn brute_force_merge(partial_split_read_map: &mut PartialSplitReadMap) -> Result<PartialSplitRead, usize> {
+/// Merges all fragments of one read regardless of their positions, on failure all fragments are returned.
+fn brute_force_merge(partial_split_read_map: &mut PartialSplitReadMap) -> Result<PartialSplitRead, Vec<PartialSplitRead>> {
 	let mut record = None;
 
-	let mut combined_count = 0usize;
-
 	while !partial_split_read_map.is_empty() {
 		let first_key = *partial_split_read_map.keys().next().unwrap();
 		let vec = partial_split_read_map.remove(&first_key).unwrap();
 
-		let vec_len = vec.len();
-		for (i, new_record) in vec.into_iter().enumerate() {
+		let mut remaining_vec = vec.into_iter();
+		while let Some(new_record) = remaining_vec.next() {
 			match record {
 				None => {
 					record = Some(new_record);
-					combined_count += 1;
 				}
 				Some(existing) => {
 					let combined = PartialSplitRead::combine(existing, new_record);
 					match combined {
 						Ok(combined) => {
 							record = Some(combined);
-							combined_count += 1;
 						}
-						Err((_,_)) => {
-							let remaining_record_count = count_remaining_records(partial_split_read_map);
-							let remaining_vec_count = vec_len - i;
-							return Err(combined_count + remaining_record_count + remaining_vec_count);
+						Err((existing, new_record)) => {
+							let mut dropped_reads = vec![existing, new_record];
+							dropped_reads.extend(remaining_vec);
+							dropped_reads.extend(drain_remaining_reads(partial_split_read_map));
+							return Err(dropped_reads);
 						}
 					}
 				}
@@ -267,19 +292,15 @@ fn brute_force_merge(partial_split_read_map: &mut PartialSplitReadMap) -> Result
 		Ok(record)
 	}
 	else {
-		Err(combined_count)
+		Err(vec![record])
 	}
 
 }
 
-fn count_remaining_records(partial_split_read_map: &PartialSplitReadMap) -> usize {
-	let mut count = 0;
-
-	for (_, vec) in partial_split_read_map.iter() {
-		count += vec.len();
-	}
-
-	count
+fn drain_remaining_reads(partial_split_read_map: &mut PartialSplitReadMap) -> Vec<PartialSplitRead> {
+	partial_split_read_map.drain()
+		.flat_map(|(_, vec)| vec)
+		.collect()
 }
 
 fn get_next_pair(partial_split_read_map: &PartialSplitReadMap) -> Option<((i32, u32, usize), (i32, u32))> {
@@ -332,63 +353,218 @@ pub struct SplitReadCollections {
 	pub(crate) duplicates: SplitReadCollection
 }
 
-impl From<PresentationAssemblerCollection> for (SplitReadCollections, usize) {
-	fn from(value: PresentationAssemblerCollection) -> Self {
+impl SplitReadCollections {
+	/// Reference id and position of every fragment end that continues in another fragment.
+	/// Duplicates and secondary alignments are left out, like for the circular RNA and fusion candidates.
+	pub fn get_breakpoints(&self) -> Vec<(i32, u32)> {
+		self.normals.inner().iter()
+			.chain(self.supplementaries.inner().iter())
+			.flat_map(|split_read| split_read.get_breakpoints())
+			.collect()
+	}
+
+	/// Merges the fragments of every assembler, returns `None` if the token was cancelled in between.
+	pub fn assemble(value: PresentationAssemblerCollection, cancellation_token: &CancellationToken) -> Option<(Self, SplitReadQuarantine)> {
 		let PresentationAssemblerCollection {
 			normals,
 			supplementaries,
 			secondaries,
 			duplicates,
+			..
 		} = value;
 
 		let merged_normals = 
-			presentation_assembler_collection_into_split_read_collection(normals);
+			presentation_assembler_collection_into_split_read_collection(normals, cancellation_token);
 		let merged_supplementaries = 
-			presentation_assembler_collection_into_split_read_collection(supplementaries);
+			presentation_assembler_collection_into_split_read_collection(supplementaries, cancellation_token);
 		let merged_secondaries = 
-			presentation_assembler_collection_into_split_read_collection(secondaries);
+			presentation_assembler_collection_into_split_read_collection(secondaries, cancellation_token);
 		let merged_duplicates = 
-			presentation_assembler_collection_into_split_read_collection(duplicates);
+			presentation_assembler_collection_into_split_read_collection(duplicates, cancellation_token);
+
+		if cancellation_token.is_cancelled() {
+			return None;
+		}
 
 		let normals = merged_normals.0;
 		let supplementaries = merged_supplementaries.0;
 		let secondaries = merged_secondaries.0;
 		let duplicates = merged_duplicates.0;
 
-		let dropped_reads = merged_normals.1 + merged_supplementaries.1 + merged_secondaries.1 + merged_duplicates.1;
+		let quarantine = vec![merged_normals.1, merged_supplementaries.1, merged_secondaries.1, merged_duplicates.1]
+			.into_iter()
+			.fold(SplitReadQuarantine::default(), SplitReadQuarantine::combine);
 
-		(
+		Some((
 			SplitReadCollections {
 				normals,
 				supplementaries,
 				secondaries,
 				duplicates
 			},
-			dropped_reads
-		)
+			quarantine
+		))
 	}
 }
 
-fn presentation_assembler_collection_into_split_read_collection(value: Vec<PresentationAssembler>) -> (SplitReadCollection, usize) {
+impl From<PresentationAssemblerCollection> for (SplitReadCollections, SplitReadQuarantine) {
+	fn from(value: PresentationAssemblerCollection) -> Self {
+		SplitReadCollections::assemble(value, &CancellationToken::new())
+			.expect("a new cancellation token is never cancelled")
+	}
+}
+
+/// Each assembler holds the fragments of one read name, so the merge only ever searches within a single template.
+/// Once the token is cancelled, the remaining assemblers are skipped.
+fn presentation_assembler_collection_into_split_read_collection(value: Vec<PresentationAssembler>, cancellation_token: &CancellationToken) -> (SplitReadCollection, SplitReadQuarantine) {
 	let collections = value.into_par_iter().fold(
 		|| {
 			(SplitReadCollection {
 				split_reads: vec![]
-			}, 0usize)
+			}, vec![])
 		},
 		|a,b| {
-			let (a1, a2) = a;
-			let (b1, b2): (SplitReadCollection, usize) = b.into();
-			(SplitReadCollection::combine(a1,b1), a2 + b2)
+			if cancellation_token.is_cancelled() {
+				return a;
+			}
+
+			let (a1, mut a2) = a;
+			let (b1, b2): (SplitReadCollection, Vec<PresentationRecord>) = b.into();
+			a2.extend(b2);
+			(SplitReadCollection::combine(a1,b1), a2)
 		}
-	).collect::<Vec<(SplitReadCollection, usize)>>();
+	).collect::<Vec<(SplitReadCollection, Vec<PresentationRecord>)>>();
 
-	collections.into_iter().fold(
+	let (collection, dropped_records) = collections.into_iter().fold(
 		(SplitReadCollection {
 			split_reads: vec![]
-		}, 0usize),
-		|(a1, a2),(b1, b2)| {
-			(SplitReadCollection::combine(a1,b1), a2 + b2)
+		}, vec![]),
+		|(a1, mut a2),(b1, b2)| {
+			a2.extend(b2);
+			(SplitReadCollection::combine(a1,b1), a2)
 		}
-	)
-}
\ No newline at end of file
+	);
+
+	(collection, SplitReadQuarantine::from_records(dropped_records))
+}
+#[cfg(test)]
+mod split_read_collection_tests {
+	use bam::Record;
+
+	use crate::statistics::calculation::assembler::map::{CalculationAssemblerMap, LocalAssemblerMap};
+	use crate::statistics::presentation::assembler::collection::PresentationAssemblerCollection;
+	use crate::statistics::presentation::assembler::PresentationAssembler;
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::collection::{SplitReadCollection, SplitReadCollections};
+	use crate::statistics::presentation::split_read::quarantine::{QuarantineReason, SplitReadQuarantine};
+	use crate::statistics::shared::assembler_mode::AssemblerMode;
+
+	fn record(name: &str, is_first_mate: bool, is_last_mate: bool, start: u32, p_next: i32) -> PresentationRecord {
+		let flags = PresentationFlags::new(true, true, false, is_last_mate, is_first_mate, false, false, false, false, false, false);
+		PresentationRecord::new(name.to_string(), flags, 255, 50, p_next, start, start + 50, 500, 0, 0)
+	}
+
+	fn mapped_record(flag: u16, start: i32, template_length: i32) -> Record {
+		let mut record = Record::new();
+		record.set_name(b"read".iter().cloned());
+		record.set_flag(flag);
+		record.set_ref_id(0);
+		record.set_start(start);
+		record.set_mate_ref_id(0);
+		record.set_cigar(b"50M".iter().cloned()).unwrap();
+		record.set_template_len(template_length);
+		record
+	}
+
+	fn tagged_record(flag: u16, start: i32, template_length: i32, split_index: i32) -> Record {
+		let mut record = mapped_record(flag, start, template_length);
+		record.tags_mut().push_num(b"XX", split_index * 50 + 1);
+		record.tags_mut().push_num(b"XY", split_index * 50 + 50);
+		record.tags_mut().push_num(b"XQ", split_index);
+		record
+	}
+
+	#[test]
+	fn tagged_chain_spans_template_lengths_and_supplementaries_test() {
+		let mut first = tagged_record(0x0, 100, 500, 0);
+		first.tags_mut().push_num(b"XN", 1001);
+		let mut second = tagged_record(0x800, 1000, -800, 1);
+		second.tags_mut().push_string(b"XC", b"chr1");
+		second.tags_mut().push_num(b"XP", 101);
+
+		let map = CalculationAssemblerMap::new(AssemblerMode::SegemehlTags);
+		let mut local = LocalAssemblerMap::default();
+		local.add_record(AssemblerMode::SegemehlTags, first);
+		local.add_record(AssemblerMode::SegemehlTags, second);
+		map.add_local(local);
+
+		let (collections, quarantine): (SplitReadCollections, SplitReadQuarantine) =
+			PresentationAssemblerCollection::from(map).into();
+
+		assert_eq!(collections.normals.inner().len(), 1);
+		assert_eq!(collections.normals.inner()[0].get_split_count(false), 2);
+		assert!(collections.supplementaries.inner().is_empty());
+		assert_eq!(quarantine.get_record_count(), 0);
+	}
+
+	fn mate_pair_map(skip_mate_pairs: bool) -> CalculationAssemblerMap {
+		let mut first = mapped_record(0x1 | 0x2 | 0x40, 100, 350);
+		let mut second = mapped_record(0x1 | 0x2 | 0x80 | 0x10, 400, -350);
+		first.set_mate_start(400);
+		second.set_mate_start(100);
+
+		let mut map = CalculationAssemblerMap::new(AssemblerMode::Flags);
+		let mut local = LocalAssemblerMap::default();
+		local.add_record(AssemblerMode::Flags, first);
+		local.add_record(AssemblerMode::Flags, second);
+		map.skip_mate_pairs(skip_mate_pairs);
+		map.add_local(local);
+		map
+	}
+
+	#[test]
+	fn skipped_mate_pairs_are_counted_test() {
+		let collection = PresentationAssemblerCollection::from(mate_pair_map(true));
+
+		assert_eq!(collection.get_skipped_mate_pairs(), 1);
+
+		let (collections, quarantine): (SplitReadCollections, SplitReadQuarantine) = collection.into();
+
+		assert!(collections.normals.inner().is_empty());
+		assert_eq!(quarantine.get_record_count(), 0);
+	}
+
+	#[test]
+	fn two_fragment_split_read_with_template_lengths_is_assembled_test() {
+		let collection = PresentationAssemblerCollection::from(mate_pair_map(false));
+
+		assert_eq!(collection.get_skipped_mate_pairs(), 0);
+
+		let (collections, quarantine): (SplitReadCollections, SplitReadQuarantine) = collection.into();
+
+		assert_eq!(collections.normals.inner().len(), 1);
+		assert_eq!(collections.normals.inner()[0].get_split_count(false), 2);
+		assert_eq!(quarantine.get_record_count(), 0);
+	}
+
+	#[test]
+	fn unmergeable_records_are_returned_test() {
+		let records = vec![
+			record("complete", true, false, 100, 300),
+			record("complete", false, true, 300, 100),
+			record("missing_end", true, false, 1000, 5000)
+		];
+
+		let (collection, dropped_records): (SplitReadCollection, Vec<PresentationRecord>) =
+			PresentationAssembler::new(records, AssemblerMode::Flags).into();
+
+		assert_eq!(collection.inner().len(), 1);
+		assert_eq!(dropped_records.len(), 1);
+
+		let quarantine = SplitReadQuarantine::from_records(dropped_records);
+
+		assert_eq!(quarantine.get_reads()[0].get_name(), "missing_end");
+		assert_eq!(quarantine.get_reads()[0].get_reason(), QuarantineReason::MissingEnd);
+	}
+}
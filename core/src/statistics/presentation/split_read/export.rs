@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::SplitRead;
+
+/// Assembled split reads kept after the statistics are calculated, so they can be written out for a genome browser.
+///
+/// The name column of both formats holds the read name and the class, separated by `|`.
+#[derive(Debug, Default)]
+pub struct SplitReadExport {
+	reference_names: Vec<String>,
+	split_reads: Vec<SplitRead>
+}
+
+impl SplitReadExport {
+	pub fn new(split_reads: Vec<SplitRead>, reference_names: &[String]) -> Self {
+		Self {
+			reference_names: reference_names.to_vec(),
+			split_reads
+		}
+	}
+
+	pub fn from_collections(collections: &SplitReadCollections, reference_names: &[String]) -> Self {
+		let split_reads = collections.normals.inner().iter()
+			.chain(collections.supplementaries.inner().iter())
+			.chain(collections.secondaries.inner().iter())
+			.chain(collections.duplicates.inner().iter())
+			.cloned()
+			.collect();
+
+		Self::new(split_reads, reference_names)
+	}
+
+	pub fn get_split_reads(&self) -> &Vec<SplitRead> {
+		&self.split_reads
+	}
+
+	/// One line per read, reference and strand, with one block per fragment.
+	/// BED12 can't span references or strands, so reads that do are split into several lines.
+	pub fn write_bed12<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+		for split_read in &self.split_reads {
+			let name = Self::get_name(split_read);
+			let mut blocks_by_strand: BTreeMap<(i32, bool), Vec<(u32, u32)>> = BTreeMap::new();
+
+			for fragment in split_read.get_mapped_fragments() {
+				blocks_by_strand.entry((fragment.ref_id, fragment.is_reverse_strand))
+					.or_default()
+					.push((fragment.start, fragment.end));
+			}
+
+			for ((ref_id, is_reverse_strand), blocks) in blocks_by_strand {
+				let reference_name = match self.reference_names.get(ref_id as usize) {
+					Some(reference_name) => reference_name,
+					None => continue
+				};
+
+				let blocks = Self::merge_blocks(blocks);
+				let start = blocks.first().unwrap().0;
+				let end = blocks.last().unwrap().1;
+
+				let block_sizes: String = blocks.iter()
+					.map(|(block_start, block_end)| format!("{},", block_end - block_start))
+					.collect();
+				let block_starts: String = blocks.iter()
+					.map(|(block_start, _)| format!("{},", block_start - start))
+					.collect();
+
+				writeln!(
+					writer,
+					"{}\t{}\t{}\t{}\t0\t{}\t{}\t{}\t0\t{}\t{}\t{}",
+					reference_name,
+					start,
+					end,
+					name,
+					if is_reverse_strand { '-' } else { '+' },
+					start,
+					end,
+					blocks.len(),
+					block_sizes,
+					block_starts
+				)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// One line per pair of consecutive fragments in read order.
+	pub fn write_bedpe<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+		for split_read in &self.split_reads {
+			let name = Self::get_name(split_read);
+
+			for junction in split_read.get_junctions() {
+				let (previous_reference_name, next_reference_name) = match (
+					self.reference_names.get(junction.previous.ref_id as usize),
+					self.reference_names.get(junction.next.ref_id as usize)
+				) {
+					(Some(previous), Some(next)) => (previous, next),
+					_ => continue
+				};
+
+				writeln!(
+					writer,
+					"{}\t{}\t{}\t{}\t{}\t{}\t{}\t.\t{}\t{}",
+					previous_reference_name,
+					junction.previous.start,
+					junction.previous.end,
+					next_reference_name,
+					junction.next.start,
+					junction.next.end,
+					name,
+					if junction.previous.is_reverse_strand { '-' } else { '+' },
+					if junction.next.is_reverse_strand { '-' } else { '+' }
+				)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn get_name(split_read: &SplitRead) -> String {
+		format!("{}|{}", split_read.get_name(), split_read.get_class())
+	}
+
+	/// BED12 blocks must not overlap, so overlapping fragments are joined into one block.
+	fn merge_blocks(mut blocks: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+		blocks.sort_unstable();
+
+		let mut merged: Vec<(u32, u32)> = Vec::with_capacity(blocks.len());
+
+		for (start, end) in blocks {
+			match merged.last_mut() {
+				Some(last) if start <= last.1 => last.1 = last.1.max(end),
+				_ => merged.push((start, end))
+			}
+		}
+
+		merged
+	}
+}
+
+#[cfg(test)]
+mod split_read_export_tests {
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::export::SplitReadExport;
+	use crate::statistics::presentation::split_read::SplitRead;
+
+	fn split_read(fragments: Vec<(i32, u32, u32, bool)>) -> SplitRead {
+		let records = fragments.into_iter()
+			.map(|(ref_id, start, end, is_reverse_strand)| {
+				let flags = PresentationFlags::new(true, true, is_reverse_strand, false, false, false, false, false, false, false, false);
+				PresentationRecord::new("read".to_string(), flags, 255, end - start, 0, start, end, 0, ref_id, 0)
+			})
+			.collect();
+
+		SplitRead::from((records, 0))
+	}
+
+	fn export(split_reads: Vec<SplitRead>) -> SplitReadExport {
+		SplitReadExport {
+			reference_names: vec!["chr1".to_string(), "chr2".to_string()],
+			split_reads
+		}
+	}
+
+	#[test]
+	fn write_bed12_test() {
+		let export = export(vec![
+			split_read(vec![(0, 100, 150, false), (0, 300, 320, false), (0, 310, 340, false)]),
+			split_read(vec![(0, 100, 150, false), (1, 500, 550, true)])
+		]);
+
+		let mut output = vec![];
+		export.write_bed12(&mut output).unwrap();
+
+		assert_eq!(String::from_utf8(output).unwrap(), concat!(
+			"chr1\t100\t340\tread|collinear\t0\t+\t100\t340\t0\t2\t50,40,\t0,200,\n",
+			"chr1\t100\t150\tread|inter_chromosomal_chimera\t0\t+\t100\t150\t0\t1\t50,\t0,\n",
+			"chr2\t500\t550\tread|inter_chromosomal_chimera\t0\t-\t500\t550\t0\t1\t50,\t0,\n"
+		));
+	}
+
+	#[test]
+	fn write_bedpe_test() {
+		let export = export(vec![
+			split_read(vec![(0, 1000, 1050, false), (0, 100, 150, false)]),
+			split_read(vec![(0, 100, 150, false)])
+		]);
+
+		let mut output = vec![];
+		export.write_bedpe(&mut output).unwrap();
+
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"chr1\t1000\t1050\tchr1\t100\t150\tread|back_splice\t.\t+\t+\n"
+		);
+	}
+}
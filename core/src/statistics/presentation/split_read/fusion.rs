@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use serde_derive::{Deserialize, Serialize};
+use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::junction::SplitJunction;
+use crate::statistics::presentation::split_read::SplitRead;
+
+/// Reference id and strand of both partners, the first partner always has the smaller reference id.
+type PartnerKey = (i32, bool, i32, bool);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionCandidate {
+	first_reference_name: String,
+	first_start: u32,
+	first_end: u32,
+	first_is_reverse_strand: bool,
+	second_reference_name: String,
+	second_start: u32,
+	second_end: u32,
+	second_is_reverse_strand: bool,
+	supporting_reads: u64
+}
+
+impl FusionCandidate {
+	pub fn get_first_reference_name(&self) -> &str {
+		&self.first_reference_name
+	}
+
+	/// The range of the breakpoints on the first reference.
+	pub fn get_first_range(&self) -> (u32, u32) {
+		(self.first_start, self.first_end)
+	}
+
+	pub fn get_first_strand(&self) -> char {
+		if self.first_is_reverse_strand { '-' } else { '+' }
+	}
+
+	pub fn get_second_reference_name(&self) -> &str {
+		&self.second_reference_name
+	}
+
+	/// The range of the breakpoints on the second reference.
+	pub fn get_second_range(&self) -> (u32, u32) {
+		(self.second_start, self.second_end)
+	}
+
+	pub fn get_second_strand(&self) -> char {
+		if self.second_is_reverse_strand { '-' } else { '+' }
+	}
+
+	pub fn get_supporting_reads(&self) -> u64 {
+		self.supporting_reads
+	}
+}
+
+#[derive(Debug)]
+struct BreakpointCluster {
+	first_start: u32,
+	first_end: u32,
+	second_start: u32,
+	second_end: u32,
+	reads: HashSet<usize>
+}
+
+impl BreakpointCluster {
+	fn new(first: u32, second: u32, read: usize) -> Self {
+		let mut reads = HashSet::new();
+		reads.insert(read);
+
+		Self {
+			first_start: first,
+			first_end: first,
+			second_start: second,
+			second_end: second,
+			reads
+		}
+	}
+
+	fn accepts(&self, first: u32, second: u32) -> bool {
+		first <= self.first_end + FusionCandidates::CLUSTER_DISTANCE &&
+			first + FusionCandidates::CLUSTER_DISTANCE >= self.first_start &&
+			second <= self.second_end + FusionCandidates::CLUSTER_DISTANCE &&
+			second + FusionCandidates::CLUSTER_DISTANCE >= self.second_start
+	}
+
+	fn add(&mut self, first: u32, second: u32, read: usize) {
+		self.first_start = self.first_start.min(first);
+		self.first_end = self.first_end.max(first);
+		self.second_start = self.second_start.min(second);
+		self.second_end = self.second_end.max(second);
+		self.reads.insert(read);
+	}
+}
+
+/// Breakpoint pairs collected one split read at a time, only the reads with inter-chromosomal junctions leave a trace.
+#[derive(Debug, Default)]
+pub struct FusionBreakpoints {
+	breakpoints: HashMap<PartnerKey, Vec<(u32, u32, usize)>>,
+	supporting_read_count: u64,
+	read_count: usize
+}
+
+impl FusionBreakpoints {
+	pub fn add_split_read(&mut self, split_read: &SplitRead) {
+		let read = self.read_count;
+		let mut is_supporting = false;
+
+		for junction in split_read.get_junctions().iter().filter(|junction| junction.is_inter_chromosomal()) {
+			let (key, first, second) = FusionCandidates::normalize(junction);
+			self.breakpoints.entry(key).or_default().push((first, second, read));
+			is_supporting = true;
+		}
+
+		if is_supporting {
+			self.supporting_read_count += 1;
+		}
+
+		self.read_count += 1;
+	}
+
+	pub fn into_candidates(self, reference_names: &[String]) -> FusionCandidates {
+		let mut candidates = vec![];
+
+		for ((first_ref_id, first_is_reverse_strand, second_ref_id, second_is_reverse_strand), mut pairs) in self.breakpoints {
+			let (first_reference_name, second_reference_name) = match (
+				reference_names.get(first_ref_id as usize),
+				reference_names.get(second_ref_id as usize)
+			) {
+				(Some(first), Some(second)) => (first, second),
+				_ => continue
+			};
+
+			pairs.sort_unstable();
+
+			let mut clusters: Vec<BreakpointCluster> = vec![];
+
+			for (first, second, read) in pairs {
+				match clusters.iter_mut().rev().find(|cluster| cluster.accepts(first, second)) {
+					Some(cluster) => cluster.add(first, second, read),
+					None => clusters.push(BreakpointCluster::new(first, second, read))
+				}
+			}
+
+			candidates.extend(clusters.into_iter().map(|cluster| FusionCandidate {
+				first_reference_name: first_reference_name.clone(),
+				first_start: cluster.first_start,
+				first_end: cluster.first_end + 1,
+				first_is_reverse_strand,
+				second_reference_name: second_reference_name.clone(),
+				second_start: cluster.second_start,
+				second_end: cluster.second_end + 1,
+				second_is_reverse_strand,
+				supporting_reads: cluster.reads.len() as u64
+			}));
+		}
+
+		candidates.sort_by(|a, b| {
+			b.supporting_reads.cmp(&a.supporting_reads)
+				.then_with(|| a.first_reference_name.cmp(&b.first_reference_name))
+				.then_with(|| a.first_start.cmp(&b.first_start))
+				.then_with(|| a.second_reference_name.cmp(&b.second_reference_name))
+				.then_with(|| a.second_start.cmp(&b.second_start))
+		});
+
+		FusionCandidates {
+			candidate_count: candidates.len() as u64,
+			supporting_read_count: self.supporting_read_count,
+			candidates
+		}
+	}
+}
+
+/// Breakpoint pairs of split reads whose fragments map to different references.
+///
+/// Only the summary is serialized, the clusters are kept for the export.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FusionCandidates {
+	candidate_count: u64,
+	supporting_read_count: u64,
+	#[serde(skip)]
+	candidates: Vec<FusionCandidate>
+}
+
+impl FusionCandidates {
+	/// Breakpoints closer than this on both references end up in the same cluster.
+	pub const CLUSTER_DISTANCE: u32 = 10;
+
+	/// Duplicates and secondary alignments are left out, so they can't inflate the support of a candidate.
+	pub fn from_collections(collections: &SplitReadCollections, reference_names: &[String]) -> Self {
+		let split_reads = collections.normals.inner().iter()
+			.chain(collections.supplementaries.inner().iter());
+
+		Self::from_split_reads(split_reads, reference_names)
+	}
+
+	pub fn from_split_reads<'a>(split_reads: impl Iterator<Item = &'a SplitRead>, reference_names: &[String]) -> Self {
+		let mut breakpoints = FusionBreakpoints::default();
+
+		for split_read in split_reads {
+			breakpoints.add_split_read(split_read);
+		}
+
+		breakpoints.into_candidates(reference_names)
+	}
+
+	/// Reading the opposite strand of a fusion visits the partners in reverse order with flipped strands,
+	/// so the junction is flipped whenever that puts the smaller reference first.
+	fn normalize(junction: &SplitJunction) -> (PartnerKey, u32, u32) {
+		let (previous, next) = (junction.previous, junction.next);
+
+		if previous.ref_id <= next.ref_id {
+			(
+				(previous.ref_id, previous.is_reverse_strand, next.ref_id, next.is_reverse_strand),
+				previous.get_outgoing_breakpoint(),
+				next.get_incoming_breakpoint()
+			)
+		}
+		else {
+			(
+				(next.ref_id, !next.is_reverse_strand, previous.ref_id, !previous.is_reverse_strand),
+				next.get_incoming_breakpoint(),
+				previous.get_outgoing_breakpoint()
+			)
+		}
+	}
+
+	pub fn get_candidate_count(&self) -> u64 {
+		self.candidate_count
+	}
+
+	pub fn get_supporting_read_count(&self) -> u64 {
+		self.supporting_read_count
+	}
+
+	/// Candidates sorted by descending support, empty after deserialization.
+	pub fn get_candidates(&self) -> &Vec<FusionCandidate> {
+		&self.candidates
+	}
+
+	/// The score column holds the supporting reads, capped at 1000 like in BED, the uncapped count follows as an extra column.
+	pub fn write_bedpe<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+		for (i, candidate) in self.candidates.iter().enumerate() {
+			writeln!(
+				writer,
+				"{}\t{}\t{}\t{}\t{}\t{}\tfusion_{}\t{}\t{}\t{}\t{}",
+				candidate.first_reference_name,
+				candidate.first_start,
+				candidate.first_end,
+				candidate.second_reference_name,
+				candidate.second_start,
+				candidate.second_end,
+				i + 1,
+				candidate.supporting_reads.min(1000),
+				candidate.get_first_strand(),
+				candidate.get_second_strand(),
+				candidate.supporting_reads
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod fusion_candidate_tests {
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::fusion::FusionCandidates;
+	use crate::statistics::presentation::split_read::SplitRead;
+
+	fn split_read(fragments: Vec<(i32, u32, bool)>) -> SplitRead {
+		let records = fragments.into_iter()
+			.map(|(ref_id, start, is_reverse_strand)| {
+				let flags = PresentationFlags::new(true, true, is_reverse_strand, false, false, false, false, false, false, false, false);
+				PresentationRecord::new("read".to_string(), flags, 255, 50, 0, start, start + 50, 0, ref_id, 0)
+			})
+			.collect();
+
+		SplitRead::from((records, 0))
+	}
+
+	#[test]
+	fn from_split_reads_test() {
+		let split_reads = [
+			split_read(vec![(0, 1000, false), (1, 500, false)]),
+			split_read(vec![(0, 1005, false), (1, 502, false)]),
+			split_read(vec![(1, 502, true), (0, 1000, true)]),
+			split_read(vec![(0, 5000, false), (1, 500, false)]),
+			split_read(vec![(0, 100, false), (0, 300, false)])
+		];
+		let reference_names = vec!["chr1".to_string(), "chr2".to_string()];
+
+		let candidates = FusionCandidates::from_split_reads(split_reads.iter(), &reference_names);
+
+		assert_eq!(candidates.get_candidate_count(), 2);
+		assert_eq!(candidates.get_supporting_read_count(), 4);
+
+		let best = &candidates.get_candidates()[0];
+
+		assert_eq!(best.get_first_reference_name(), "chr1");
+		assert_eq!(best.get_first_range(), (1050, 1056));
+		assert_eq!(best.get_first_strand(), '+');
+		assert_eq!(best.get_second_reference_name(), "chr2");
+		assert_eq!(best.get_second_range(), (500, 503));
+		assert_eq!(best.get_second_strand(), '+');
+		assert_eq!(best.get_supporting_reads(), 3);
+	}
+
+	#[test]
+	fn write_bedpe_test() {
+		let split_reads = [split_read(vec![(0, 1000, false), (1, 500, true)])];
+		let reference_names = vec!["chr1".to_string(), "chr2".to_string()];
+
+		let candidates = FusionCandidates::from_split_reads(split_reads.iter(), &reference_names);
+
+		let mut output = vec![];
+		candidates.write_bedpe(&mut output).unwrap();
+
+		assert_eq!(String::from_utf8(output).unwrap(), "chr1\t1050\t1051\tchr2\t550\t551\tfusion_1\t1\t+\t-\t1\n");
+	}
+}
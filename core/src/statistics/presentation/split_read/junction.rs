@@ -0,0 +1,84 @@
+use std::cmp::{max, min};
+use serde_derive::{Deserialize, Serialize};
+use crate::statistics::presentation::record::PresentationRecord;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FragmentPosition {
+	pub ref_id: i32,
+	pub start: u32,
+	pub end: u32,
+	pub is_reverse_strand: bool
+}
+
+impl From<&PresentationRecord> for FragmentPosition {
+	fn from(record: &PresentationRecord) -> Self {
+		Self {
+			ref_id: record.get_ref_id(),
+			start: record.get_start(),
+			end: record.get_end(),
+			is_reverse_strand: record.get_flags().get_is_reverse_strand()
+		}
+	}
+}
+
+impl FragmentPosition {
+	/// The reference position where the read leaves this fragment towards the next one.
+	pub fn get_outgoing_breakpoint(&self) -> u32 {
+		if self.is_reverse_strand { self.start } else { self.end }
+	}
+
+	/// The reference position where the read enters this fragment from the previous one.
+	pub fn get_incoming_breakpoint(&self) -> u32 {
+		if self.is_reverse_strand { self.end } else { self.start }
+	}
+}
+
+/// Two mapped fragments that follow each other in read order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitJunction {
+	pub previous: FragmentPosition,
+	pub next: FragmentPosition
+}
+
+impl SplitJunction {
+	/// Builds the junctions between all consecutive fragments, which have to be given in read order.
+	pub fn from_fragments(fragments: &[&PresentationRecord]) -> Vec<Self> {
+		fragments.windows(2)
+			.map(|pair| Self {
+				previous: pair[0].into(),
+				next: pair[1].into()
+			})
+			.collect()
+	}
+
+	pub fn is_inter_chromosomal(&self) -> bool {
+		self.previous.ref_id != self.next.ref_id
+	}
+
+	pub fn is_strand_switch(&self) -> bool {
+		!self.is_inter_chromosomal() && self.previous.is_reverse_strand != self.next.is_reverse_strand
+	}
+
+	/// A later fragment maps upstream of an earlier one on the same reference and strand.
+	/// On the reverse strand the read runs against the reference, so a collinear successor lies upstream.
+	pub fn is_back_splice(&self) -> bool {
+		if self.is_inter_chromosomal() || self.is_strand_switch() {
+			return false;
+		}
+
+		if self.previous.is_reverse_strand {
+			self.next.start > self.previous.start
+		}
+		else {
+			self.next.start < self.previous.start
+		}
+	}
+
+	/// The reference range spanned by both fragments, for a back-splice this is the circular RNA.
+	pub fn get_spanned_range(&self) -> (u32, u32) {
+		(
+			min(self.previous.start, self.next.start),
+			max(self.previous.end, self.next.end)
+		)
+	}
+}
\ No newline at end of file
@@ -2,18 +2,30 @@ use std::cmp::{max, min, Ordering};
 use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
 use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
 use crate::statistics::presentation::record::PresentationRecord;
+use crate::statistics::presentation::split_read::class::SplitReadClass;
+use crate::statistics::presentation::split_read::junction::{FragmentPosition, SplitJunction};
 use serde_derive::{Serialize,Deserialize};
 
+pub mod circular;
+pub mod class;
 pub mod collection;
+pub mod export;
+pub mod fusion;
 pub mod statistics;
+pub mod summary;
 pub mod partial;
+pub mod quarantine;
+pub mod junction;
+pub mod tagged;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitRead {
 	forward_strand_records: Vec<PresentationRecord>,
 	reverse_strand_records: Vec<PresentationRecord>,
 	unmapped_records: Vec<PresentationRecord>,
-	min_unmapped_records: usize
+	min_unmapped_records: usize,
+	junctions: Vec<SplitJunction>,
+	class: SplitReadClass
 }
 
 impl SplitRead {
@@ -79,6 +91,42 @@ impl SplitRead {
 		}
 	}
 
+	/// Junctions between consecutive mapped fragments, in read order.
+	pub fn get_junctions(&self) -> &Vec<SplitJunction> {
+		&self.junctions
+	}
+
+	/// Reference id and position of every fragment end that continues in another fragment.
+	pub fn get_breakpoints(&self) -> impl Iterator<Item = (i32, u32)> + '_ {
+		self.junctions.iter()
+			.flat_map(|junction| vec![
+				(junction.previous.ref_id, junction.previous.get_outgoing_breakpoint()),
+				(junction.next.ref_id, junction.next.get_incoming_breakpoint())
+			])
+	}
+
+	pub fn get_class(&self) -> SplitReadClass {
+		self.class
+	}
+
+	/// The name shared by all fragments of the read.
+	pub fn get_name(&self) -> String {
+		self.forward_strand_records.iter()
+			.chain(self.reverse_strand_records.iter())
+			.chain(self.unmapped_records.iter())
+			.next()
+			.map(|record| record.get_name())
+			.unwrap_or_default()
+	}
+
+	/// Positions of all mapped fragments, sorted by strand and start.
+	pub fn get_mapped_fragments(&self) -> Vec<FragmentPosition> {
+		self.forward_strand_records.iter()
+			.chain(self.reverse_strand_records.iter())
+			.map(FragmentPosition::from)
+			.collect()
+	}
+
 	pub fn get_split_count(&self, include_unmapped: bool) -> usize {
 		let mapped_splits =
 			self.forward_strand_records.len() +
@@ -168,7 +216,7 @@ impl SplitRead {
 		(gap_length_map, total_length, split_count, split_count_unmapped)
 	}
 
-	pub fn calculate_statistics_into(&self, gap_length_map: &CalculationFrequencyMap<i64>, total_length_map: &CalculationFrequencyMap<u32>, split_count_map: &CalculationFrequencyMap<usize>, split_count_unmapped_map: &CalculationFrequencyMap<usize>, unmapped_count_map: &CalculationFrequencyMap<usize>) {
+	pub fn calculate_statistics_into(&self, gap_length_map: &CalculationFrequencyMap<i64>, total_length_map: &CalculationFrequencyMap<u32>, split_count_map: &CalculationFrequencyMap<usize>, split_count_unmapped_map: &CalculationFrequencyMap<usize>, unmapped_count_map: &CalculationFrequencyMap<usize>, class_map: &CalculationFrequencyMap<SplitReadClass>) {
 		self.calculate_gap_lengths_into_map(gap_length_map);
 		let total_length = self.get_total_length();
 		let split_count = self.get_split_count(false);
@@ -180,12 +228,22 @@ impl SplitRead {
 		split_count_map.add_entry(split_count);
 		split_count_unmapped_map.add_entry(split_count_unmapped);
 		unmapped_count_map.add_entry(unmapped_count);
+		class_map.add_entry(self.class);
 	}
 }
 
 impl From<(Vec<PresentationRecord>, usize)> for SplitRead {
+	/// The junctions are built in read order, which is lost once the records are sorted by position.
 	fn from(records: (Vec<PresentationRecord>, usize)) -> Self {
 		let (associated_records, min_unmapped_records) = records;
+		let associated_records = into_read_order(associated_records);
+
+		let mapped_records: Vec<&PresentationRecord> = associated_records.iter()
+			.filter(|record| record.get_flags().get_is_mapped())
+			.collect();
+		let junctions = SplitJunction::from_fragments(&mapped_records);
+		let class = SplitReadClass::classify(&junctions);
+
 		let mut forward_strand_records: Vec<PresentationRecord> = Vec::new();
 		let mut reverse_strand_records: Vec<PresentationRecord> = Vec::new();
 		let mut unmapped_records: Vec<PresentationRecord> = Vec::new();
@@ -231,7 +289,85 @@ impl From<(Vec<PresentationRecord>, usize)> for SplitRead {
 			forward_strand_records,
 			reverse_strand_records,
 			unmapped_records,
-			min_unmapped_records
+			min_unmapped_records,
+			junctions,
+			class
+		}
+	}
+}
+
+/// Puts the fragments into read order: by the segemehl split index (`XQ`) if every fragment carries one,
+/// otherwise along the `RNEXT`/`PNEXT` links, starting at the fragment flagged as the first segment.
+/// Fragments that are not reached by the links keep their order behind the linked ones.
+fn into_read_order(mut records: Vec<PresentationRecord>) -> Vec<PresentationRecord> {
+	if records.iter().all(|record| record.get_split_tags().is_some()) {
+		records.sort_by_key(|record| {
+			let split_tags = record.get_split_tags().unwrap();
+			(split_tags.get_index(), split_tags.get_read_start())
+		});
+
+		return records;
+	}
+
+	let start = records.iter().position(|record| {
+		let flags = record.get_flags();
+		flags.get_is_first_mate() && !flags.get_is_last_mate()
+	});
+
+	let mut current = match start {
+		Some(start) => records.remove(start),
+		None => return records
+	};
+
+	let mut ordered_records = vec![];
+
+	loop {
+		let next = records.iter().position(|record|
+			current.get_p_next() as u32 == record.get_start() && current.get_r_next() == record.get_ref_id()
+		);
+
+		ordered_records.push(current);
+
+		match next {
+			Some(next) => current = records.remove(next),
+			None => break
 		}
 	}
-}
\ No newline at end of file
+
+	ordered_records.extend(records);
+	ordered_records
+}
+
+#[cfg(test)]
+mod split_read_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::record::split_tags::SplitTags;
+	use crate::statistics::presentation::split_read::class::SplitReadClass;
+	use crate::statistics::presentation::split_read::SplitRead;
+
+	fn fragment(is_first_mate: bool, is_last_mate: bool, start: u32, p_next: i32) -> PresentationRecord {
+		let flags = PresentationFlags::new(true, true, false, is_last_mate, is_first_mate, false, false, false, false, false, false);
+		PresentationRecord::new("read".to_string(), flags, 255, 50, p_next, start, start + 49, 0, 0, 0)
+	}
+
+	fn tagged_fragment(index: u32, start: u32) -> PresentationRecord {
+		fragment(false, false, start, 0)
+			.with_split_tags(SplitTags::new(index * 50 + 1, index * 50 + 50, index, None, None, None))
+	}
+
+	#[rstest]
+	#[case(vec![tagged_fragment(0, 1000), tagged_fragment(1, 100)], SplitReadClass::BackSplice)]
+	#[case(vec![tagged_fragment(1, 100), tagged_fragment(0, 1000)], SplitReadClass::BackSplice)]
+	#[case(vec![tagged_fragment(1, 1000), tagged_fragment(0, 100)], SplitReadClass::Collinear)]
+	#[case(vec![fragment(true, false, 1000, 500), fragment(false, false, 500, 100), fragment(false, true, 100, 1000)], SplitReadClass::BackSplice)]
+	#[case(vec![fragment(false, true, 100, 1000), fragment(false, false, 500, 100), fragment(true, false, 1000, 500)], SplitReadClass::BackSplice)]
+	#[case(vec![fragment(false, false, 1000, 2000), fragment(false, true, 2000, 100), fragment(true, false, 100, 1000)], SplitReadClass::Collinear)]
+	fn classified_in_read_order_test(#[case] records: Vec<PresentationRecord>, #[case] expected: SplitReadClass) {
+		let split_read = SplitRead::from((records, 0));
+
+		assert_eq!(split_read.get_class(), expected);
+	}
+}
@@ -61,6 +61,42 @@ impl PartialSplitRead {
 		}
 	}
 
+	/// All fragments of the partial read, not necessarily in read order.
+	pub fn into_records(self) -> Vec<PresentationRecord> {
+		match self {
+			PartialSplitRead::SingleSplitRead(read) |
+			PartialSplitRead::StartOnly(read) |
+			PartialSplitRead::EndOnly(read) => {
+				vec![read]
+			}
+			PartialSplitRead::MiddleOnly(middle) => {
+				middle
+			}
+			PartialSplitRead::StartAndMiddle(read, mut middle) |
+			PartialSplitRead::MiddleAndEnd(mut middle, read) => {
+				middle.push(read);
+				middle
+			}
+			PartialSplitRead::StartAndEnd(first, second) |
+			PartialSplitRead::EndAndStart(first, second) => {
+				vec![first, second]
+			}
+			PartialSplitRead::StartMiddleEnd(first, mut middle, second) |
+			PartialSplitRead::EndAndStartAndMiddle(first, second, mut middle) |
+			PartialSplitRead::MiddleAndEndAndStart(mut middle, first, second) => {
+				middle.push(first);
+				middle.push(second);
+				middle
+			}
+			PartialSplitRead::MiddleAndEndAndStartAndMiddle(mut end_middle, end, start, start_middle) => {
+				end_middle.push(end);
+				end_middle.push(start);
+				end_middle.extend(start_middle);
+				end_middle
+			}
+		}
+	}
+
 	pub fn get_template_length(&self) -> u32 {
 		match self {
 			PartialSplitRead::SingleSplitRead(read) |
@@ -648,10 +684,11 @@ impl TryInto<SplitRead> for PartialSplitRead {
 			PartialSplitRead::StartAndEnd(start, end) => {
 				(vec![start, end], 0)
 			}
-			PartialSplitRead::StartMiddleEnd(start, mut middle, end) => {
-				middle.push(start);
-				middle.push(end);
-				(middle, 0)
+			PartialSplitRead::StartMiddleEnd(start, middle, end) => {
+				let mut vec = vec![start];
+				vec.extend(middle);
+				vec.push(end);
+				(vec, 0)
 			}
 			PartialSplitRead::StartOnly(read) |
 			PartialSplitRead::EndOnly(read) => {
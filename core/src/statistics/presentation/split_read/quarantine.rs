@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use serde_derive::{Deserialize, Serialize};
+use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+use crate::statistics::presentation::record::PresentationRecord;
+
+/// Why the fragments of a read could not be assembled into a split read.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuarantineReason {
+	/// None of the fragments is flagged as the first segment.
+	MissingStart,
+	/// None of the fragments is flagged as the last segment.
+	MissingEnd,
+	/// Start and end are present, but the `RNEXT`/`PNEXT` of a fragment before the end points to no other fragment.
+	ConflictingNextPosition,
+	/// Every fragment is linked, but the assembler could still not chain them.
+	Unclassified
+}
+
+impl QuarantineReason {
+	pub const ALL: [QuarantineReason; 4] = [
+		QuarantineReason::MissingStart,
+		QuarantineReason::MissingEnd,
+		QuarantineReason::ConflictingNextPosition,
+		QuarantineReason::Unclassified
+	];
+
+	/// The fragments have to share the read name.
+	pub fn classify(records: &[PresentationRecord]) -> Self {
+		if !records.iter().any(|record| record.get_flags().get_is_first_mate()) {
+			QuarantineReason::MissingStart
+		}
+		else if !records.iter().any(|record| record.get_flags().get_is_last_mate()) {
+			QuarantineReason::MissingEnd
+		}
+		else if records.iter().any(|record| !record.get_flags().get_is_last_mate() && !Self::points_to_fragment(record, records)) {
+			QuarantineReason::ConflictingNextPosition
+		}
+		else {
+			QuarantineReason::Unclassified
+		}
+	}
+
+	fn points_to_fragment(record: &PresentationRecord, records: &[PresentationRecord]) -> bool {
+		records.iter().any(|other| record.get_p_next() as u32 == other.get_start() && record.get_r_next() == other.get_ref_id())
+	}
+}
+
+impl Display for QuarantineReason {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			QuarantineReason::MissingStart => "missing_start",
+			QuarantineReason::MissingEnd => "missing_end",
+			QuarantineReason::ConflictingNextPosition => "conflicting_next_position",
+			QuarantineReason::Unclassified => "unclassified"
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedRead {
+	name: String,
+	reason: QuarantineReason,
+	records: Vec<PresentationRecord>
+}
+
+impl QuarantinedRead {
+	pub fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn get_reason(&self) -> QuarantineReason {
+		self.reason
+	}
+
+	pub fn get_records(&self) -> &Vec<PresentationRecord> {
+		&self.records
+	}
+}
+
+/// Fragments dropped by the assembler, grouped by read name.
+///
+/// The reads can be left out, the number of dropped fragments per reason is kept either way.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SplitReadQuarantine {
+	reads: Vec<QuarantinedRead>,
+	#[serde(default)]
+	reason_map: PresentationFrequencyMap<QuarantineReason>
+}
+
+impl SplitReadQuarantine {
+	pub fn from_records(records: Vec<PresentationRecord>) -> Self {
+		let mut records_by_name: HashMap<String, Vec<PresentationRecord>> = HashMap::new();
+
+		for record in records {
+			records_by_name.entry(record.get_name()).or_default().push(record);
+		}
+
+		let mut reads: Vec<QuarantinedRead> = records_by_name.into_iter()
+			.map(|(name, records)| QuarantinedRead {
+				name,
+				reason: QuarantineReason::classify(&records),
+				records
+			})
+			.collect();
+
+		reads.sort_by(|a, b| a.name.cmp(&b.name));
+
+		let mut reason_map = PresentationFrequencyMap::new();
+
+		for read in &reads {
+			for _ in &read.records {
+				reason_map.add_entry(read.reason);
+			}
+		}
+
+		Self {
+			reads,
+			reason_map
+		}
+	}
+
+	pub fn combine(mut a: Self, b: Self) -> Self {
+		a.reads.extend(b.reads);
+		a.reason_map = PresentationFrequencyMap::merge(&a.reason_map, &b.reason_map);
+		a
+	}
+
+	/// Drops the fragments, only the counts remain.
+	pub fn without_reads(mut self) -> Self {
+		self.reads = vec![];
+		self
+	}
+
+	pub fn get_reads(&self) -> &Vec<QuarantinedRead> {
+		&self.reads
+	}
+
+	/// Number of dropped fragments.
+	pub fn get_record_count(&self) -> usize {
+		self.reason_map.get_frequency_sum() as usize
+	}
+
+	/// Number of dropped fragments per reason.
+	pub fn get_reason_map(&self) -> PresentationFrequencyMap<QuarantineReason> {
+		self.reason_map.clone()
+	}
+}
+
+#[cfg(test)]
+mod split_read_quarantine_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::split_read::quarantine::{QuarantineReason, SplitReadQuarantine};
+
+	fn record(name: &str, is_first_mate: bool, is_last_mate: bool, template_length: u32) -> PresentationRecord {
+		linked_record(name, is_first_mate, is_last_mate, template_length, 0)
+	}
+
+	fn linked_record(name: &str, is_first_mate: bool, is_last_mate: bool, template_length: u32, p_next: i32) -> PresentationRecord {
+		let flags = PresentationFlags::new(true, true, false, is_last_mate, is_first_mate, false, false, false, false, false, false);
+		PresentationRecord::new(name.to_string(), flags, 255, 50, p_next, 100, 150, template_length, 0, 0)
+	}
+
+	#[rstest]
+	#[case(vec![(false, false, 500), (false, true, 500)], QuarantineReason::MissingStart)]
+	#[case(vec![(true, false, 500), (false, false, 500)], QuarantineReason::MissingEnd)]
+	#[case(vec![(true, false, 500), (false, true, 500)], QuarantineReason::ConflictingNextPosition)]
+	fn classify_test(#[case] fragments: Vec<(bool, bool, u32)>, #[case] expected: QuarantineReason) {
+		let records: Vec<PresentationRecord> = fragments.into_iter()
+			.map(|(is_first_mate, is_last_mate, template_length)| record("read", is_first_mate, is_last_mate, template_length))
+			.collect();
+
+		assert_eq!(QuarantineReason::classify(&records), expected);
+	}
+
+	#[test]
+	fn linked_fragments_are_unclassified_test() {
+		let records = vec![
+			linked_record("read", true, false, 500, 100),
+			linked_record("read", false, true, 500, 100)
+		];
+
+		assert_eq!(QuarantineReason::classify(&records), QuarantineReason::Unclassified);
+	}
+
+	#[test]
+	fn from_records_test() {
+		let quarantine = SplitReadQuarantine::from_records(vec![
+			record("a", true, false, 500),
+			record("b", true, false, 500),
+			record("a", false, false, 500),
+			record("b", false, true, 500)
+		]);
+
+		assert_eq!(quarantine.get_reads().len(), 2);
+		assert_eq!(quarantine.get_record_count(), 4);
+		assert_eq!(quarantine.get_reason_map().get(&QuarantineReason::MissingEnd), Some(2));
+		assert_eq!(quarantine.get_reason_map().get(&QuarantineReason::ConflictingNextPosition), Some(2));
+	}
+
+	#[test]
+	fn without_reads_keeps_the_counts_test() {
+		let quarantine = SplitReadQuarantine::combine(
+			SplitReadQuarantine::from_records(vec![record("a", true, false, 500)]).without_reads(),
+			SplitReadQuarantine::from_records(vec![record("b", false, true, 500), record("b", false, false, 500)])
+		);
+
+		assert_eq!(quarantine.get_reads().len(), 1);
+		assert_eq!(quarantine.get_record_count(), 3);
+		assert_eq!(quarantine.get_reason_map().get(&QuarantineReason::MissingEnd), Some(1));
+		assert_eq!(quarantine.get_reason_map().get(&QuarantineReason::MissingStart), Some(2));
+	}
+}
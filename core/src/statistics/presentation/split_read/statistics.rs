@@ -1,8 +1,12 @@
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde_derive::{Serialize, Deserialize};
 use crate::statistics::calculation::frequency_map::CalculationFrequencyMap;
 use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
 use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::class::SplitReadClass;
+use crate::statistics::presentation::split_read::quarantine::{QuarantineReason, SplitReadQuarantine};
+use crate::statistics::presentation::split_read::SplitRead;
+use crate::statistics::shared::rebinning::Rebinning;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitReadStatistics {
@@ -10,7 +14,13 @@ pub struct SplitReadStatistics {
 	total_length_map: PresentationFrequencyMap<u32>,
 	split_count_map: PresentationFrequencyMap<usize>,
 	split_count_unmapped_map: PresentationFrequencyMap<usize>,
-	unmapped_count_map: PresentationFrequencyMap<usize>
+	unmapped_count_map: PresentationFrequencyMap<usize>,
+	#[serde(default)]
+	class_map: PresentationFrequencyMap<SplitReadClass>,
+	#[serde(default)]
+	quarantine_reason_map: PresentationFrequencyMap<QuarantineReason>,
+	#[serde(default)]
+	skipped_mate_pair_count: u64
 }
 
 impl SplitReadStatistics {
@@ -20,10 +30,29 @@ impl SplitReadStatistics {
 			total_length_map: PresentationFrequencyMap::new(),
 			split_count_map: PresentationFrequencyMap::new(),
 			split_count_unmapped_map: PresentationFrequencyMap::new(),
-			unmapped_count_map: PresentationFrequencyMap::new()
+			unmapped_count_map: PresentationFrequencyMap::new(),
+			class_map: PresentationFrequencyMap::new(),
+			quarantine_reason_map: PresentationFrequencyMap::new(),
+			skipped_mate_pair_count: 0
 		}
 	}
 
+	/// Adds the number of dropped fragments per reason.
+	pub fn with_quarantine(mut self, quarantine: &SplitReadQuarantine) -> Self {
+		self.quarantine_reason_map = quarantine.get_reason_map();
+		self
+	}
+
+	pub fn with_skipped_mate_pairs(mut self, skipped_mate_pair_count: u64) -> Self {
+		self.skipped_mate_pair_count = skipped_mate_pair_count;
+		self
+	}
+
+	/// Templates that were not assembled because they looked like ordinary paired-end mates, 0 unless skipping was enabled.
+	pub fn get_skipped_mate_pair_count(&self) -> u64 {
+		self.skipped_mate_pair_count
+	}
+
 	pub fn get_gap_length_map(&self) -> &PresentationFrequencyMap<i64> {
 		&self.gap_length_map
 	}
@@ -43,41 +72,103 @@ impl SplitReadStatistics {
 	pub fn get_unmapped_count_map(&self) -> &PresentationFrequencyMap<usize> {
 		&self.unmapped_count_map
 	}
+
+	pub fn get_class_map(&self) -> &PresentationFrequencyMap<SplitReadClass> {
+		&self.class_map
+	}
+
+	pub fn get_class_count(&self, class: SplitReadClass) -> u64 {
+		self.class_map.get(&class).unwrap_or(0)
+	}
+
+	pub fn get_quarantine_reason_map(&self) -> &PresentationFrequencyMap<QuarantineReason> {
+		&self.quarantine_reason_map
+	}
+
+	pub fn get_quarantine_count(&self, reason: QuarantineReason) -> u64 {
+		self.quarantine_reason_map.get(&reason).unwrap_or(0)
+	}
+
+	/// Only the length maps, the counts of splits and unmapped fragments stay small.
+	pub(crate) fn rebin(&mut self, rebinning: Rebinning) {
+		self.gap_length_map.rebin(rebinning);
+		self.total_length_map.rebin(rebinning);
+	}
 }
 
-impl From<SplitReadCollections> for SplitReadStatistics {
-	fn from(value: SplitReadCollections) -> Self {
-		println!("Calculating Statistics");
-
-		let gap_length_map = CalculationFrequencyMap::<i64>::new();
-		let total_length_map = CalculationFrequencyMap::<u32>::new();
-		let split_count_map = CalculationFrequencyMap::<usize>::new();
-		let split_count_unmapped_map = CalculationFrequencyMap::<usize>::new();
-		let unmapped_count_map = CalculationFrequencyMap::<usize>::new();
-
-		let SplitReadCollections {
-			normals, supplementaries, secondaries, duplicates
-		} = value;
-
-		normals.into_inner().into_par_iter().for_each(|item| {
-			item.calculate_statistics_into(&gap_length_map, &total_length_map, &split_count_map, &split_count_unmapped_map, &unmapped_count_map)
-		});
-		supplementaries.into_inner().into_par_iter().for_each(|item| {
-			item.calculate_statistics_into(&gap_length_map, &total_length_map, &split_count_map, &split_count_unmapped_map, &unmapped_count_map)
-		});
-		secondaries.into_inner().into_par_iter().for_each(|item| {
-			item.calculate_statistics_into(&gap_length_map, &total_length_map, &split_count_map, &split_count_unmapped_map, &unmapped_count_map)
-		});
-		duplicates.into_inner().into_par_iter().for_each(|item| {
-			item.calculate_statistics_into(&gap_length_map, &total_length_map, &split_count_map, &split_count_unmapped_map, &unmapped_count_map)
-		});
+impl Default for SplitReadStatistics {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Split read statistics that are added one read at a time, from several threads if necessary.
+#[derive(Debug)]
+pub struct SplitReadStatisticsCalculationData {
+	gap_length_map: CalculationFrequencyMap<i64>,
+	total_length_map: CalculationFrequencyMap<u32>,
+	split_count_map: CalculationFrequencyMap<usize>,
+	split_count_unmapped_map: CalculationFrequencyMap<usize>,
+	unmapped_count_map: CalculationFrequencyMap<usize>,
+	class_map: CalculationFrequencyMap<SplitReadClass>
+}
 
+impl SplitReadStatisticsCalculationData {
+	pub fn new() -> Self {
 		Self {
-			gap_length_map: gap_length_map.into(),
-			total_length_map: total_length_map.into(),
-			split_count_map: split_count_map.into(),
-			split_count_unmapped_map: split_count_unmapped_map.into(),
-			unmapped_count_map: unmapped_count_map.into()
+			gap_length_map: CalculationFrequencyMap::new(),
+			total_length_map: CalculationFrequencyMap::new(),
+			split_count_map: CalculationFrequencyMap::new(),
+			split_count_unmapped_map: CalculationFrequencyMap::new(),
+			unmapped_count_map: CalculationFrequencyMap::new(),
+			class_map: CalculationFrequencyMap::new()
 		}
 	}
-}
\ No newline at end of file
+
+	pub fn add_split_read(&self, split_read: &SplitRead) {
+		split_read.calculate_statistics_into(
+			&self.gap_length_map,
+			&self.total_length_map,
+			&self.split_count_map,
+			&self.split_count_unmapped_map,
+			&self.unmapped_count_map,
+			&self.class_map
+		);
+	}
+}
+
+impl Default for SplitReadStatisticsCalculationData {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl From<SplitReadStatisticsCalculationData> for SplitReadStatistics {
+	fn from(value: SplitReadStatisticsCalculationData) -> Self {
+		Self {
+			gap_length_map: value.gap_length_map.into(),
+			total_length_map: value.total_length_map.into(),
+			split_count_map: value.split_count_map.into(),
+			split_count_unmapped_map: value.split_count_unmapped_map.into(),
+			unmapped_count_map: value.unmapped_count_map.into(),
+			class_map: value.class_map.into(),
+			quarantine_reason_map: PresentationFrequencyMap::new(),
+			skipped_mate_pair_count: 0
+		}
+	}
+}
+
+impl From<SplitReadCollections> for SplitReadStatistics {
+	fn from(value: SplitReadCollections) -> Self {
+		let calculation_data = SplitReadStatisticsCalculationData::new();
+
+		value.normals.inner().par_iter()
+			.chain(value.supplementaries.inner().par_iter())
+			.chain(value.secondaries.inner().par_iter())
+			.chain(value.duplicates.inner().par_iter())
+			.for_each(|split_read| calculation_data.add_split_read(split_read));
+
+		calculation_data.into()
+	}
+}
+
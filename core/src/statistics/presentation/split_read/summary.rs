@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::statistics::presentation::split_read::circular::CircularRnaJunctions;
+use crate::statistics::presentation::split_read::collection::SplitReadCollections;
+use crate::statistics::presentation::split_read::fusion::FusionBreakpoints;
+use crate::statistics::presentation::split_read::quarantine::SplitReadQuarantine;
+use crate::statistics::presentation::split_read::statistics::SplitReadStatisticsCalculationData;
+use crate::statistics::presentation::split_read::SplitRead;
+
+/// Everything the presentation data keeps of the split reads, collected one read at a time,
+/// so a read can be dropped as soon as it is assembled.
+///
+/// The reads are only kept for the split read export and the dropped fragments only for the quarantine export.
+#[derive(Debug, Default)]
+pub struct SplitReadSummary {
+	pub(crate) statistics: SplitReadStatisticsCalculationData,
+	pub(crate) circular_rna: CircularRnaJunctions,
+	pub(crate) fusion: FusionBreakpoints,
+	pub(crate) breakpoints: HashMap<(i32, u32), u64>,
+	pub(crate) quarantine: SplitReadQuarantine,
+	pub(crate) split_reads: Vec<SplitRead>,
+	/// Templates left out of the assembly as ordinary paired-end mates.
+	pub(crate) skipped_mate_pairs: u64,
+	pub(crate) keep_split_reads: bool,
+	pub(crate) keep_quarantined_reads: bool
+}
+
+impl SplitReadSummary {
+	pub fn new(keep_split_reads: bool, keep_quarantined_reads: bool) -> Self {
+		Self {
+			keep_split_reads,
+			keep_quarantined_reads,
+			..Default::default()
+		}
+	}
+
+	/// The statistics are calculated in parallel, the candidates and breakpoints one read after another.
+	pub fn from_collections(collections: SplitReadCollections, quarantine: SplitReadQuarantine, keep_split_reads: bool, keep_quarantined_reads: bool) -> Self {
+		let mut summary = Self::new(keep_split_reads, keep_quarantined_reads);
+
+		let SplitReadCollections {
+			normals,
+			supplementaries,
+			secondaries,
+			duplicates
+		} = collections;
+
+		for (collection, is_candidate) in [(normals, true), (supplementaries, true), (secondaries, false), (duplicates, false)] {
+			collection.inner().par_iter().for_each(|split_read| summary.statistics.add_split_read(split_read));
+
+			for split_read in collection.into_inner() {
+				summary.add_to_candidates(split_read, is_candidate);
+			}
+		}
+
+		summary.add_quarantine(quarantine);
+		summary
+	}
+
+	/// Duplicates and secondary alignments are no candidates, so they can't inflate the support of a candidate or breakpoint.
+	pub fn add_split_read(&mut self, split_read: SplitRead, is_candidate: bool) {
+		self.statistics.add_split_read(&split_read);
+		self.add_to_candidates(split_read, is_candidate);
+	}
+
+	fn add_to_candidates(&mut self, split_read: SplitRead, is_candidate: bool) {
+		if is_candidate {
+			self.circular_rna.add_split_read(&split_read);
+			self.fusion.add_split_read(&split_read);
+
+			for breakpoint in split_read.get_breakpoints() {
+				*self.breakpoints.entry(breakpoint).or_default() += 1;
+			}
+		}
+
+		if self.keep_split_reads {
+			self.split_reads.push(split_read);
+		}
+	}
+
+	pub fn add_quarantine(&mut self, quarantine: SplitReadQuarantine) {
+		let quarantine = if self.keep_quarantined_reads {
+			quarantine
+		}
+		else {
+			quarantine.without_reads()
+		};
+
+		self.quarantine = SplitReadQuarantine::combine(std::mem::take(&mut self.quarantine), quarantine);
+	}
+}
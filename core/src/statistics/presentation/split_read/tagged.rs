@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use crate::statistics::presentation::record::PresentationRecord;
+use crate::statistics::presentation::split_read::SplitRead;
+
+/// Chains the fragments of each read and template segment by their segemehl split index (`XQ`).
+///
+/// Returns the assembled split reads together with all records that could not be chained,
+/// either because they carry no split tags or because their fragments do not form a complete chain.
+pub fn assemble_tagged_records(records: Vec<PresentationRecord>) -> (Vec<SplitRead>, Vec<PresentationRecord>) {
+	let mut remaining_records = vec![];
+	let mut records_by_segment: HashMap<(String, u8), Vec<PresentationRecord>> = HashMap::new();
+
+	for record in records {
+		if record.get_split_tags().is_none() {
+			remaining_records.push(record);
+			continue;
+		}
+
+		let segment_index = record.get_flags().get_segment_index();
+		records_by_segment.entry((record.get_name(), segment_index)).or_default().push(record);
+	}
+
+	let mut split_reads = vec![];
+
+	for (_, mut fragments) in records_by_segment {
+		fragments.sort_by_key(|fragment| {
+			let split_tags = fragment.get_split_tags().unwrap();
+			(split_tags.get_index(), split_tags.get_read_start())
+		});
+
+		if is_complete_chain(&fragments) {
+			split_reads.push(SplitRead::from((fragments, 0)));
+		}
+		else {
+			remaining_records.extend(fragments);
+		}
+	}
+
+	(split_reads, remaining_records)
+}
+
+/// A chain is complete if the split indices run from 0 without gaps or duplicates,
+/// only the first fragment lacks a predecessor (`XC`/`XP`) and only the last one lacks a successor (`XN`).
+fn is_complete_chain(fragments: &[PresentationRecord]) -> bool {
+	if fragments.is_empty() {
+		return false;
+	}
+
+	let last_index = fragments.len() - 1;
+
+	fragments.iter().enumerate().all(|(i, fragment)| {
+		let split_tags = fragment.get_split_tags().unwrap();
+
+		split_tags.get_index() as usize == i &&
+			split_tags.has_previous() == (i != 0) &&
+			split_tags.has_next() == (i != last_index)
+	})
+}
+
+#[cfg(test)]
+mod tagged_split_read_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::record::flags::PresentationFlags;
+	use crate::statistics::presentation::record::PresentationRecord;
+	use crate::statistics::presentation::record::split_tags::SplitTags;
+	use crate::statistics::presentation::split_read::tagged::assemble_tagged_records;
+
+	fn fragment(name: &str, index: u32, has_previous: bool, has_next: bool) -> PresentationRecord {
+		mate_fragment(name, false, index, has_previous, has_next)
+	}
+
+	fn mate_fragment(name: &str, is_last_mate: bool, index: u32, has_previous: bool, has_next: bool) -> PresentationRecord {
+		let flags = PresentationFlags::new(true, true, false, is_last_mate, !is_last_mate, false, false, false, false, false, false);
+		let start = index * 1000;
+		let split_tags = SplitTags::new(
+			index * 50 + 1,
+			index * 50 + 50,
+			index,
+			if has_previous { Some("chr1".to_string()) } else { None },
+			if has_previous { Some(start - 999) } else { None },
+			if has_next { Some(start + 1001) } else { None }
+		);
+
+		PresentationRecord::new(name.to_string(), flags, 255, 50, 0, start, start + 49, 0, 0, 0)
+			.with_split_tags(split_tags)
+	}
+
+	#[rstest]
+	#[case(vec![(0, false, true), (1, true, false)], 1, 0)]
+	#[case(vec![(1, true, false), (0, false, true)], 1, 0)]
+	#[case(vec![(0, false, true), (1, true, true), (2, true, false)], 1, 0)]
+	#[case(vec![(0, false, true), (2, true, false)], 0, 2)]
+	#[case(vec![(0, false, true), (1, true, true)], 0, 2)]
+	#[case(vec![(0, false, true), (0, false, true), (1, true, false)], 0, 3)]
+	fn assemble_tagged_records_test(
+		#[case] fragments: Vec<(u32, bool, bool)>,
+		#[case] expected_split_reads: usize,
+		#[case] expected_remaining_records: usize
+	) {
+		let records = fragments.into_iter()
+			.map(|(index, has_previous, has_next)| fragment("read", index, has_previous, has_next))
+			.collect();
+
+		let (split_reads, remaining_records) = assemble_tagged_records(records);
+
+		assert_eq!(split_reads.len(), expected_split_reads);
+		assert_eq!(remaining_records.len(), expected_remaining_records);
+	}
+
+	#[test]
+	fn untagged_records_remain_test() {
+		let flags = PresentationFlags::new(true, true, false, false, true, false, false, false, false, false, false);
+		let untagged = PresentationRecord::new("other".to_string(), flags, 255, 50, 0, 0, 49, 0, 0, 0);
+
+		let records = vec![fragment("read", 0, false, true), untagged, fragment("read", 1, true, false)];
+
+		let (split_reads, remaining_records) = assemble_tagged_records(records);
+
+		assert_eq!(split_reads.len(), 1);
+		assert_eq!(split_reads[0].get_split_count(false), 2);
+		assert_eq!(remaining_records.len(), 1);
+		assert_eq!(remaining_records[0].get_name(), "other");
+	}
+
+	#[test]
+	fn mates_are_chained_separately_test() {
+		let records = vec![
+			mate_fragment("read", false, 0, false, true),
+			mate_fragment("read", true, 0, false, true),
+			mate_fragment("read", false, 1, true, false),
+			mate_fragment("read", true, 1, true, false)
+		];
+
+		let (split_reads, remaining_records) = assemble_tagged_records(records);
+
+		assert_eq!(split_reads.len(), 2);
+		assert!(remaining_records.is_empty());
+	}
+}
@@ -0,0 +1,138 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::statistics::presentation::frequency_map::get_weighted_quantile;
+
+/// Lowest and highest multiple of the median coverage a bin may have to count as near the median.
+pub const NEAR_MEDIAN_RANGE: (f64, f64) = (0.2, 5.0);
+
+/// How evenly the reads are spread over the bins, every bin is weighted by its length in bases.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct CoverageUniformity {
+	pub bin_count: u64,
+	pub mean_coverage: f64,
+	pub median_coverage: f64,
+	/// Standard deviation divided by the mean, 0 without any coverage.
+	pub coefficient_of_variation: f64,
+	/// 0 for a perfectly even coverage, approaching 1 if all reads are in a single bin.
+	pub gini_coefficient: f64,
+	/// Mean coverage divided by the 20th percentile, `None` if more than a fifth of the bases are not covered.
+	pub fold_80_base_penalty: Option<f64>,
+	/// Fraction of the bases in bins with 0.2 to 5 times the median coverage.
+	pub near_median_fraction: f64
+}
+
+impl CoverageUniformity {
+	/// `bins` are pairs of depth in aligned bases and bin length.
+	pub fn from_bins(mut bins: Vec<(f64, u64)>) -> Self {
+		bins.retain(|(_, length)| *length > 0);
+
+		let total_length: u64 = bins.iter().map(|(_, length)| *length).sum();
+
+		if total_length == 0 {
+			return Default::default();
+		}
+
+		bins.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+		let total_length_f64 = total_length as f64;
+
+		let mean_coverage = bins.iter()
+			.map(|(coverage, length)| coverage * *length as f64)
+			.sum::<f64>() / total_length_f64;
+
+		let median_coverage = get_weighted_quantile(&bins, 0.5).unwrap_or(0.0);
+		let twentieth_percentile = get_weighted_quantile(&bins, 0.2).unwrap_or(0.0);
+
+		let variance = bins.iter()
+			.map(|(coverage, length)| (coverage - mean_coverage).powi(2) * *length as f64)
+			.sum::<f64>() / total_length_f64;
+
+		// Sum of the weighted differences of all pairs, bins before a bin are smaller and bins after it larger.
+		let mut length_before = 0.0;
+		let mut pair_differences = 0.0;
+
+		for (coverage, length) in bins.iter() {
+			let length = *length as f64;
+			let length_after = total_length_f64 - length_before - length;
+
+			pair_differences += coverage * length * (length_before - length_after);
+			length_before += length;
+		}
+
+		let (lower_bound, upper_bound) = (NEAR_MEDIAN_RANGE.0 * median_coverage, NEAR_MEDIAN_RANGE.1 * median_coverage);
+
+		let near_median_length: u64 = bins.iter()
+			.filter(|(coverage, _)| *coverage >= lower_bound && *coverage <= upper_bound)
+			.map(|(_, length)| *length)
+			.sum();
+
+		let (coefficient_of_variation, gini_coefficient) = if mean_coverage > 0.0 {
+			(variance.sqrt() / mean_coverage, pair_differences / (total_length_f64 * total_length_f64 * mean_coverage))
+		}
+		else {
+			(0.0, 0.0)
+		};
+
+		let fold_80_base_penalty = if twentieth_percentile > 0.0 {
+			Some(mean_coverage / twentieth_percentile)
+		}
+		else {
+			None
+		};
+
+		Self {
+			bin_count: bins.len() as u64,
+			mean_coverage,
+			median_coverage,
+			coefficient_of_variation,
+			gini_coefficient,
+			fold_80_base_penalty,
+			near_median_fraction: near_median_length as f64 / total_length_f64
+		}
+	}
+}
+
+#[cfg(test)]
+mod coverage_uniformity_tests {
+	use rstest::rstest;
+
+	use crate::statistics::presentation::uniformity::CoverageUniformity;
+
+	#[test]
+	fn empty_test() {
+		assert_eq!(CoverageUniformity::from_bins(vec![]), CoverageUniformity::default());
+		assert_eq!(CoverageUniformity::from_bins(vec![(0.0, 100), (0.0, 100)]).fold_80_base_penalty, None);
+	}
+
+	#[test]
+	fn even_coverage_test() {
+		let uniformity = CoverageUniformity::from_bins(vec![(30.0, 100); 10]);
+
+		assert_eq!(uniformity.bin_count, 10);
+		assert_eq!(uniformity.coefficient_of_variation, 0.0);
+		assert_eq!(uniformity.gini_coefficient, 0.0);
+		assert_eq!(uniformity.fold_80_base_penalty, Some(1.0));
+		assert_eq!(uniformity.near_median_fraction, 1.0);
+	}
+
+	#[rstest]
+	#[case(vec![(0.0, 1), (1.0, 1)], 0.5)]
+	#[case(vec![(1.0, 1), (2.0, 1), (3.0, 1)], 2.0 / 9.0)]
+	#[case(vec![(0.0, 3), (4.0, 1)], 0.75)]
+	fn gini_coefficient_test(#[case] bins: Vec<(f64, u64)>, #[case] expected: f64) {
+		let gini_coefficient = CoverageUniformity::from_bins(bins).gini_coefficient;
+
+		assert!((gini_coefficient - expected).abs() < 1e-12, "Expected: {}, Actual: {}", expected, gini_coefficient);
+	}
+
+	#[test]
+	fn uneven_coverage_test() {
+		let uniformity = CoverageUniformity::from_bins(vec![(1.0, 100), (10.0, 300), (10.0, 500), (100.0, 100)]);
+
+		assert_eq!(uniformity.median_coverage, 10.0);
+		assert!((uniformity.mean_coverage - 18.1).abs() < 1e-9);
+		assert!((uniformity.fold_80_base_penalty.unwrap() - 1.81).abs() < 1e-9);
+		assert_eq!(uniformity.near_median_fraction, 0.8);
+		assert!((uniformity.coefficient_of_variation - 1.5156).abs() < 1e-4);
+	}
+}
@@ -0,0 +1,59 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::statistics::calculation::unmapped::base_composition::BaseCompositionCalculationData;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct BaseComposition {
+    pub a: u64,
+    pub c: u64,
+    pub g: u64,
+    pub t: u64,
+    pub n: u64
+}
+
+impl BaseComposition {
+    pub fn merge(lhs: &Self, rhs: &Self) -> Self {
+        Self {
+            a: lhs.a + rhs.a,
+            c: lhs.c + rhs.c,
+            g: lhs.g + rhs.g,
+            t: lhs.t + rhs.t,
+            n: lhs.n + rhs.n
+        }
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.a + self.c + self.g + self.t + self.n
+    }
+
+    /// The GC fraction of all called bases, N is left out.
+    pub fn get_gc_content(&self) -> f64 {
+        let called = self.a + self.c + self.g + self.t;
+
+        if called == 0 {
+            return 0.0;
+        }
+
+        (self.g + self.c) as f64 / called as f64
+    }
+
+    pub fn get_n_rate(&self) -> f64 {
+        if self.get_total() == 0 {
+            return 0.0;
+        }
+
+        self.n as f64 / self.get_total() as f64
+    }
+}
+
+impl From<BaseCompositionCalculationData> for BaseComposition {
+    fn from(value: BaseCompositionCalculationData) -> Self {
+        Self {
+            a: value.a.into_inner(),
+            c: value.c.into_inner(),
+            g: value.g.into_inner(),
+            t: value.t.into_inner(),
+            n: value.n.into_inner()
+        }
+    }
+}
@@ -3,9 +3,10 @@ use serde_derive::{Deserialize, Serialize};
 use single_read::UnmappedSingleReadPresentationData;
 
 use crate::statistics::calculation::unmapped::UnmappedCalculationData;
-use indicatif::{ProgressBar, ProgressStyle};
 use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
+use crate::statistics::presentation::unmapped::base_composition::BaseComposition;
 
+pub mod base_composition;
 pub mod single_read;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -22,6 +23,37 @@ impl UnmappedPresentationData {
         )
     }
 
+    pub fn get_base_composition(&self) -> BaseComposition {
+        BaseComposition::merge(
+            &self.single_read.get_base_composition(),
+            &self.split_read.get_base_composition()
+        )
+    }
+
+    pub fn get_mean_quality_map(&self) -> PresentationFrequencyMap<u8> {
+        PresentationFrequencyMap::merge(
+            self.single_read.get_mean_quality_map(),
+            self.split_read.get_mean_quality_map()
+        )
+    }
+
+    pub fn get_longest_homopolymer_map(&self) -> PresentationFrequencyMap<u32> {
+        PresentationFrequencyMap::merge(
+            self.single_read.get_longest_homopolymer_map(),
+            self.split_read.get_longest_homopolymer_map()
+        )
+    }
+
+    pub fn get_low_complexity_fraction(&self) -> f64 {
+        let read_count = self.get_read_length_map().get_frequency_sum();
+
+        if read_count == 0 {
+            return 0.0;
+        }
+
+        (self.single_read.get_low_complexity_count() + self.split_read.get_low_complexity_count()) as f64 / read_count as f64
+    }
+
     pub fn get_single_read(&self) -> &UnmappedSingleReadPresentationData {
         &self.single_read
     }
@@ -33,32 +65,9 @@ impl UnmappedPresentationData {
 
 impl From<UnmappedCalculationData> for UnmappedPresentationData {
     fn from(calculation: UnmappedCalculationData) -> Self {
-        let pb = ProgressBar::new_spinner();
-        pb.set_message("Calculating Unmapped Single Read Statistics...");
-        pb.set_prefix("[1/2]");
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{prefix}         {spinner} [{elapsed_precise}] {msg}")
-            .progress_chars("#>-")
-            .tick_chars("/-\\|"));
-        pb.enable_steady_tick(60/15);
-
         let single_read = calculation.single_read.into();
-
-        pb.reset_eta();
-        pb.reset_elapsed();
-
-        pb.set_message("Calculating Unmapped Split Read Statistics...");
-        pb.set_prefix("[2/2]");
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{prefix}         {spinner} [{elapsed_precise}] {msg}")
-            .progress_chars("#>-")
-            .tick_chars("/-\\|"));
-        pb.enable_steady_tick(60/15);
-
         let split_read = calculation.split_read.into();
 
-        pb.finish_and_clear();
-
         Self {
             single_read,
             split_read
@@ -2,16 +2,57 @@ use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
 
 use serde_derive::{Deserialize, Serialize};
 use crate::statistics::calculation::unmapped::single_read::UnmappedSingleReadCalculationData;
+use crate::statistics::presentation::unmapped::base_composition::BaseComposition;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UnmappedSingleReadPresentationData {
-    read_length_map: PresentationFrequencyMap<u32>
+    read_length_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    base_composition: BaseComposition,
+    #[serde(default)]
+    mean_quality_map: PresentationFrequencyMap<u8>,
+    #[serde(default)]
+    longest_homopolymer_map: PresentationFrequencyMap<u32>,
+    #[serde(default)]
+    low_complexity_count: u64,
+    #[serde(default)]
+    mate_reference_map: PresentationFrequencyMap<i32>,
+    #[serde(default)]
+    mate_unmapped_count: u64
 }
 
 impl UnmappedSingleReadPresentationData {
     pub fn get_read_length_map(&self) -> &PresentationFrequencyMap<u32> {
         &self.read_length_map
     }
+
+    pub fn get_base_composition(&self) -> BaseComposition {
+        self.base_composition
+    }
+
+    /// The mean base quality of each read, rounded down.
+    pub fn get_mean_quality_map(&self) -> &PresentationFrequencyMap<u8> {
+        &self.mean_quality_map
+    }
+
+    pub fn get_longest_homopolymer_map(&self) -> &PresentationFrequencyMap<u32> {
+        &self.longest_homopolymer_map
+    }
+
+    /// Reads dominated by a single base, see [`crate::util::is_low_complexity_sequence`].
+    pub fn get_low_complexity_count(&self) -> u64 {
+        self.low_complexity_count
+    }
+
+    /// The reference ids the mapped mates of the reads are aligned to.
+    pub fn get_mate_reference_map(&self) -> &PresentationFrequencyMap<i32> {
+        &self.mate_reference_map
+    }
+
+    /// Paired reads whose mate is unmapped as well.
+    pub fn get_mate_unmapped_count(&self) -> u64 {
+        self.mate_unmapped_count
+    }
 }
 
 impl AsRef<PresentationFrequencyMap<u32>> for UnmappedSingleReadPresentationData {
@@ -23,9 +64,21 @@ impl AsRef<PresentationFrequencyMap<u32>> for UnmappedSingleReadPresentationData
 impl From<UnmappedSingleReadCalculationData> for UnmappedSingleReadPresentationData {
     fn from(value: UnmappedSingleReadCalculationData) -> Self {
         let read_length_map = value.read_length_map.into();
+        let base_composition = value.base_composition.into();
+        let mean_quality_map = value.mean_quality_map.into();
+        let longest_homopolymer_map = value.longest_homopolymer_map.into();
+        let low_complexity_count = value.low_complexity_count.into_inner();
+        let mate_reference_map = value.mate_reference_map.into();
+        let mate_unmapped_count = value.mate_unmapped_count.into_inner();
 
         Self {
-            read_length_map
+            read_length_map,
+            base_composition,
+            mean_quality_map,
+            longest_homopolymer_map,
+            low_complexity_count,
+            mate_reference_map,
+            mate_unmapped_count
         }
     }
 }
@@ -0,0 +1,21 @@
+use bam::Record;
+use serde_derive::{Deserialize, Serialize};
+use crate::util::get_record_integer_tag;
+
+/// Determines how the fragments of a split read are chained together.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AssemblerMode {
+	/// Infers the order from the first/last segment flags and RNEXT/PNEXT.
+	#[default]
+	Flags,
+	/// Uses the segemehl split tags (XX/XY/XQ/XC/XP/XN), records without them are assembled via flags.
+	SegemehlTags
+}
+
+impl AssemblerMode {
+	/// Segemehl chains the primary and the supplementary alignments of a read by their split tags,
+	/// so tagged supplementary alignments are assembled together with the primary ones.
+	pub fn chains_supplementary(&self, record: &Record) -> bool {
+		*self == AssemblerMode::SegemehlTags && get_record_integer_tag(record, b"XQ").is_some()
+	}
+}
@@ -1,7 +1,35 @@
 use std::num::{NonZeroU32};
 use serde_derive::{Serialize, Deserialize};
+use crate::statistics::calculation::binned::BinConfig;
+use crate::statistics::shared::assembler_mode::AssemblerMode;
+use crate::statistics::shared::metric_selection::MetricSelection;
+use crate::statistics::shared::rebinning::Rebinning;
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meta {
-	pub bin_size: NonZeroU32
-}
\ No newline at end of file
+	/// Length of the bins, with a fixed number of bins the length of the bins on the longest reference.
+	pub bin_size: NonZeroU32,
+	/// Set if every reference is divided into the same number of bins instead of bins of the same length.
+	#[serde(default)]
+	pub bin_count: Option<NonZeroU32>,
+	/// Additional resolutions the references are binned with, see [`Meta::get_bin_config`] for the base resolution.
+	#[serde(default)]
+	pub zoom_levels: Vec<BinConfig>,
+	#[serde(default)]
+	pub weight_multimappers: bool,
+	#[serde(default)]
+	pub assembler_mode: AssemblerMode,
+	#[serde(default)]
+	pub metrics: MetricSelection,
+	#[serde(default)]
+	pub rebinning: Rebinning
+}
+
+impl Meta {
+	pub fn get_bin_config(&self) -> BinConfig {
+		match self.bin_count {
+			Some(bin_count) => BinConfig::NumberOfBins(bin_count),
+			None => BinConfig::LengthOfBins(self.bin_size)
+		}
+	}
+}
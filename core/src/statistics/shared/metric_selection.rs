@@ -0,0 +1,57 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// The statistics a calculation collects, disabled ones stay empty in the presentation data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricSelection {
+	pub per_reference: bool,
+	pub unmapped: bool,
+	pub split_reads: bool,
+	pub insert_size: bool
+}
+
+impl MetricSelection {
+	pub fn all() -> Self {
+		Self {
+			per_reference: true,
+			unmapped: true,
+			split_reads: true,
+			insert_size: true
+		}
+	}
+
+	pub fn none() -> Self {
+		Self {
+			per_reference: false,
+			unmapped: false,
+			split_reads: false,
+			insert_size: false
+		}
+	}
+
+	pub fn with_per_reference(mut self, per_reference: bool) -> Self {
+		self.per_reference = per_reference;
+		self
+	}
+
+	pub fn with_unmapped(mut self, unmapped: bool) -> Self {
+		self.unmapped = unmapped;
+		self
+	}
+
+	pub fn with_split_reads(mut self, split_reads: bool) -> Self {
+		self.split_reads = split_reads;
+		self
+	}
+
+	pub fn with_insert_size(mut self, insert_size: bool) -> Self {
+		self.insert_size = insert_size;
+		self
+	}
+}
+
+/// Files written before the selection existed contain every metric.
+impl Default for MetricSelection {
+	fn default() -> Self {
+		Self::all()
+	}
+}
@@ -1,2 +1,5 @@
 pub mod header_statistics;
 pub mod meta;
+pub mod assembler_mode;
+pub mod metric_selection;
+pub mod rebinning;
@@ -0,0 +1,129 @@
+use std::num::NonZeroU32;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// How frequency maps with wide keys, like gap and read lengths, are compressed before they are stored.
+///
+/// Rebinning moves every value to the representative of its bin and never changes the frequencies,
+/// counts and frequency sums therefore stay exact. Every quantile, the mean and the median absolute
+/// deviation of a rebinned map are within the error bound of the variant to the exact value.
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Rebinning {
+	/// Keeps every distinct value.
+	#[default]
+	#[serde(rename = "e")]
+	Exact,
+	/// Bins of the same width, every value changes by at most half the width.
+	#[serde(rename = "w")]
+	FixedWidth(NonZeroU32),
+	/// Bins growing with the distance to zero, every value changes by at most `relative_error` times itself.
+	///
+	/// Values below `1 / relative_error` are kept exact, so short reads and gaps lose nothing.
+	#[serde(rename = "l")]
+	Logarithmic {
+		#[serde(rename = "r")]
+		relative_error: f64
+	}
+}
+
+impl Rebinning {
+	/// `None` if the relative error is not between 0 and 1.
+	pub fn logarithmic(relative_error: f64) -> Option<Self> {
+		if relative_error > 0.0 && relative_error < 1.0 {
+			Some(Rebinning::Logarithmic { relative_error })
+		}
+		else {
+			None
+		}
+	}
+
+	pub fn is_exact(&self) -> bool {
+		matches!(self, Rebinning::Exact)
+	}
+
+	/// The representative of the bin the integral `value` falls into, again an integral value.
+	pub fn get_representative(&self, value: f64) -> f64 {
+		match self {
+			Rebinning::Exact => value,
+			Rebinning::FixedWidth(width) => {
+				let width = width.get() as f64;
+				let bin_start = (value / width).floor() * width;
+
+				bin_start + ((width - 1.0) / 2.0).floor()
+			}
+			Rebinning::Logarithmic { relative_error } => {
+				let magnitude = value.abs();
+
+				if magnitude * relative_error <= 1.0 {
+					return value;
+				}
+
+				// Half of the error goes to the bin and half to rounding the representative, which
+				// costs at most 0.5 / magnitude and is below relative_error / 2 outside the exact range.
+				let bin_error = relative_error / 2.0;
+				let gamma = (1.0 + bin_error) / (1.0 - bin_error);
+				let bin_index = (magnitude.ln() / gamma.ln()).ceil();
+				let representative = 2.0 * gamma.powf(bin_index) / (gamma + 1.0);
+
+				value.signum() * representative.round()
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod rebinning_tests {
+	use std::num::NonZeroU32;
+
+	use rstest::rstest;
+
+	use crate::statistics::shared::rebinning::Rebinning;
+
+	#[rstest]
+	#[case(1, 17.0, 17.0)]
+	#[case(10, 0.0, 4.0)]
+	#[case(10, 9.0, 4.0)]
+	#[case(10, 10.0, 14.0)]
+	#[case(10, -1.0, -6.0)]
+	#[case(100, 12345.0, 12349.0)]
+	fn fixed_width_test(#[case] width: u32, #[case] value: f64, #[case] expected: f64) {
+		let rebinning = Rebinning::FixedWidth(NonZeroU32::new(width).unwrap());
+
+		assert_eq!(rebinning.get_representative(value), expected);
+	}
+
+	#[rstest]
+	#[case(0.01)]
+	#[case(0.05)]
+	#[case(0.2)]
+	fn logarithmic_error_bound_test(#[case] relative_error: f64) {
+		let rebinning = Rebinning::logarithmic(relative_error).unwrap();
+
+		for value in (-200_000..200_000).step_by(7).map(|value| value as f64) {
+			let representative = rebinning.get_representative(value);
+
+			assert_eq!(representative, representative.round());
+			assert!(
+				(representative - value).abs() <= relative_error * value.abs(),
+				"{} was rebinned to {}", value, representative
+			);
+		}
+	}
+
+	#[test]
+	fn logarithmic_keeps_small_values_test() {
+		let rebinning = Rebinning::logarithmic(0.01).unwrap();
+
+		for value in -100..=100 {
+			assert_eq!(rebinning.get_representative(value as f64), value as f64);
+		}
+	}
+
+	#[rstest]
+	#[case(0.0)]
+	#[case(1.0)]
+	#[case(-0.5)]
+	fn invalid_relative_error_test(#[case] relative_error: f64) {
+		assert_eq!(Rebinning::logarithmic(relative_error), None);
+	}
+}
@@ -1,9 +1,12 @@
 use std::num::NonZeroU32;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use bam::Record;
 use crate::statistics::presentation::frequency_map::PresentationFrequencyMap;
 use bam::record::cigar::{Operation, CigarIter};
+use bam::record::tags::TagValue;
 use std::iter::{Peekable, Rev};
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 
 pub fn length(start: u32, end: u32) -> u32 {
 	if end < start {
@@ -27,6 +30,17 @@ pub fn get_quality_frequency_map(map: &PresentationFrequencyMap<u8>) -> Vec<(u8,
 	vec
 }
 
+/// Fraction of reads with exactly one reported alignment, given a map of `NH` values.
+pub fn get_unique_mapping_fraction(map: &PresentationFrequencyMap<u32>) -> f64 {
+	let total = map.get_frequency_sum();
+
+	if total == 0 {
+		return 0.0;
+	}
+
+	map.get(&1).unwrap_or(0) as f64 / total as f64
+}
+
 pub struct BinCoordinates {
 	pub bin_index: u32,
 	pub position_in_bin: u32
@@ -51,6 +65,14 @@ pub fn get_record_name_as_string(record: &Record) -> String {
 	String::from_utf8_lossy(&*vec).to_string()
 }
 
+/// Hash of the QNAME to spread templates over shards.
+/// `DefaultHasher` may change between Rust versions, so the hash must never be persisted.
+pub fn get_record_name_hash(record: &Record) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	record.name().hash(&mut hasher);
+	hasher.finish()
+}
+
 pub fn get_record_length_on_reference(record: &Record) -> u32 {
 	(get_record_end(record) - get_record_start(record)) + 1
 }
@@ -59,10 +81,112 @@ pub fn get_record_length_sequence(record: &Record) -> u32 {
 	record.query_len()
 }
 
+/// The mean Phred quality of the read, rounded down, or `None` if the record has no qualities.
+pub fn get_record_mean_base_quality(record: &Record) -> Option<u8> {
+	let qualities = record.qualities();
+
+	if !qualities.available() || qualities.len() == 0 {
+		return None;
+	}
+
+	let sum: u64 = qualities.raw().iter().map(|quality| *quality as u64).sum();
+	Some((sum / qualities.len() as u64) as u8)
+}
+
+/// Length of the longest run of the same base.
+pub fn get_longest_homopolymer(sequence: &[u8]) -> u32 {
+	let mut longest = 0u32;
+	let mut current = 0u32;
+
+	for (i, base) in sequence.iter().enumerate() {
+		if i > 0 && sequence[i - 1] == *base {
+			current += 1;
+		}
+		else {
+			current = 1;
+		}
+
+		longest = longest.max(current);
+	}
+
+	longest
+}
+
+/// A read is low complexity if a single base makes up 80% of it, or a single homopolymer covers half of it,
+/// which catches poly-A tails and the N or G runs of failed sequencing cycles.
+pub fn is_low_complexity_sequence(sequence: &[u8]) -> bool {
+	if sequence.is_empty() {
+		return false;
+	}
+
+	let mut counts = [0usize; 256];
+	for base in sequence {
+		counts[*base as usize] += 1;
+	}
+
+	let dominant_base_count = counts.iter().max().cloned().unwrap_or(0);
+
+	dominant_base_count * 5 >= sequence.len() * 4 ||
+		get_longest_homopolymer(sequence) as usize * 2 >= sequence.len()
+}
+
 pub fn get_record_t_len(record: &Record) -> u32 {
 	record.template_len().abs() as u32
 }
 
+pub fn get_record_integer_tag(record: &Record, name: &[u8; 2]) -> Option<i64> {
+	match record.tags().get(name) {
+		Some(TagValue::Int(value, _)) => Some(value),
+		_ => None
+	}
+}
+
+pub fn get_record_string_tag(record: &Record, name: &[u8; 2]) -> Option<String> {
+	match record.tags().get(name) {
+		Some(TagValue::String(value, _)) => Some(String::from_utf8_lossy(value).to_string()),
+		_ => None
+	}
+}
+
+pub fn get_record_read_group(record: &Record) -> Option<String> {
+	get_record_string_tag(record, b"RG")
+}
+
+/// Number of reported alignments for the read (`NH` tag), `None` if the tag is missing or invalid.
+pub fn get_record_hit_count(record: &Record) -> Option<u32> {
+	get_record_integer_tag(record, b"NH")
+		.filter(|value| *value > 0)
+		.map(|value| value as u32)
+}
+
+pub fn is_insert_size_record(record: &Record) -> bool {
+	let flags = record.flag();
+
+	flags.is_mapped() &&
+		flags.all_segments_aligned() &&
+		flags.first_in_pair() &&
+		!flags.is_secondary() &&
+		!flags.is_supplementary() &&
+		record.template_len() != 0
+}
+
+/// The two records are the mates of a paired-end template rather than fragments of a split read:
+/// the first and the last segment, both without split tags (`XQ`) and with template lengths of opposite sign.
+/// Split reads without tags from aligners that set the template length look the same, so the assemblers only use this on request.
+pub fn is_mate_pair(records: &[Record]) -> bool {
+	match records {
+		[first, second] => {
+			let segments = |record: &Record| (record.flag().first_in_pair(), record.flag().last_in_pair());
+
+			matches!((segments(first), segments(second)), ((true, false), (false, true)) | ((false, true), (true, false))) &&
+				first.template_len() != 0 &&
+				first.template_len() == -second.template_len() &&
+				records.iter().all(|record| get_record_integer_tag(record, b"XQ").is_none())
+		}
+		_ => false
+	}
+}
+
 pub fn get_record_mapping_quality(record: &Record) -> u8 {
 	record.mapq()
 }
@@ -75,6 +199,74 @@ pub fn get_record_end(record: &Record) -> u32 {
 	record.calculate_end() as u32
 }
 
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RecordClipping {
+	pub leading_soft: u32,
+	pub leading_hard: u32,
+	pub trailing_soft: u32,
+	pub trailing_hard: u32
+}
+
+impl RecordClipping {
+	pub fn get_leading_length(&self) -> u32 {
+		self.leading_soft + self.leading_hard
+	}
+
+	pub fn get_trailing_length(&self) -> u32 {
+		self.trailing_soft + self.trailing_hard
+	}
+
+	/// The CIGAR string is always given in reference orientation,
+	/// so for reverse strand records the leading clip is at the 3' end of the read.
+	pub fn get_five_prime_length(&self, is_reverse_strand: bool) -> u32 {
+		if is_reverse_strand {
+			self.get_trailing_length()
+		}
+		else {
+			self.get_leading_length()
+		}
+	}
+
+	pub fn get_three_prime_length(&self, is_reverse_strand: bool) -> u32 {
+		if is_reverse_strand {
+			self.get_leading_length()
+		}
+		else {
+			self.get_trailing_length()
+		}
+	}
+}
+
+pub fn get_record_clipping(record: &Record) -> RecordClipping {
+	let operations: Vec<(u32, Operation)> = record.cigar().iter().collect();
+	get_clipping_from_operations(&operations)
+}
+
+pub fn get_clipping_from_operations(operations: &[(u32, Operation)]) -> RecordClipping {
+	let mut clipping = RecordClipping::default();
+
+	let mut leading_count = 0usize;
+
+	for (len, op) in operations {
+		match op {
+			Operation::Hard => clipping.leading_hard += len,
+			Operation::Soft => clipping.leading_soft += len,
+			_ => break
+		}
+		leading_count += 1;
+	}
+
+	for (len, op) in operations[leading_count..].iter().rev() {
+		match op {
+			Operation::Hard => clipping.trailing_hard += len,
+			Operation::Soft => clipping.trailing_soft += len,
+			_ => break
+		}
+	}
+
+	clipping
+}
+
 enum CigarPeekableReversableIter<'a> {
 	Norm(Peekable<CigarIter<'a>>),
 	Rev(Peekable<Rev<CigarIter<'a>>>)
@@ -180,6 +372,7 @@ impl<'a> CigarMaxLengthIter<'a> {
 				Some((len, op)) => {
 					match op {
 						Operation::Deletion |
+						Operation::Skip |
 						Operation::AlnMatch |
 						Operation::SeqMatch |
 						Operation::SeqMismatch => {
@@ -194,7 +387,6 @@ impl<'a> CigarMaxLengthIter<'a> {
 						Operation::Insertion => {
 							Some((len,op))
 						},
-						Operation::Skip |
 						Operation::Padding => {
 							continue;
 						},
@@ -322,11 +514,32 @@ pub fn record_to_sam(record: &Record) -> String {
 	)
 }
 
+/// Writes the read as a FASTQ entry, records without qualities get `!` for every base.
+/// Reverse strand records are written as stored, which for unmapped reads is the sequenced strand.
+pub fn write_record_as_fastq<W: Write>(record: &Record, writer: &mut W) -> std::io::Result<()> {
+	writer.write_all(b"@")?;
+	writer.write_all(record.name())?;
+	writer.write_all(b"\n")?;
+	writer.write_all(&record.sequence().to_vec())?;
+	writer.write_all(b"\n+\n")?;
+
+	if record.qualities().available() {
+		writer.write_all(&record.qualities().to_readable())?;
+	}
+	else {
+		writer.write_all(&vec![b'!'; record.sequence().len()])?;
+	}
+
+	writer.write_all(b"\n")
+}
+
 #[cfg(test)]
 mod tests {
-	use crate::util::calculate_bin;
+	use crate::util::{calculate_bin, get_clipping_from_operations, get_longest_homopolymer, is_low_complexity_sequence, is_mate_pair, write_record_as_fastq, RecordClipping};
+	use bam::Record;
 	use std::num::NonZeroU32;
 	use rstest::rstest;
+	use bam::record::cigar::Operation;
 
 	#[rstest]
 	#[should_panic]
@@ -365,4 +578,80 @@ mod tests {
 			bin_coordinates.position_in_bin
 		)
 	}
-}
\ No newline at end of file
+
+	#[rstest]
+	#[case(vec![(50, Operation::AlnMatch)], 0, 0, 0, 0)]
+	#[case(vec![(5, Operation::Hard), (10, Operation::Soft), (50, Operation::AlnMatch)], 10, 5, 0, 0)]
+	#[case(vec![(50, Operation::AlnMatch), (7, Operation::Soft), (3, Operation::Hard)], 0, 0, 7, 3)]
+	#[case(vec![(4, Operation::Soft), (20, Operation::AlnMatch), (2, Operation::Insertion), (20, Operation::AlnMatch), (6, Operation::Soft)], 4, 0, 6, 0)]
+	#[case(vec![(12, Operation::Soft)], 12, 0, 0, 0)]
+	fn get_clipping_from_operations_test(
+		#[case] operations: Vec<(u32, Operation)>,
+		#[case] expected_leading_soft: u32,
+		#[case] expected_leading_hard: u32,
+		#[case] expected_trailing_soft: u32,
+		#[case] expected_trailing_hard: u32
+	) {
+		let expected = RecordClipping {
+			leading_soft: expected_leading_soft,
+			leading_hard: expected_leading_hard,
+			trailing_soft: expected_trailing_soft,
+			trailing_hard: expected_trailing_hard
+		};
+
+		let clipping = get_clipping_from_operations(&operations);
+
+		assert_eq!(expected, clipping, "Got Wrong Clipping, Expected: {:?}, Actual: {:?}", expected, clipping);
+	}
+
+	#[rstest]
+	#[case(b"", 0, false)]
+	#[case(b"ACGTACGTAC", 1, false)]
+	#[case(b"ACGGGGGGTA", 6, true)]
+	#[case(b"AAAACAAAAACAAAA", 5, true)]
+	fn low_complexity_test(#[case] sequence: &[u8], #[case] expected_homopolymer: u32, #[case] expected_low_complexity: bool) {
+		assert_eq!(get_longest_homopolymer(sequence), expected_homopolymer);
+		assert_eq!(is_low_complexity_sequence(sequence), expected_low_complexity);
+	}
+
+	#[rstest]
+	#[case(0x1 | 0x40, 300, 0x1 | 0x80, -300, false, true)]
+	#[case(0x1 | 0x80, -300, 0x1 | 0x40, 300, false, true)]
+	#[case(0x1 | 0x40, 300, 0x1 | 0x80, 300, false, false)]
+	#[case(0x1 | 0x40, 300, 0x1, -300, false, false)]
+	#[case(0x1 | 0x40, 300, 0x1 | 0x80, -300, true, false)]
+	fn is_mate_pair_test(
+		#[case] first_flag: u16,
+		#[case] first_template_length: i32,
+		#[case] second_flag: u16,
+		#[case] second_template_length: i32,
+		#[case] has_split_tags: bool,
+		#[case] expected: bool
+	) {
+		let records: Vec<Record> = vec![(first_flag, first_template_length), (second_flag, second_template_length)].into_iter()
+			.map(|(flag, template_length)| {
+				let mut record = Record::new();
+				record.set_flag(flag);
+				record.set_template_len(template_length);
+				if has_split_tags {
+					record.tags_mut().push_num(b"XQ", 0);
+				}
+				record
+			})
+			.collect();
+
+		assert_eq!(is_mate_pair(&records), expected);
+	}
+
+	#[test]
+	fn write_record_as_fastq_test() {
+		let mut record = Record::new();
+		record.set_name(b"read".iter().cloned());
+		record.set_seq_qual(b"ACGT".iter().cloned(), vec![30u8, 30, 20, 10]).unwrap();
+
+		let mut output = vec![];
+		write_record_as_fastq(&record, &mut output).unwrap();
+
+		assert_eq!(String::from_utf8(output).unwrap(), "@read\nACGT\n+\n??5+\n");
+	}
+}
@@ -9,10 +9,15 @@ use segemehl_21_core::{
     statistics::presentation::frequency_map::PresentationFrequencyMap
 };
 use segemehl_21_core::statistics::presentation::cigar_operations::CigarOperations;
+use segemehl_21_core::statistics::presentation::alignment_categories::AlignmentCategories;
+use segemehl_21_core::statistics::presentation::split_read::class::SplitReadClass;
 use segemehl_21_core::statistics::presentation::PresentationData;
+use segemehl_21_core::statistics::presentation::binned::map::BinnedStatisticsPresentationMap;
 use crate::box_plots::{box_plot_from_frequency_maps, BoxPlot, boxplot_entry_from_frequency_map, split_box_plot};
 use crate::util::set_panic_hook;
 
+pub use crate::util::console::ConsoleEventSink;
+
 mod box_plots;
 mod util;
 
@@ -44,6 +49,9 @@ pub struct PerFileStatistics {
     pub median_length_of_read_in_file: f64,
     pub mode_length_of_read_in_file: u32,
     pub mean_length_of_read_in_file: f64,
+    pub read_length_interquartile_range: f64,
+    pub read_length_standard_deviation: f64,
+    pub read_length_median_absolute_deviation: f64,
 
     pub median_number_of_reads_per_chromosome: f64,
     pub mode_number_of_reads_per_chromosome: u64,
@@ -56,10 +64,31 @@ pub struct PerFileStatistics {
     pub shortest_chromosome_length: u32,
     pub longest_chromosome_length: u32,
 
-    pub median_chromosome_coverage: f64,
-    pub mean_chromosome_coverage: f64,
-    pub least_chromosome_coverage: f64,
-    pub most_chromosome_coverage: f64
+    pub median_chromosome_depth: f64,
+    pub mean_chromosome_depth: f64,
+    pub least_chromosome_depth: f64,
+    pub most_chromosome_depth: f64,
+
+    pub median_chromosome_read_density: f64,
+    pub mean_chromosome_read_density: f64,
+    pub least_chromosome_read_density: f64,
+    pub most_chromosome_read_density: f64,
+
+    pub coverage_coefficient_of_variation: f64,
+    pub coverage_gini_coefficient: f64,
+    pub coverage_fold_80_base_penalty: Option<f64>,
+    pub coverage_near_median_fraction: f64,
+
+    pub circular_rna_candidate_count: u64,
+    pub circular_rna_supporting_read_count: u64,
+    pub top_circular_rna_candidates: Vec<String>,
+
+    pub unmapped_gc_content: f64,
+    pub unmapped_n_rate: f64,
+    pub unmapped_mean_base_quality: f64,
+    pub unmapped_low_complexity_fraction: f64,
+    pub unmapped_mate_unmapped_count: u64,
+    pub top_unmapped_mate_references: Vec<String>
 }
 
 #[wasm_bindgen]
@@ -105,13 +134,14 @@ pub fn generate_file_stats_inner(buffer: ArrayBuffer) -> Result<JsValue, JsValue
     return JsValue::from_serde(&file_stats).map_err(|_| JsValue::from_str("Error Converting stats to JSON"));
 }
 
+const TOP_CIRCULAR_RNA_CANDIDATE_COUNT: usize = 10;
+const TOP_UNMAPPED_MATE_REFERENCE_COUNT: usize = 10;
+
 fn generate_per_file_stats(statistics: &PresentationData) -> PerFileStatistics {
     let total_chromosome_length: u64 = statistics.get_per_reference_data()
         .map(|item| item.get_reference_length() as u64)
         .sum();
 
-    let chromosome_count = statistics.get_per_reference_data().count();
-
     let shortest_chromosome_length = statistics.get_per_reference_data()
         .map(|item| item.get_reference_length())
         .min()
@@ -122,19 +152,40 @@ fn generate_per_file_stats(statistics: &PresentationData) -> PerFileStatistics {
         .max()
         .unwrap_or(0);
 
-    let median_length_of_chromosomes = (longest_chromosome_length as usize + shortest_chromosome_length as usize) as f64 / 2.0;
-
-    let mean_length_of_chromosomes = total_chromosome_length as f64 / chromosome_count as f64;
-
     let mut chromosome_length_map = PresentationFrequencyMap::<u32>::new();
 
     for chromosome in statistics.get_per_reference_data() {
         chromosome_length_map.add_entry(chromosome.get_reference_length())
     }
-    let (mode_length_of_chromosomes, _) = chromosome_length_map.get_max_frequency().unwrap_or((0,0));
+
+    let median_length_of_chromosomes = chromosome_length_map.get_median().unwrap_or(0.0);
+    let mode_length_of_chromosomes = chromosome_length_map.get_mode().unwrap_or(0);
+    let mean_length_of_chromosomes = chromosome_length_map.get_mean().unwrap_or(0.0);
 
     let complete_read_length_map = statistics.get_read_length_on_reference_map();
 
+    let coverage_uniformity = statistics.get_coverage_uniformity();
+
+    let circular_rna_candidates = statistics.get_circular_rna_candidates();
+    let top_circular_rna_candidates = circular_rna_candidates.get_top_candidates().iter()
+        .take(TOP_CIRCULAR_RNA_CANDIDATE_COUNT)
+        .map(|candidate| format!(
+            "{}:{}-{} ({}) {} Reads",
+            candidate.get_reference_name(),
+            candidate.get_start(),
+            candidate.get_end(),
+            candidate.get_strand(),
+            candidate.get_supporting_reads()
+        ))
+        .collect();
+
+    let unmapped_data = statistics.get_unmapped_data();
+    let unmapped_base_composition = unmapped_data.get_base_composition();
+    let top_unmapped_mate_references = statistics.get_unmapped_mate_references().into_iter()
+        .take(TOP_UNMAPPED_MATE_REFERENCE_COUNT)
+        .map(|(reference_name, count)| format!("{}: {} Reads", reference_name, count))
+        .collect();
+
     let statistics = PerFileStatistics {
         number_reads_in_file: complete_read_length_map.get_frequency_sum(),
         total_length_of_reads_in_file: complete_read_length_map.get_weighted_frequency_sum(),
@@ -142,9 +193,12 @@ fn generate_per_file_stats(statistics: &PresentationData) -> PerFileStatistics {
         length_of_longest_read: complete_read_length_map.get_max_entry().unwrap_or((0,0)).0,
         smallest_number_of_reads_for_single_chromosome: statistics.get_least_read_count(),
         biggest_number_of_reads_for_single_chromosome: statistics.get_most_read_count(),
-        median_length_of_read_in_file:complete_read_length_map.get_median_entry().unwrap_or(0.0),
-        mode_length_of_read_in_file: complete_read_length_map.get_max_frequency().unwrap_or((0,0)).0,
-        mean_length_of_read_in_file: complete_read_length_map.get_mean_entry().unwrap(),
+        median_length_of_read_in_file: complete_read_length_map.get_median().unwrap_or(0.0),
+        mode_length_of_read_in_file: complete_read_length_map.get_mode().unwrap_or(0),
+        mean_length_of_read_in_file: complete_read_length_map.get_mean().unwrap_or(0.0),
+        read_length_interquartile_range: complete_read_length_map.get_interquartile_range().unwrap_or(0.0),
+        read_length_standard_deviation: complete_read_length_map.get_standard_deviation().unwrap_or(0.0),
+        read_length_median_absolute_deviation: complete_read_length_map.get_median_absolute_deviation().unwrap_or(0.0),
         median_number_of_reads_per_chromosome: statistics.get_median_read_count(),
         mode_number_of_reads_per_chromosome: statistics.get_mode_read_count(),
         mean_number_of_reads_per_chromosome: statistics.get_mean_read_count(),
@@ -154,10 +208,27 @@ fn generate_per_file_stats(statistics: &PresentationData) -> PerFileStatistics {
         mean_length_of_chromosomes,
         shortest_chromosome_length,
         longest_chromosome_length,
-        median_chromosome_coverage: statistics.get_median_coverage(),
-        mean_chromosome_coverage: statistics.get_mean_coverage(),
-        least_chromosome_coverage: statistics.get_least_coverage(),
-        most_chromosome_coverage: statistics.get_most_coverage()
+        median_chromosome_depth: statistics.get_median_depth(),
+        mean_chromosome_depth: statistics.get_mean_depth(),
+        least_chromosome_depth: statistics.get_least_depth(),
+        most_chromosome_depth: statistics.get_most_depth(),
+        median_chromosome_read_density: statistics.get_median_read_density(),
+        mean_chromosome_read_density: statistics.get_mean_read_density(),
+        least_chromosome_read_density: statistics.get_least_read_density(),
+        most_chromosome_read_density: statistics.get_most_read_density(),
+        coverage_coefficient_of_variation: coverage_uniformity.coefficient_of_variation,
+        coverage_gini_coefficient: coverage_uniformity.gini_coefficient,
+        coverage_fold_80_base_penalty: coverage_uniformity.fold_80_base_penalty,
+        coverage_near_median_fraction: coverage_uniformity.near_median_fraction,
+        circular_rna_candidate_count: circular_rna_candidates.get_candidate_count(),
+        circular_rna_supporting_read_count: circular_rna_candidates.get_supporting_read_count(),
+        top_circular_rna_candidates,
+        unmapped_gc_content: unmapped_base_composition.get_gc_content(),
+        unmapped_n_rate: unmapped_base_composition.get_n_rate(),
+        unmapped_mean_base_quality: unmapped_data.get_mean_quality_map().get_mean().unwrap_or(0.0),
+        unmapped_low_complexity_fraction: unmapped_data.get_low_complexity_fraction(),
+        unmapped_mate_unmapped_count: unmapped_data.get_split_read().get_mate_unmapped_count(),
+        top_unmapped_mate_references
     };
 
     return statistics;
@@ -176,9 +247,9 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
 
     repository.insert("reference_length".to_string(), reference_length_data.clone());
 
-    let bin_size_data: Vec<f64> = data.get_per_reference_data().map(
-        |_| data.get_metadata().bin_size.get() as f64
-    ).collect();
+    let bin_size_data: Vec<f64> = data.get_per_reference_data()
+        .map(|item| item.get_binned_statistics().get_bin_size().get() as f64)
+        .collect();
 
     repository.insert("bin_size".to_string(), bin_size_data);
 
@@ -208,13 +279,13 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
 
     let gap_lengths_file_mean: f64 = data
         .get_gap_length_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let gap_lengths_file_mode: f64 = data
         .get_gap_length_map()
-        .get_max_frequency().unwrap_or((0,0)).0 as f64;
+        .get_mode().unwrap_or(0) as f64;
     let gap_lengths_file_median: f64 = data
         .get_gap_length_map()
-        .get_median_entry().unwrap_or(0.0);
+        .get_median().unwrap_or(0.0);
     let gap_lengths_file_shortest: f64 = data
         .get_gap_length_map()
         .get_min_entry()
@@ -246,13 +317,13 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
 
     let complete_lengths_file_mean: f64 = data
         .get_assembler_length_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let complete_lengths_file_mode: f64 = data
         .get_assembler_length_map()
-        .get_max_frequency().unwrap_or((0,0)).0 as f64;
+        .get_mode().unwrap_or(0) as f64;
     let complete_lengths_file_median: f64 = data
         .get_assembler_length_map()
-        .get_median_entry().unwrap_or(0.0);
+        .get_median().unwrap_or(0.0);
     let complete_lengths_file_shortest: f64 = data
         .get_assembler_length_map()
         .get_min_entry()
@@ -284,13 +355,13 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
 
     let split_counts_file_mean: f64 = data
         .get_split_count_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let split_counts_file_mode: f64 = data
         .get_split_count_map()
-        .get_max_frequency().unwrap_or((0,0)).0 as f64;
+        .get_mode().unwrap_or(0) as f64;
     let split_counts_file_median: f64 = data
         .get_split_count_map()
-        .get_median_entry().unwrap_or(0.0);
+        .get_median().unwrap_or(0.0);
     let split_counts_file_shortest: f64 = data
         .get_split_count_map()
         .get_min_entry()
@@ -322,6 +393,74 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
         split_counts_file_longest
     ]);
 
+    let split_read_classes_file_data: Vec<f64> = SplitReadClass::ALL.iter()
+        .map(|class| data.get_split_read_data().get_class_count(*class) as f64)
+        .collect();
+
+    repository.insert("split_read_classes_file".to_string(), split_read_classes_file_data);
+
+    ////////////////////////////////////////////////////////////////////////////
+    // ---------------------------- Insert Size ----------------------------- //
+    ////////////////////////////////////////////////////////////////////////////
+
+    let insert_sizes_file_box_plot_entry = boxplot_entry_from_frequency_map(data.get_insert_size_map());
+
+    let (
+        insert_sizes_file_box_min,
+        insert_sizes_file_box_q1,
+        insert_sizes_file_box_median,
+        insert_sizes_file_box_mean,
+        insert_sizes_file_box_mode,
+        insert_sizes_file_box_q3,
+        insert_sizes_file_box_max
+    ) = split_box_plot(BoxPlot{ entries: vec![insert_sizes_file_box_plot_entry]});
+
+    repository.insert("insert_sizes_file_min".to_string(), insert_sizes_file_box_min);
+    repository.insert("insert_sizes_file_q1".to_string(), insert_sizes_file_box_q1);
+    repository.insert("insert_sizes_file_median".to_string(), insert_sizes_file_box_median);
+    repository.insert("insert_sizes_file_mean".to_string(), insert_sizes_file_box_mean);
+    repository.insert("insert_sizes_file_mode".to_string(), insert_sizes_file_box_mode);
+    repository.insert("insert_sizes_file_q3".to_string(), insert_sizes_file_box_q3);
+    repository.insert("insert_sizes_file_max".to_string(), insert_sizes_file_box_max);
+
+    let insert_sizes_file_statistics = data.get_insert_size_statistics();
+
+    repository.insert("insert_sizes_file".to_string(), vec![
+        insert_sizes_file_statistics.mean,
+        insert_sizes_file_statistics.median,
+        insert_sizes_file_statistics.median_absolute_deviation
+    ]);
+
+    let insert_sizes_per_reference_box_plot = box_plot_from_frequency_maps(
+        data.get_per_reference_data()
+            .map(|item| item.get_insert_size_map().clone())
+            .collect()
+    );
+
+    let (
+        insert_sizes_per_reference_box_min,
+        insert_sizes_per_reference_box_q1,
+        insert_sizes_per_reference_box_median,
+        insert_sizes_per_reference_box_mean,
+        insert_sizes_per_reference_box_mode,
+        insert_sizes_per_reference_box_q3,
+        insert_sizes_per_reference_box_max
+    ) = split_box_plot(insert_sizes_per_reference_box_plot);
+
+    repository.insert("insert_sizes_per_reference_min".to_string(), insert_sizes_per_reference_box_min);
+    repository.insert("insert_sizes_per_reference_q1".to_string(), insert_sizes_per_reference_box_q1);
+    repository.insert("insert_sizes_per_reference_median".to_string(), insert_sizes_per_reference_box_median);
+    repository.insert("insert_sizes_per_reference_mean".to_string(), insert_sizes_per_reference_box_mean);
+    repository.insert("insert_sizes_per_reference_mode".to_string(), insert_sizes_per_reference_box_mode);
+    repository.insert("insert_sizes_per_reference_q3".to_string(), insert_sizes_per_reference_box_q3);
+    repository.insert("insert_sizes_per_reference_max".to_string(), insert_sizes_per_reference_box_max);
+
+    let insert_sizes_per_reference_median_absolute_deviation: Vec<f64> = data.get_per_reference_data()
+        .map(|item| item.get_insert_size_statistics().median_absolute_deviation)
+        .collect();
+
+    repository.insert("insert_sizes_per_reference_mad".to_string(), insert_sizes_per_reference_median_absolute_deviation);
+
     ////////////////////////////////////////////////////////////////////////////
     // ----------------------------- Unmapped ------------------------------- //
     ////////////////////////////////////////////////////////////////////////////
@@ -365,16 +504,16 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     let mean_unmapped_read_length = data
         .get_unmapped_data()
         .get_read_length_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let mode_unmapped_read_length = data
         .get_unmapped_data()
         .get_read_length_map()
-        .get_max_frequency()
-        .unwrap_or((0,0)).0 as f64;
+        .get_mode()
+        .unwrap_or(0) as f64;
     let median_unmapped_read_length = data
         .get_unmapped_data()
         .get_read_length_map()
-        .get_median_entry()
+        .get_median()
         .unwrap_or(0.0);
     let shortest_unmapped_read_length = data
         .get_unmapped_data()
@@ -424,11 +563,11 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("read_length_sequence_file_max".to_string(), read_length_sequence_file_box_max);
 
     let mean_read_length_sequence_file = data.get_read_length_sequence_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let mode_read_length_sequence_file = data.get_read_length_sequence_map()
-        .get_max_frequency().unwrap_or((0,0)).0 as f64;
+        .get_mode().unwrap_or(0) as f64;
     let median_read_length_sequence_file = data.get_read_length_sequence_map()
-        .get_median_entry().unwrap_or(0.0);
+        .get_median().unwrap_or(0.0);
     let shortest_read_length_sequence_file = data.get_read_length_sequence_map()
         .get_min_entry().unwrap_or((0,0)).0 as f64;
     let longest_read_length_sequence_file = data.get_read_length_sequence_map()
@@ -465,11 +604,11 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("read_length_reference_file_max".to_string(), read_length_reference_file_box_max);
 
     let mean_read_length_reference_file = data.get_read_length_on_reference_map()
-        .get_mean_entry().unwrap();
+        .get_mean().unwrap_or(0.0);
     let mode_read_length_reference_file = data.get_read_length_on_reference_map()
-        .get_max_frequency().unwrap_or((0,0)).0 as f64;
+        .get_mode().unwrap_or(0) as f64;
     let median_read_length_reference_file = data.get_read_length_on_reference_map()
-        .get_median_entry().unwrap_or(0.0);
+        .get_median().unwrap_or(0.0);
     let shortest_read_length_reference_file = data.get_read_length_on_reference_map()
         .get_min_entry().unwrap_or((0,0)).0 as f64;
     let longest_read_length_reference_file = data.get_read_length_on_reference_map()
@@ -508,14 +647,14 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("read_length_sequence_per_reference_max".to_string(), read_length_sequence_per_reference_box_max);
 
     let read_length_sequence_per_reference_mean_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_sequence_map().get_mean_entry().unwrap())
+        .map(|item| item.get_read_length_sequence_map().get_mean().unwrap_or(0.0))
         .collect();
     let read_length_sequence_per_reference_mode_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_sequence_map().get_max_frequency())
-        .map(|item| item.unwrap_or((0,0)).0 as f64)
+        .map(|item| item.get_read_length_sequence_map().get_mode())
+        .map(|item| item.unwrap_or(0) as f64)
         .collect();
     let read_length_sequence_per_reference_median_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_sequence_map().get_median_entry())
+        .map(|item| item.get_read_length_sequence_map().get_median())
         .map(|item| item.unwrap_or(0.0))
         .collect();
     let read_length_sequence_per_reference_shortest_data: Vec<f64> = data.get_per_reference_data()
@@ -552,14 +691,14 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("read_length_reference_per_reference_max".to_string(), read_length_reference_per_reference_box_max);
 
     let read_length_reference_per_reference_mean_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_on_reference_map().get_mean_entry().unwrap())
+        .map(|item| item.get_read_length_on_reference_map().get_mean().unwrap_or(0.0))
         .collect();
     let read_length_reference_per_reference_mode_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_on_reference_map().get_max_frequency())
-        .map(|item| item.unwrap_or((0,0)).0 as f64)
+        .map(|item| item.get_read_length_on_reference_map().get_mode())
+        .map(|item| item.unwrap_or(0) as f64)
         .collect();
     let read_length_reference_per_reference_median_data: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_read_length_on_reference_map().get_median_entry())
+        .map(|item| item.get_read_length_on_reference_map().get_median())
         .map(|item| item.unwrap_or(0.0))
         .collect();
     let read_length_reference_per_reference_shortest_data: Vec<f64> = data.get_per_reference_data()
@@ -617,34 +756,54 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("coverage_per_reference".to_string(), coverage_per_reference_data.clone());
 
     for reference in data.get_per_reference_data() {
-        let read_count_name = format!("{}_read_counts_per_bin", reference.get_reference_name());
-        let total_read_length_name = format!("{}_total_read_length_per_bin", reference.get_reference_name());
-        let coverage_name = format!("{}_coverage_per_bin", reference.get_reference_name());
-
-        let read_count_data: Vec<f64> = reference
-            .get_binned_statistics()
-            .get_bins()
-            .map(|item| item.get_read_count())
-            .map(|item| item as f64)
-            .collect();
-
-        let total_read_length_data: Vec<f64> = reference
-            .get_binned_statistics()
-            .get_bins()
-            .map(|item| item.get_total_read_length())
-            .map(|item| item as f64)
+        let binned_statistics_levels: Vec<BinnedStatisticsPresentationMap> = std::iter::once(reference.get_binned_statistics())
+            .chain(reference.get_zoom_levels().iter().cloned())
             .collect();
 
-        let coverage_data: Vec<f64> = reference
-            .get_binned_statistics()
-            .get_bins()
-            .map(|item| item.get_coverage())
+        let bin_sizes_name = format!("{}_bin_sizes", reference.get_reference_name());
+        let bin_sizes_data: Vec<f64> = binned_statistics_levels.iter()
+            .map(|binned_statistics| binned_statistics.get_bin_size().get() as f64)
             .collect();
 
-
-        repository.insert(read_count_name, read_count_data);
-        repository.insert(total_read_length_name, total_read_length_data);
-        repository.insert(coverage_name, coverage_data);
+        repository.insert(bin_sizes_name, bin_sizes_data);
+
+        // The base resolution keeps the unsuffixed names, every zoom level is suffixed with its index.
+        for (level, binned_statistics) in binned_statistics_levels.iter().enumerate() {
+            let suffix = if level == 0 { String::new() } else { format!("_level_{}", level) };
+
+            let read_count_name = format!("{}_read_counts_per_bin{}", reference.get_reference_name(), suffix);
+            let total_read_length_name = format!("{}_total_read_length_per_bin{}", reference.get_reference_name(), suffix);
+            let coverage_name = format!("{}_coverage_per_bin{}", reference.get_reference_name(), suffix);
+            let breakpoints_name = format!("{}_breakpoints_per_bin{}", reference.get_reference_name(), suffix);
+
+            let read_count_data: Vec<f64> = binned_statistics
+                .get_bins()
+                .map(|item| item.get_read_count())
+                .map(|item| item as f64)
+                .collect();
+
+            let total_read_length_data: Vec<f64> = binned_statistics
+                .get_bins()
+                .map(|item| item.get_total_read_length())
+                .map(|item| item as f64)
+                .collect();
+
+            let coverage_data: Vec<f64> = binned_statistics
+                .get_bins()
+                .map(|item| item.get_coverage())
+                .collect();
+
+            let breakpoints_data: Vec<f64> = binned_statistics
+                .get_bins()
+                .map(|item| item.get_breakpoints())
+                .map(|item| item as f64)
+                .collect();
+
+            repository.insert(read_count_name, read_count_data);
+            repository.insert(total_read_length_name, total_read_length_data);
+            repository.insert(coverage_name, coverage_data);
+            repository.insert(breakpoints_name, breakpoints_data);
+        }
     }
 
     ////////////////////////////////////////////////////////////////////////////
@@ -852,6 +1011,60 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
         repository.insert(format!("{}_cigar_percentage_per_bin_skip",&name), per_bin_percentage_skip_data);
     }
 
+    ////////////////////////////////////////////////////////////////////////////
+    // ------------------------ Alignment Categories ------------------------ //
+    ////////////////////////////////////////////////////////////////////////////
+
+    let per_reference_alignment_categories: Vec<AlignmentCategories> = data.get_per_reference_data()
+        .map(|item| item.get_alignment_categories())
+        .collect();
+
+    let duplicate_rate_per_reference_data: Vec<f64> = per_reference_alignment_categories.iter()
+        .map(|item| item.get_duplicate_rate() * 100.0)
+        .collect();
+
+    let secondary_rate_per_reference_data: Vec<f64> = per_reference_alignment_categories.iter()
+        .map(|item| item.get_secondary_rate() * 100.0)
+        .collect();
+
+    let supplementary_rate_per_reference_data: Vec<f64> = per_reference_alignment_categories.iter()
+        .map(|item| item.get_supplementary_rate() * 100.0)
+        .collect();
+
+    let qc_failed_rate_per_reference_data: Vec<f64> = per_reference_alignment_categories.iter()
+        .map(|item| item.get_qc_failed_rate() * 100.0)
+        .collect();
+
+    repository.insert("duplicate_rate_per_reference".to_string(), duplicate_rate_per_reference_data);
+    repository.insert("secondary_rate_per_reference".to_string(), secondary_rate_per_reference_data);
+    repository.insert("supplementary_rate_per_reference".to_string(), supplementary_rate_per_reference_data);
+    repository.insert("qc_failed_rate_per_reference".to_string(), qc_failed_rate_per_reference_data);
+
+    let unique_mapping_rate_per_reference_data: Vec<f64> = data.get_per_reference_data()
+        .map(|item| item.get_unique_mapping_fraction() * 100.0)
+        .collect();
+
+    repository.insert("unique_mapping_rate_file".to_string(), vec![data.get_unique_mapping_fraction() * 100.0]);
+    repository.insert("unique_mapping_rate_per_reference".to_string(), unique_mapping_rate_per_reference_data);
+
+    for reference in data.get_per_reference_data() {
+        let duplicate_rate_name = format!("{}_duplicate_rate_per_bin", reference.get_reference_name());
+        let secondary_rate_name = format!("{}_secondary_rate_per_bin", reference.get_reference_name());
+
+        let per_bin_alignment_categories = reference.get_alignment_categories_per_bin();
+
+        let duplicate_rate_data: Vec<f64> = per_bin_alignment_categories.iter()
+            .map(|item| item.get_duplicate_rate() * 100.0)
+            .collect();
+
+        let secondary_rate_data: Vec<f64> = per_bin_alignment_categories.iter()
+            .map(|item| item.get_secondary_rate() * 100.0)
+            .collect();
+
+        repository.insert(duplicate_rate_name, duplicate_rate_data);
+        repository.insert(secondary_rate_name, secondary_rate_data);
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     // ------------------------------ Quality ------------------------------- //
     ////////////////////////////////////////////////////////////////////////////
@@ -888,15 +1101,14 @@ fn generate_data_repository(data: &PresentationData) -> HashMap<String, Vec<f64>
     repository.insert("read_quality_per_reference_max".to_string(), read_quality_per_reference_box_max);
 
     let per_reference_read_quality_mean: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_quality_frequency().get_mean_entry().unwrap())
+        .map(|item| item.get_quality_frequency().get_mean().unwrap_or(0.0))
         .collect();
     let per_reference_read_quality_mode: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_quality_frequency().get_max_frequency())
-        .map(|item| item.map(|(quality,_)| quality ))
+        .map(|item| item.get_quality_frequency().get_mode())
         .map(|item| item.unwrap_or(0) as f64)
         .collect();
     let per_reference_read_quality_median: Vec<f64> = data.get_per_reference_data()
-        .map(|item| item.get_quality_frequency().get_median_entry())
+        .map(|item| item.get_quality_frequency().get_median())
         .map(|item| item.unwrap_or(0.0))
         .collect();
     let per_reference_read_quality_min: Vec<f64> = data.get_per_reference_data()
@@ -1,4 +1,5 @@
 use wasm_bindgen::prelude::*;
+use segemehl_21_core::event::{Event, EventLevel, EventSink};
 
 #[wasm_bindgen]
 extern "C" {
@@ -41,4 +42,16 @@ macro_rules! console_info {
 #[macro_export]
 macro_rules! console_warn {
     ($($t:tt)*) => ($crate::util::console::warn(&format_args!($($t)*).to_string()))
-}
\ No newline at end of file
+}
+/// Routes the events of core to the browser console.
+#[derive(Debug, Default)]
+pub struct ConsoleEventSink;
+
+impl EventSink for ConsoleEventSink {
+	fn handle(&self, event: Event) {
+		match event.get_level() {
+			EventLevel::Info => console_log!("{}", event),
+			EventLevel::Warning => console_warn!("{}", event)
+		}
+	}
+}
@@ -7,6 +7,9 @@ const longest_read_length_row = document.getElementById("numeric-statistics-long
 const median_read_length_row = document.getElementById("numeric-statistics-median-read-length-row");
 const mode_read_length_row = document.getElementById("numeric-statistics-mode-read-length-row");
 const mean_read_length_row = document.getElementById("numeric-statistics-mean-read-length-row");
+const read_length_interquartile_range_row = document.getElementById("numeric-statistics-read-length-interquartile-range-row");
+const read_length_standard_deviation_row = document.getElementById("numeric-statistics-read-length-standard-deviation-row");
+const read_length_median_absolute_deviation_row = document.getElementById("numeric-statistics-read-length-median-absolute-deviation-row");
 
 const chromosome_file_row = document.getElementById("numeric-statistics-chromosome-file-row");
 
@@ -19,10 +22,18 @@ const longest_length_of_chromosomes_row = document.getElementById("numeric-stati
 
 const chromosome_coverage_file_row = document.getElementById("numeric-statistics-chromosome-coverage-file-row");
 
-const median_chromosome_coverage_row = document.getElementById("numeric-statistics-median-chromosome-coverage-row");
-const mean_chromosome_coverage_row = document.getElementById("numeric-statistics-mean-chromosome-coverage-row");
-const least_chromosome_coverage_row = document.getElementById("numeric-statistics-least-chromosome-coverage-row");
-const most_chromosome_coverage_row = document.getElementById("numeric-statistics-most-chromosome-coverage-row");
+const median_chromosome_depth_row = document.getElementById("numeric-statistics-median-chromosome-depth-row");
+const mean_chromosome_depth_row = document.getElementById("numeric-statistics-mean-chromosome-depth-row");
+const least_chromosome_depth_row = document.getElementById("numeric-statistics-least-chromosome-depth-row");
+const most_chromosome_depth_row = document.getElementById("numeric-statistics-most-chromosome-depth-row");
+const median_chromosome_read_density_row = document.getElementById("numeric-statistics-median-chromosome-read-density-row");
+const mean_chromosome_read_density_row = document.getElementById("numeric-statistics-mean-chromosome-read-density-row");
+const least_chromosome_read_density_row = document.getElementById("numeric-statistics-least-chromosome-read-density-row");
+const most_chromosome_read_density_row = document.getElementById("numeric-statistics-most-chromosome-read-density-row");
+const coverage_coefficient_of_variation_row = document.getElementById("numeric-statistics-coverage-coefficient-of-variation-row");
+const coverage_gini_coefficient_row = document.getElementById("numeric-statistics-coverage-gini-coefficient-row");
+const coverage_fold_80_base_penalty_row = document.getElementById("numeric-statistics-coverage-fold-80-base-penalty-row");
+const coverage_near_median_fraction_row = document.getElementById("numeric-statistics-coverage-near-median-fraction-row");
 
 const per_chromosome_file_row = document.getElementById("numeric-statistics-per-chromosome-file-row");
 
@@ -30,11 +41,28 @@ const median_number_of_reads_per_chromosome_row = document.getElementById("numer
 const mode_number_of_reads_per_chromosome_row = document.getElementById("numeric-statistics-mode-number-of-reads-per-chromosome-row");
 const mean_number_of_reads_per_chromosome_row = document.getElementById("numeric-statistics-mean-number-of-reads-per-chromosome-row");
 
+const circular_rna_file_row = document.getElementById("numeric-statistics-circular-rna-file-row");
+
+const circular_rna_candidate_count_row = document.getElementById("numeric-statistics-circular-rna-candidate-count-row");
+const circular_rna_supporting_read_count_row = document.getElementById("numeric-statistics-circular-rna-supporting-read-count-row");
+const top_circular_rna_candidates_row = document.getElementById("numeric-statistics-top-circular-rna-candidates-row");
+
+const unmapped_file_row = document.getElementById("numeric-statistics-unmapped-file-row");
+
+const unmapped_gc_content_row = document.getElementById("numeric-statistics-unmapped-gc-content-row");
+const unmapped_n_rate_row = document.getElementById("numeric-statistics-unmapped-n-rate-row");
+const unmapped_mean_base_quality_row = document.getElementById("numeric-statistics-unmapped-mean-base-quality-row");
+const unmapped_low_complexity_fraction_row = document.getElementById("numeric-statistics-unmapped-low-complexity-fraction-row");
+const unmapped_mate_unmapped_count_row = document.getElementById("numeric-statistics-unmapped-mate-unmapped-count-row");
+const top_unmapped_mate_references_row = document.getElementById("numeric-statistics-top-unmapped-mate-references-row");
+
 export function rebuild_numeric_statistics(file_names, numeric_statistics) {
     rebuild_per_file_numeric_statistics(file_names, numeric_statistics);
     rebuild_chromosome_numeric_statistics(file_names, numeric_statistics);
     rebuild_chromosome_coverage_numeric_statistics(file_names, numeric_statistics);
     rebuild_per_chromosome_numeric_statistics(file_names, numeric_statistics);
+    rebuild_circular_rna_numeric_statistics(file_names, numeric_statistics);
+    rebuild_unmapped_numeric_statistics(file_names, numeric_statistics);
 }
 
 function rebuild_per_file_numeric_statistics(file_names, numeric_statistics) {
@@ -45,6 +73,9 @@ function rebuild_per_file_numeric_statistics(file_names, numeric_statistics) {
     let median_read_lengths = [];
     let mode_read_lengths = [];
     let mean_read_lengths = [];
+    let read_length_interquartile_ranges = [];
+    let read_length_standard_deviations = [];
+    let read_length_median_absolute_deviations = [];
 
     for (let i = 0; i < file_names.length; i++) {
         const statistic = numeric_statistics[i];
@@ -56,6 +87,9 @@ function rebuild_per_file_numeric_statistics(file_names, numeric_statistics) {
         median_read_lengths.push(statistic.median_length_of_read_in_file);
         mode_read_lengths.push(statistic.mode_length_of_read_in_file);
         mean_read_lengths.push(statistic.mean_length_of_read_in_file);
+        read_length_interquartile_ranges.push(statistic.read_length_interquartile_range);
+        read_length_standard_deviations.push(statistic.read_length_standard_deviation.toFixed(2));
+        read_length_median_absolute_deviations.push(statistic.read_length_median_absolute_deviation);
     }
 
     rebuild_table_header("Per File Statistics", file_names, per_file_file_row);
@@ -67,6 +101,9 @@ function rebuild_per_file_numeric_statistics(file_names, numeric_statistics) {
     rebuild_table_row("Median Read Length", median_read_length_row, median_read_lengths);
     rebuild_table_row("Mode Read Length", mode_read_length_row, mode_read_lengths);
     rebuild_table_row("Mean Read Length", mean_read_length_row, mean_read_lengths);
+    rebuild_table_row("Interquartile Range of Read Lengths", read_length_interquartile_range_row, read_length_interquartile_ranges);
+    rebuild_table_row("Standard Deviation of Read Lengths", read_length_standard_deviation_row, read_length_standard_deviations);
+    rebuild_table_row("Median Absolute Deviation of Read Lengths", read_length_median_absolute_deviation_row, read_length_median_absolute_deviations);
 }
 
 function rebuild_chromosome_numeric_statistics(file_names, numeric_statistics) {
@@ -99,26 +136,50 @@ function rebuild_chromosome_numeric_statistics(file_names, numeric_statistics) {
 }
 
 function rebuild_chromosome_coverage_numeric_statistics(file_names, numeric_statistics) {
-    let median_coverages = [];
-    let mean_coverages = [];
-    let least_coverages = [];
-    let most_coverages = [];
+    let median_depths = [];
+    let mean_depths = [];
+    let least_depths = [];
+    let most_depths = [];
+    let median_read_densities = [];
+    let mean_read_densities = [];
+    let least_read_densities = [];
+    let most_read_densities = [];
+    let coefficients_of_variation = [];
+    let gini_coefficients = [];
+    let fold_80_base_penalties = [];
+    let near_median_fractions = [];
 
     for (let i = 0; i < file_names.length; i++) {
         const statistic = numeric_statistics[i];
 
-        median_coverages.push((statistic.median_chromosome_coverage * 100).toFixed(2) + "%");
-        mean_coverages.push((statistic.mean_chromosome_coverage * 100).toFixed(2) + "%");
-        least_coverages.push((statistic.least_chromosome_coverage * 100).toFixed(2) + "%");
-        most_coverages.push((statistic.most_chromosome_coverage * 100).toFixed(2) + "%");
+        median_depths.push(statistic.median_chromosome_depth.toFixed(2) + "x");
+        mean_depths.push(statistic.mean_chromosome_depth.toFixed(2) + "x");
+        least_depths.push(statistic.least_chromosome_depth.toFixed(2) + "x");
+        most_depths.push(statistic.most_chromosome_depth.toFixed(2) + "x");
+        median_read_densities.push(statistic.median_chromosome_read_density.toFixed(4));
+        mean_read_densities.push(statistic.mean_chromosome_read_density.toFixed(4));
+        least_read_densities.push(statistic.least_chromosome_read_density.toFixed(4));
+        most_read_densities.push(statistic.most_chromosome_read_density.toFixed(4));
+        coefficients_of_variation.push(statistic.coverage_coefficient_of_variation.toFixed(3));
+        gini_coefficients.push(statistic.coverage_gini_coefficient.toFixed(3));
+        fold_80_base_penalties.push(statistic.coverage_fold_80_base_penalty == null ? "-" : statistic.coverage_fold_80_base_penalty.toFixed(3));
+        near_median_fractions.push((statistic.coverage_near_median_fraction * 100).toFixed(2) + "%");
     }
 
     rebuild_table_header("Chromosome Coverage Statistics",file_names, chromosome_coverage_file_row);
 
-    rebuild_table_row("Median Coverage of Chromosome", median_chromosome_coverage_row, median_coverages);
-    rebuild_table_row("Mean Coverage of Chromosome", mean_chromosome_coverage_row, mean_coverages);
-    rebuild_table_row("Least Coverage of Chromosomes", least_chromosome_coverage_row, least_coverages);
-    rebuild_table_row("Most Coverage of Chromosomes", most_chromosome_coverage_row, most_coverages);
+    rebuild_table_row("Median Depth of Chromosomes", median_chromosome_depth_row, median_depths);
+    rebuild_table_row("Mean Depth of all Bases", mean_chromosome_depth_row, mean_depths);
+    rebuild_table_row("Least Depth of Chromosomes", least_chromosome_depth_row, least_depths);
+    rebuild_table_row("Most Depth of Chromosomes", most_chromosome_depth_row, most_depths);
+    rebuild_table_row("Median Reads per Base of Chromosomes", median_chromosome_read_density_row, median_read_densities);
+    rebuild_table_row("Mean Reads per Base of Chromosomes", mean_chromosome_read_density_row, mean_read_densities);
+    rebuild_table_row("Least Reads per Base of Chromosomes", least_chromosome_read_density_row, least_read_densities);
+    rebuild_table_row("Most Reads per Base of Chromosomes", most_chromosome_read_density_row, most_read_densities);
+    rebuild_table_row("Coefficient of Variation of Bin Coverage", coverage_coefficient_of_variation_row, coefficients_of_variation);
+    rebuild_table_row("Gini Coefficient of Bin Coverage", coverage_gini_coefficient_row, gini_coefficients);
+    rebuild_table_row("Fold-80 Base Penalty", coverage_fold_80_base_penalty_row, fold_80_base_penalties);
+    rebuild_table_row("Bases in Bins within 0.2x-5x of the Median Coverage", coverage_near_median_fraction_row, near_median_fractions);
 }
 
 function rebuild_per_chromosome_numeric_statistics(file_names, numeric_statistics) {
@@ -142,6 +203,55 @@ function rebuild_per_chromosome_numeric_statistics(file_names, numeric_statistic
 
 }
 
+function rebuild_circular_rna_numeric_statistics(file_names, numeric_statistics) {
+    let candidate_counts = [];
+    let supporting_read_counts = [];
+    let top_candidates = [];
+
+    for (let i = 0; i < file_names.length; i++) {
+        const statistic = numeric_statistics[i];
+
+        candidate_counts.push(statistic.circular_rna_candidate_count);
+        supporting_read_counts.push(statistic.circular_rna_supporting_read_count);
+        top_candidates.push(statistic.top_circular_rna_candidates.join("<br>"));
+    }
+
+    rebuild_table_header("Circular RNA Statistics", file_names, circular_rna_file_row);
+
+    rebuild_table_row("Number of Candidates", circular_rna_candidate_count_row, candidate_counts);
+    rebuild_table_row("Number of Back-Splice Reads", circular_rna_supporting_read_count_row, supporting_read_counts);
+    rebuild_table_row("Top Candidates", top_circular_rna_candidates_row, top_candidates);
+}
+
+function rebuild_unmapped_numeric_statistics(file_names, numeric_statistics) {
+    let gc_contents = [];
+    let n_rates = [];
+    let mean_base_qualities = [];
+    let low_complexity_fractions = [];
+    let mate_unmapped_counts = [];
+    let top_mate_references = [];
+
+    for (let i = 0; i < file_names.length; i++) {
+        const statistic = numeric_statistics[i];
+
+        gc_contents.push(statistic.unmapped_gc_content);
+        n_rates.push(statistic.unmapped_n_rate);
+        mean_base_qualities.push(statistic.unmapped_mean_base_quality);
+        low_complexity_fractions.push(statistic.unmapped_low_complexity_fraction);
+        mate_unmapped_counts.push(statistic.unmapped_mate_unmapped_count);
+        top_mate_references.push(statistic.top_unmapped_mate_references.join("<br>"));
+    }
+
+    rebuild_table_header("Unmapped Read Statistics", file_names, unmapped_file_row);
+
+    rebuild_table_row("GC Content", unmapped_gc_content_row, gc_contents);
+    rebuild_table_row("N Rate", unmapped_n_rate_row, n_rates);
+    rebuild_table_row("Mean Base Quality", unmapped_mean_base_quality_row, mean_base_qualities);
+    rebuild_table_row("Low Complexity Fraction", unmapped_low_complexity_fraction_row, low_complexity_fractions);
+    rebuild_table_row("Number of Reads with Unmapped Mate", unmapped_mate_unmapped_count_row, mate_unmapped_counts);
+    rebuild_table_row("Top Mate References", top_unmapped_mate_references_row, top_mate_references);
+}
+
 function rebuild_table_header(header_name, file_names, header_row) {
     clear_table_row(header_row);
 
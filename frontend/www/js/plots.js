@@ -35,6 +35,11 @@ import {
     update_reference_dependent_split_read_plots,
     update_split_read_plots
 } from "./plots/split_read_plots";
+import {
+    setup_alignment_category_plots,
+    update_alignment_category_plots,
+    update_reference_dependent_alignment_category_plots
+} from "./plots/alignment_category_plots";
 import {get_reference_names} from "./reference_list";
 
 export function setup_plots() {
@@ -43,6 +48,7 @@ export function setup_plots() {
     setup_unmapped_plots();
     setup_coverage_plots();
     setup_cigar_plots();
+    setup_alignment_category_plots();
     setup_read_quality_plots();
     setup_read_length_plots();
 
@@ -57,6 +63,7 @@ export function update_all_plots() {
     update_unmapped_plots();
     update_coverage_plots();
     update_cigar_plots();
+    update_alignment_category_plots();
     update_read_quality_plots();
     update_read_length_plots();
 
@@ -69,6 +76,7 @@ function update_reference_dependent_plots() {
     update_reference_dependent_unmapped_plots();
     update_reference_dependent_coverage_plots();
     update_reference_dependent_cigar_plots();
+    update_reference_dependent_alignment_category_plots();
     update_reference_dependent_read_quality_plots();
     update_reference_dependent_read_length_plots();
 }
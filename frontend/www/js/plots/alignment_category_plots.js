@@ -0,0 +1,798 @@
+import {linking_update_selected_reference} from "../plots";
+import {bin_data_to_line_data, calculate_decimation_size, decimate_bin_data_mean, generate_labels} from "./line_plot";
+import {get_reference_names} from "../reference_list";
+import {get_dataset, get_file_list} from "../file_storage";
+
+export function setup_alignment_category_plots() {
+    setup_duplicate_rate_per_reference_plot();
+    setup_duplicate_rate_per_bin_on_reference_plot();
+    setup_secondary_rate_per_reference_plot();
+    setup_secondary_rate_per_bin_on_reference_plot();
+    setup_supplementary_rate_per_reference_plot();
+    setup_qc_failed_rate_per_reference_plot();
+    setup_unique_mapping_rate_per_reference_plot();
+}
+
+export function update_alignment_category_plots() {
+    reference_names = get_reference_names();
+
+    update_duplicate_rate_per_reference_plot();
+    update_secondary_rate_per_reference_plot();
+    update_supplementary_rate_per_reference_plot();
+    update_qc_failed_rate_per_reference_plot();
+    update_unique_mapping_rate_per_reference_plot();
+}
+
+export function update_reference_dependent_alignment_category_plots() {
+    update_duplicate_rate_per_bin_on_reference_plot();
+    update_secondary_rate_per_bin_on_reference_plot();
+}
+
+const selected_reference = document.getElementById("chromosome-select");
+
+let reference_names = [];
+
+let duplicate_rate_per_reference_plot;
+
+function setup_duplicate_rate_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Duplicate Rate per Reference"
+                },
+                tooltip: {
+                    enabled: true,
+                    callbacks: {
+                        label: function(context) {
+                            var label = context.dataset.label || '';
+
+                            if (label) {
+                                label += ': ';
+                            }
+                            if (context.parsed.y !== null) {
+                                label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                                label += "%";
+                            }
+                            return label;
+                        }
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Duplicate Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    duplicate_rate_per_reference_plot = new Chart(
+        document.getElementById('duplicate_rate_per_reference_canvas'),
+        config
+    );
+}
+
+function update_duplicate_rate_per_reference_plot() {
+    if (duplicate_rate_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"duplicate_rate_per_reference");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        duplicate_rate_per_reference_plot.config.data = plot_data;
+        duplicate_rate_per_reference_plot.update();
+    }
+}
+
+let duplicate_rate_per_bin_on_reference_plot;
+
+function setup_duplicate_rate_per_bin_on_reference_plot() {
+    let data = {
+        datasets: []
+    };
+
+    let config = {
+        type: 'line',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Duplicate Rate per Bin on selected Chromosome"
+                }
+            },
+            tooltip: {
+                enabled: true,
+                callbacks: {
+                    label: function(context) {
+                        var label = context.dataset.label || '';
+
+                        if (label) {
+                            label += ': ';
+                        }
+                        if (context.parsed.y !== null) {
+                            label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                            label += "%";
+                        }
+                        return label;
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            interaction: {
+                mode: "index",
+                axis: "x",
+                intersect: false
+            },
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Position on Reference',
+                    },
+                    type: "linear",
+                    ticks: {
+                        source: 'auto',
+                        // Disabled rotation for performance
+                        maxRotation: 0,
+                        autoSkip: true,
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Duplicate Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            animation: false,
+            parsing: false,
+            line: {
+                cubicInterpolationMode: "monotone"
+            }
+        }
+    };
+
+    duplicate_rate_per_bin_on_reference_plot = new Chart(
+        document.getElementById('duplicate_rate_per_bin_canvas'),
+        config
+    );
+}
+
+function update_duplicate_rate_per_bin_on_reference_plot() {
+    if (duplicate_rate_per_bin_on_reference_plot) {
+        let plot_data = {
+            indexAxis: "x",
+            labels: [],
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        let reference = selected_reference.value;
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const dataset_name = reference + "_duplicate_rate_per_bin";
+
+            let bin_size = get_dataset(name, "bin_size")[0];
+            let line_data = bin_data_to_line_data(get_dataset(name, dataset_name));
+            let decimated_data = decimate_bin_data_mean(line_data, 1000, 1000);
+
+            let decimation_size = calculate_decimation_size(line_data, 1000, 1000);
+            plot_data.labels = generate_labels(decimated_data, bin_size * decimation_size);
+
+            let dataset = {
+                label: name,
+                data: decimated_data,
+                backgroundColor: color,
+                borderColor: color,
+                borderWidth: 1,
+                radius: 1,
+                fill: false,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        duplicate_rate_per_bin_on_reference_plot.config.data = plot_data;
+        duplicate_rate_per_bin_on_reference_plot.update();
+    }
+}
+
+let secondary_rate_per_reference_plot;
+
+function setup_secondary_rate_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Secondary Alignment Rate per Reference"
+                },
+                tooltip: {
+                    enabled: true,
+                    callbacks: {
+                        label: function(context) {
+                            var label = context.dataset.label || '';
+
+                            if (label) {
+                                label += ': ';
+                            }
+                            if (context.parsed.y !== null) {
+                                label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                                label += "%";
+                            }
+                            return label;
+                        }
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Secondary Alignment Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    secondary_rate_per_reference_plot = new Chart(
+        document.getElementById('secondary_rate_per_reference_canvas'),
+        config
+    );
+}
+
+function update_secondary_rate_per_reference_plot() {
+    if (secondary_rate_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"secondary_rate_per_reference");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        secondary_rate_per_reference_plot.config.data = plot_data;
+        secondary_rate_per_reference_plot.update();
+    }
+}
+
+let secondary_rate_per_bin_on_reference_plot;
+
+function setup_secondary_rate_per_bin_on_reference_plot() {
+    let data = {
+        datasets: []
+    };
+
+    let config = {
+        type: 'line',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Secondary Alignment Rate per Bin on selected Chromosome"
+                }
+            },
+            tooltip: {
+                enabled: true,
+                callbacks: {
+                    label: function(context) {
+                        var label = context.dataset.label || '';
+
+                        if (label) {
+                            label += ': ';
+                        }
+                        if (context.parsed.y !== null) {
+                            label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                            label += "%";
+                        }
+                        return label;
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            interaction: {
+                mode: "index",
+                axis: "x",
+                intersect: false
+            },
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Position on Reference',
+                    },
+                    type: "linear",
+                    ticks: {
+                        source: 'auto',
+                        // Disabled rotation for performance
+                        maxRotation: 0,
+                        autoSkip: true,
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Secondary Alignment Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            animation: false,
+            parsing: false,
+            line: {
+                cubicInterpolationMode: "monotone"
+            }
+        }
+    };
+
+    secondary_rate_per_bin_on_reference_plot = new Chart(
+        document.getElementById('secondary_rate_per_bin_canvas'),
+        config
+    );
+}
+
+function update_secondary_rate_per_bin_on_reference_plot() {
+    if (secondary_rate_per_bin_on_reference_plot) {
+        let plot_data = {
+            indexAxis: "x",
+            labels: [],
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        let reference = selected_reference.value;
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const dataset_name = reference + "_secondary_rate_per_bin";
+
+            let bin_size = get_dataset(name, "bin_size")[0];
+            let line_data = bin_data_to_line_data(get_dataset(name, dataset_name));
+            let decimated_data = decimate_bin_data_mean(line_data, 1000, 1000);
+
+            let decimation_size = calculate_decimation_size(line_data, 1000, 1000);
+            plot_data.labels = generate_labels(decimated_data, bin_size * decimation_size);
+
+            let dataset = {
+                label: name,
+                data: decimated_data,
+                backgroundColor: color,
+                borderColor: color,
+                borderWidth: 1,
+                radius: 1,
+                fill: false,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        secondary_rate_per_bin_on_reference_plot.config.data = plot_data;
+        secondary_rate_per_bin_on_reference_plot.update();
+    }
+}
+
+let supplementary_rate_per_reference_plot;
+
+function setup_supplementary_rate_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Supplementary Alignment Rate per Reference"
+                },
+                tooltip: {
+                    enabled: true,
+                    callbacks: {
+                        label: function(context) {
+                            var label = context.dataset.label || '';
+
+                            if (label) {
+                                label += ': ';
+                            }
+                            if (context.parsed.y !== null) {
+                                label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                                label += "%";
+                            }
+                            return label;
+                        }
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Supplementary Alignment Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    supplementary_rate_per_reference_plot = new Chart(
+        document.getElementById('supplementary_rate_per_reference_canvas'),
+        config
+    );
+}
+
+function update_supplementary_rate_per_reference_plot() {
+    if (supplementary_rate_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"supplementary_rate_per_reference");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        supplementary_rate_per_reference_plot.config.data = plot_data;
+        supplementary_rate_per_reference_plot.update();
+    }
+}
+
+let qc_failed_rate_per_reference_plot;
+
+function setup_qc_failed_rate_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "QC-Fail Rate per Reference"
+                },
+                tooltip: {
+                    enabled: true,
+                    callbacks: {
+                        label: function(context) {
+                            var label = context.dataset.label || '';
+
+                            if (label) {
+                                label += ': ';
+                            }
+                            if (context.parsed.y !== null) {
+                                label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                                label += "%";
+                            }
+                            return label;
+                        }
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'QC-Fail Rate',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    qc_failed_rate_per_reference_plot = new Chart(
+        document.getElementById('qc_failed_rate_per_reference_canvas'),
+        config
+    );
+}
+
+function update_qc_failed_rate_per_reference_plot() {
+    if (qc_failed_rate_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"qc_failed_rate_per_reference");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        qc_failed_rate_per_reference_plot.config.data = plot_data;
+        qc_failed_rate_per_reference_plot.update();
+    }
+}
+
+let unique_mapping_rate_per_reference_plot;
+
+function setup_unique_mapping_rate_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Uniquely Mapped Reads per Reference"
+                },
+                tooltip: {
+                    enabled: true,
+                    callbacks: {
+                        label: function(context) {
+                            var label = context.dataset.label || '';
+
+                            if (label) {
+                                label += ': ';
+                            }
+                            if (context.parsed.y !== null) {
+                                label += new Intl.NumberFormat('en-US').format(context.parsed.y);
+                                label += "%";
+                            }
+                            return label;
+                        }
+                    }
+                }
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Uniquely Mapped Reads',
+                    },
+                    ticks: {
+                        callback: function(value){return value+"%"}
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    unique_mapping_rate_per_reference_plot = new Chart(
+        document.getElementById('unique_mapping_rate_per_reference_canvas'),
+        config
+    );
+}
+
+function update_unique_mapping_rate_per_reference_plot() {
+    if (unique_mapping_rate_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"unique_mapping_rate_per_reference");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color,
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        unique_mapping_rate_per_reference_plot.config.data = plot_data;
+        unique_mapping_rate_per_reference_plot.update();
+    }
+}
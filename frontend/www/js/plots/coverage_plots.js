@@ -10,6 +10,7 @@ export function setup_coverage_plots() {
     setup_total_read_length_per_bin_on_reference_plot();
     setup_coverage_per_reference_plot();
     setup_coverage_per_bin_on_reference_plot();
+    setup_bin_level_controls();
 }
 
 export function update_coverage_plots() {
@@ -21,6 +22,7 @@ export function update_coverage_plots() {
 }
 
 export function update_reference_dependent_coverage_plots() {
+    update_bin_level_options();
     update_read_counts_per_bin_on_reference_plot();
     update_total_read_length_per_bin_on_reference_plot();
     update_coverage_per_bin_on_reference_plot();
@@ -30,6 +32,50 @@ const selected_reference = document.getElementById("chromosome-select");
 
 let reference_names = [];
 
+const bin_level_select = document.getElementById("coverage_bin_level_select");
+const bin_region_start = document.getElementById("coverage_bin_region_start");
+const bin_region_end = document.getElementById("coverage_bin_region_end");
+
+function setup_bin_level_controls() {
+    bin_level_select.addEventListener("change", () => update_reference_dependent_coverage_plots());
+    bin_region_start.addEventListener("change", () => update_reference_dependent_coverage_plots());
+    bin_region_end.addEventListener("change", () => update_reference_dependent_coverage_plots());
+}
+
+// Offers the bin sizes of the first loaded file, the selection is kept if the selected reference has the same bin size.
+function update_bin_level_options() {
+    const reference = selected_reference.value;
+    const loaded_file = get_file_list().find((file_info) => file_info[2]);
+    const bin_sizes = (loaded_file && get_dataset(loaded_file[0], reference + "_bin_sizes")) || [];
+    const selected_bin_size = bin_level_select.value;
+
+    bin_level_select.replaceChildren(
+        ...bin_sizes.map((bin_size) => new Option(bin_size.toLocaleString("de-DE") + " bp", bin_size))
+    );
+
+    if (bin_sizes.map(String).includes(selected_bin_size)) {
+        bin_level_select.value = selected_bin_size;
+    }
+}
+
+// The bins of the selected bin size within the selected region, files without that bin size fall back to their base bins.
+function get_bins_in_region(name, reference, dataset_name) {
+    const bin_sizes = get_dataset(name, reference + "_bin_sizes") || [];
+    const level = Math.max(bin_sizes.indexOf(Number(bin_level_select.value)), 0);
+    const bin_size = bin_sizes[level] || 1;
+    const bins = get_dataset(name, reference + dataset_name + (level === 0 ? "" : "_level_" + level)) || [];
+
+    const region_end = Number(bin_region_end.value);
+    const first_bin = Math.min(Math.floor((Number(bin_region_start.value) || 0) / bin_size), bins.length);
+    const last_bin = region_end > 0 ? Math.ceil(region_end / bin_size) : bins.length;
+
+    return {
+        bins: bins.slice(first_bin, last_bin),
+        bin_size: bin_size,
+        offset: first_bin * bin_size
+    };
+}
+
 let read_counts_per_reference_plot;
 let read_counts_per_reference_logarithmic = document.getElementById("read_counts_per_reference_logarithmic");
 
@@ -199,14 +245,12 @@ function update_read_counts_per_bin_on_reference_plot() {
             const name = file_info[0];
             const color = file_info[1][0];
 
-            const dataset_name = reference + "_read_counts_per_bin";
-
-            let bin_size = get_dataset(name, "bin_size")[0];
-            let line_data = bin_data_to_line_data(get_dataset(name, dataset_name));
+            let bins = get_bins_in_region(name, reference, "_read_counts_per_bin");
+            let line_data = bin_data_to_line_data(bins.bins);
             let decimated_data = decimate_bin_data_mean(line_data, 1000, 1000);
 
             let decimation_size = calculate_decimation_size(line_data, 1000, 1000);
-            plot_data.labels = generate_labels(decimated_data, bin_size * decimation_size);
+            plot_data.labels = generate_labels(decimated_data, bins.bin_size * decimation_size, bins.offset);
 
             let dataset = {
                 label: name,
@@ -406,14 +450,12 @@ function update_total_read_length_per_bin_on_reference_plot() {
             const name = file_info[0];
             const color = file_info[1][0];
 
-            const dataset_name = reference + "_total_read_length_per_bin";
-
-            let bin_size = get_dataset(name, "bin_size")[0];
-            let line_data = bin_data_to_line_data(get_dataset(name, dataset_name));
+            let bins = get_bins_in_region(name, reference, "_total_read_length_per_bin");
+            let line_data = bin_data_to_line_data(bins.bins);
             let decimated_data = decimate_bin_data_mean(line_data, 1000, 1000);
 
             let decimation_size = calculate_decimation_size(line_data, 1000, 1000);
-            plot_data.labels = generate_labels(decimated_data, bin_size * decimation_size);
+            plot_data.labels = generate_labels(decimated_data, bins.bin_size * decimation_size, bins.offset);
 
             let dataset = {
                 label: name,
@@ -495,6 +537,17 @@ function setup_coverage_per_reference_plot() {
                     ticks: {
                         callback: function(value){return value+"%"}
                     }
+                },
+                breakpoints: {
+                    title: {
+                        display: true,
+                        text: 'Split Read Breakpoints',
+                    },
+                    position: "right",
+                    display: "auto",
+                    grid: {
+                        drawOnChartArea: false
+                    }
                 }
             },
             interaction: {
@@ -546,6 +599,7 @@ function update_coverage_per_reference_plot() {
 }
 
 let coverage_per_bin_on_reference_plot;
+let coverage_per_bin_breakpoints = document.getElementById("coverage_per_bin_breakpoints");
 
 function setup_coverage_per_bin_on_reference_plot() {
     let data = {
@@ -623,6 +677,8 @@ function setup_coverage_per_bin_on_reference_plot() {
         document.getElementById('coverage_per_bin_canvas'),
         config
     );
+
+    coverage_per_bin_breakpoints.addEventListener("change", () => update_coverage_per_bin_on_reference_plot());
 }
 
 function update_coverage_per_bin_on_reference_plot() {
@@ -643,14 +699,12 @@ function update_coverage_per_bin_on_reference_plot() {
             const name = file_info[0];
             const color = file_info[1][0];
 
-            const dataset_name = reference + "_coverage_per_bin";
-
-            let bin_size = get_dataset(name, "bin_size")[0];
-            let line_data = bin_data_to_line_data(get_dataset(name, dataset_name));
+            let bins = get_bins_in_region(name, reference, "_coverage_per_bin");
+            let line_data = bin_data_to_line_data(bins.bins);
             let decimated_data = decimate_bin_data_mean(line_data, 1000, 1000);
 
             let decimation_size = calculate_decimation_size(line_data, 1000, 1000);
-            plot_data.labels = generate_labels(decimated_data, bin_size * decimation_size);
+            plot_data.labels = generate_labels(decimated_data, bins.bin_size * decimation_size, bins.offset);
 
             let dataset = {
                 label: name,
@@ -663,6 +717,25 @@ function update_coverage_per_bin_on_reference_plot() {
             };
 
             plot_data.datasets.push(dataset);
+
+            if (coverage_per_bin_breakpoints.checked) {
+                let breakpoints_line_data = bin_data_to_line_data(get_bins_in_region(name, reference, "_breakpoints_per_bin").bins);
+                let decimated_breakpoints_data = decimate_bin_data_mean(breakpoints_line_data, 1000, 1000);
+
+                let breakpoints_dataset = {
+                    label: name + " Breakpoints",
+                    data: decimated_breakpoints_data,
+                    yAxisID: "breakpoints",
+                    backgroundColor: color,
+                    borderColor: color,
+                    borderDash: [4, 4],
+                    borderWidth: 1,
+                    radius: 1,
+                    fill: false,
+                };
+
+                plot_data.datasets.push(breakpoints_dataset);
+            }
         }
 
         coverage_per_bin_on_reference_plot.config.data = plot_data;
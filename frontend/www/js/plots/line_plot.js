@@ -22,11 +22,11 @@ export function bin_data_to_line_data(bin_data) {
     return line_data;
 }
 
-export function generate_labels(data, bin_size) {
+export function generate_labels(data, bin_size, offset = 0) {
     let labels = [];
 
     for (let i = 0; i < data.length; i++) {
-        labels.push(i * bin_size);
+        labels.push(offset + i * bin_size);
     }
 
     return labels;
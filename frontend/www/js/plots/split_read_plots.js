@@ -13,6 +13,9 @@ export function setup_split_read_plots() {
     setup_gap_lengths_file_plot();
     setup_complete_lengths_file_plot();
     setup_split_counts_file_plot();
+    setup_split_read_classes_file_plot();
+    setup_insert_sizes_file_plot();
+    setup_insert_sizes_per_reference_plot();
 }
 
 export function update_split_read_plots() {
@@ -21,6 +24,9 @@ export function update_split_read_plots() {
     update_gap_lengths_file_plot();
     update_complete_lengths_file_plot();
     update_split_counts_file_plot();
+    update_split_read_classes_file_plot();
+    update_insert_sizes_file_plot();
+    update_insert_sizes_per_reference_plot();
 }
 
 export function update_reference_dependent_split_read_plots() {
@@ -319,3 +325,285 @@ function update_split_counts_file_plot() {
         split_counts_file_plot.update();
     }
 }
+
+let split_read_classes_file_plot;
+const split_read_classes_file_logarithmic = document.getElementById("split_read_classes_file_logarithmic");
+const split_read_class_labels = ["Collinear", "Inter-Chromosomal Chimera", "Strand Switch", "Back-Splice", "Unclassified"];
+
+function setup_split_read_classes_file_plot() {
+    let data = {
+        labels: split_read_class_labels,
+        datasets: []
+    };
+
+    let config = {
+        type: 'bar',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Split Read Classes per File"
+                },
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Class',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Split Reads',
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false
+        }
+    };
+
+    split_read_classes_file_plot = new Chart(
+        document.getElementById('split_read_classes_file_canvas'),
+        config
+    );
+
+    split_read_classes_file_logarithmic.addEventListener("change", () => update_split_read_classes_file_plot());
+}
+
+function update_split_read_classes_file_plot() {
+    if (split_read_classes_file_plot) {
+        let plot_data = {
+            labels: split_read_class_labels,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = get_dataset(name,"split_read_classes_file");
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        const logarithmic = split_read_classes_file_logarithmic.checked;
+
+        if (logarithmic) {
+            split_read_classes_file_plot.config.options.scales.y.type="logarithmic";
+        }
+        else {
+            split_read_classes_file_plot.config.options.scales.y.type=undefined;
+        }
+
+        split_read_classes_file_plot.config.data = plot_data;
+        split_read_classes_file_plot.update();
+    }
+}
+
+let insert_sizes_file_plot;
+
+function setup_insert_sizes_file_plot() {
+    let data = {
+        labels: [""],
+        datasets: []
+    };
+
+    let config = {
+        type: 'boxplot',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Insert Sizes per File"
+                },
+                tooltip: {
+                    callbacks: {
+                        label: boxplot_tooltip
+                    }
+                },
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'File',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Insert Size',
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false
+        }
+    };
+
+    insert_sizes_file_plot = new Chart(
+        document.getElementById('insert_sizes_file_canvas'),
+        config
+    );
+}
+
+function update_insert_sizes_file_plot() {
+    if (insert_sizes_file_plot) {
+        let plot_data = {
+            labels: [""],
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = boxplot_from_separate_arrays(
+                get_dataset(name,"insert_sizes_file_min"),
+                get_dataset(name,"insert_sizes_file_q1"),
+                get_dataset(name,"insert_sizes_file_median"),
+                get_dataset(name,"insert_sizes_file_mean"),
+                get_dataset(name,"insert_sizes_file_mode"),
+                get_dataset(name,"insert_sizes_file_q3"),
+                get_dataset(name,"insert_sizes_file_max"),
+            );
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        insert_sizes_file_plot.config.data = plot_data;
+        insert_sizes_file_plot.update();
+    }
+}
+
+let insert_sizes_per_reference_plot;
+
+function setup_insert_sizes_per_reference_plot() {
+    let data = {
+        labels: reference_names,
+        datasets: []
+    };
+
+    let config = {
+        type: 'boxplot',
+        data: data,
+        options: {
+            plugins: {
+                title: {
+                    display: false,
+                    text: "Insert Sizes per Reference"
+                },
+                tooltip: {
+                    callbacks: {
+                        label: boxplot_tooltip
+                    }
+                },
+            },
+            locale: "de-DE",
+            responsive: true,
+            maintainAspectRatio: true,
+            scales: {
+                x: {
+                    title: {
+                        display: true,
+                        text: 'Reference',
+                    }
+                },
+                y: {
+                    title: {
+                        display: true,
+                        text: 'Insert Size',
+                    }
+                }
+            },
+            interaction: {
+                mode: 'index',
+                intersect: false
+            },
+            animation: false,
+            onClick: function (_, elements) {
+                linking_update_selected_reference(elements[0]);
+            }
+        }
+    };
+
+    insert_sizes_per_reference_plot = new Chart(
+        document.getElementById('insert_sizes_per_reference_canvas'),
+        config
+    );
+}
+
+function update_insert_sizes_per_reference_plot() {
+    if (insert_sizes_per_reference_plot) {
+        let plot_data = {
+            labels: reference_names,
+            datasets: []
+        };
+
+        let file_names = get_file_list();
+
+        for (const file_info of file_names) {
+            if (!file_info[2]) {continue;}
+
+            const name = file_info[0];
+            const color = file_info[1][0];
+
+            const data = boxplot_from_separate_arrays(
+                get_dataset(name,"insert_sizes_per_reference_min"),
+                get_dataset(name,"insert_sizes_per_reference_q1"),
+                get_dataset(name,"insert_sizes_per_reference_median"),
+                get_dataset(name,"insert_sizes_per_reference_mean"),
+                get_dataset(name,"insert_sizes_per_reference_mode"),
+                get_dataset(name,"insert_sizes_per_reference_q3"),
+                get_dataset(name,"insert_sizes_per_reference_max"),
+            );
+
+            let dataset = {
+                label: name,
+                data: data,
+                backgroundColor: color
+            };
+
+            plot_data.datasets.push(dataset);
+        }
+
+        insert_sizes_per_reference_plot.config.data = plot_data;
+        insert_sizes_per_reference_plot.update();
+    }
+}
\ No newline at end of file